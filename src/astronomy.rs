@@ -0,0 +1,510 @@
+// astronomy.rs - Offline sun and moon positions (low-precision Meeus series)
+//
+// Times are Julian Days in Universal Time; angles are degrees.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Julian Day of 1970-01-01 00:00 UT
+const UNIX_EPOCH_JD: f64 = 2440587.5;
+const J2000: f64 = 2451545.0;
+// Mean length of a synodic month in days
+pub const SYNODIC_MONTH: f64 = 29.530588853;
+
+/// Julian Day (UT) at 00:00 UT of a day number (days since 1970-01-01)
+#[inline]
+pub fn jd_from_days(days: i64) -> f64 {
+    days as f64 + UNIX_EPOCH_JD
+}
+
+/// Fractional day number (days since 1970-01-01) of a Julian Day
+#[inline]
+pub fn days_from_jd(jd: f64) -> f64 {
+    jd - UNIX_EPOCH_JD
+}
+
+/// Julian Day (UT) of the current moment
+pub fn jd_now() -> f64 {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0);
+    UNIX_EPOCH_JD + seconds / 86400.0
+}
+
+/// Reduce an angle to [0, 360)
+#[inline]
+pub fn normalize_degrees(angle: f64) -> f64 {
+    angle.rem_euclid(360.0)
+}
+
+/// Reduce an angle to [-180, 180)
+#[inline]
+fn normalize_signed(angle: f64) -> f64 {
+    (angle + 180.0).rem_euclid(360.0) - 180.0
+}
+
+#[inline]
+fn sin_deg(x: f64) -> f64 {
+    x.to_radians().sin()
+}
+
+#[inline]
+fn cos_deg(x: f64) -> f64 {
+    x.to_radians().cos()
+}
+
+/// TT - UT in days (polynomial fit, good for 2005-2050)
+fn delta_t(jd: f64) -> f64 {
+    let y = (jd - J2000) / 365.25;
+    (62.92 + 0.32217 * y + 0.005589 * y * y) / 86400.0
+}
+
+/// Universal Time of a Julian Ephemeris Day (Terrestrial Time)
+pub fn ut_from_tt(jde: f64) -> f64 {
+    jde - delta_t(jde)
+}
+
+/// Julian centuries of Terrestrial Time since J2000
+#[inline]
+fn centuries_tt(jd: f64) -> f64 {
+    (jd + delta_t(jd) - J2000) / 36525.0
+}
+
+/// Apparent geocentric longitude of the Sun (tropical)
+pub fn sun_longitude(jd: f64) -> f64 {
+    let t = centuries_tt(jd);
+    let l0 = 280.46646 + 36000.76983 * t + 0.0003032 * t * t;
+    let m = 357.52911 + 35999.05029 * t - 0.0001537 * t * t;
+    let c = (1.914602 - 0.004817 * t - 0.000014 * t * t) * sin_deg(m)
+        + (0.019993 - 0.000101 * t) * sin_deg(2.0 * m)
+        + 0.000289 * sin_deg(3.0 * m);
+    let omega = 125.04 - 1934.136 * t;
+    normalize_degrees(l0 + c - 0.00569 - 0.00478 * sin_deg(omega))
+}
+
+// Periodic terms for the Moon's longitude (Meeus, table 47.A):
+// multiples of D, M, M', F and the sine coefficient in 1e-6 degrees
+const MOON_LONGITUDE_TERMS: &[(i8, i8, i8, i8, i32)] = &[
+    (0, 0, 1, 0, 6288774),
+    (2, 0, -1, 0, 1274027),
+    (2, 0, 0, 0, 658314),
+    (0, 0, 2, 0, 213618),
+    (0, 1, 0, 0, -185116),
+    (0, 0, 0, 2, -114332),
+    (2, 0, -2, 0, 58793),
+    (2, -1, -1, 0, 57066),
+    (2, 0, 1, 0, 53322),
+    (2, -1, 0, 0, 45758),
+    (0, 1, -1, 0, -40923),
+    (1, 0, 0, 0, -34720),
+    (0, 1, 1, 0, -30383),
+    (2, 0, 0, -2, 15327),
+    (0, 0, 1, 2, -12528),
+    (0, 0, 1, -2, 10980),
+    (4, 0, -1, 0, 10675),
+    (0, 0, 3, 0, 10034),
+    (4, 0, -2, 0, 8548),
+    (2, 1, -1, 0, -7888),
+    (2, 1, 0, 0, -6766),
+    (1, 0, -1, 0, -5163),
+    (1, 1, 0, 0, 4987),
+    (2, -1, 1, 0, 4036),
+    (2, 0, 2, 0, 3994),
+    (4, 0, 0, 0, 3861),
+    (2, 0, -3, 0, 3665),
+    (0, 1, -2, 0, -2689),
+    (2, 0, -1, 2, -2602),
+    (2, -1, -2, 0, 2390),
+    (1, 0, 1, 0, -2348),
+    (2, -2, 0, 0, 2236),
+    (0, 1, 2, 0, -2120),
+    (0, 2, 0, 0, -2069),
+    (2, -2, -1, 0, 2048),
+    (2, 0, 1, -2, -1773),
+    (2, 0, 0, 2, -1595),
+    (4, -1, -1, 0, 1215),
+    (0, 0, 2, 2, -1110),
+    (3, 0, -1, 0, -892),
+    (2, 1, 1, 0, -810),
+    (4, -1, -2, 0, 759),
+    (0, 2, -1, 0, -713),
+    (2, 2, -1, 0, -700),
+    (2, 1, -2, 0, 691),
+    (2, -1, 0, -2, 596),
+    (4, 0, 1, 0, 549),
+    (0, 0, 4, 0, 537),
+    (4, -1, 0, 0, 520),
+    (1, 0, -2, 0, -487),
+    (2, 1, 0, -2, -399),
+    (0, 0, 2, -2, -381),
+    (1, 1, 1, 0, 351),
+    (3, 0, -2, 0, -340),
+    (4, 0, -3, 0, 330),
+    (2, -1, 2, 0, 327),
+    (0, 2, 1, 0, -323),
+    (1, 1, -1, 0, 299),
+    (2, 0, 3, 0, 294),
+];
+
+/// Fundamental arguments of the lunar theory: L', D, M, M', F and E
+fn moon_arguments(t: f64) -> (f64, f64, f64, f64, f64, f64) {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let t4 = t3 * t;
+
+    let l = 218.3164477 + 481267.88123421 * t - 0.0015786 * t2 + t3 / 538841.0 - t4 / 65194000.0;
+    let d = 297.8501921 + 445267.1114034 * t - 0.0018819 * t2 + t3 / 545868.0 - t4 / 113065000.0;
+    let m = 357.5291092 + 35999.0502909 * t - 0.0001536 * t2 + t3 / 24490000.0;
+    let mp = 134.9633964 + 477198.8675055 * t + 0.0087414 * t2 + t3 / 69699.0 - t4 / 14712000.0;
+    let f = 93.2720950 + 483202.0175233 * t - 0.0036539 * t2 - t3 / 3526000.0 + t4 / 863310000.0;
+    let e = 1.0 - 0.002516 * t - 0.0000074 * t2;
+    (l, d, m, mp, f, e)
+}
+
+/// Sum a table of lunar periodic terms with `trig` (sine or cosine)
+fn sum_moon_terms(
+    terms: &[(i8, i8, i8, i8, i32)],
+    trig: fn(f64) -> f64,
+    (d, m, mp, f, e): (f64, f64, f64, f64, f64),
+) -> f64 {
+    let mut sum = 0.0;
+    for &(cd, cm, cmp, cf, coeff) in terms {
+        let arg = cd as f64 * d + cm as f64 * m + cmp as f64 * mp + cf as f64 * f;
+        let eccentricity = match cm.abs() {
+            1 => e,
+            2 => e * e,
+            _ => 1.0,
+        };
+        sum += coeff as f64 * eccentricity * trig(arg);
+    }
+    sum
+}
+
+/// Apparent geocentric longitude of the Moon (tropical)
+pub fn moon_longitude(jd: f64) -> f64 {
+    let t = centuries_tt(jd);
+    let (l, d, m, mp, f, e) = moon_arguments(t);
+    let mut sum = sum_moon_terms(MOON_LONGITUDE_TERMS, sin_deg, (d, m, mp, f, e));
+
+    let a1 = 119.75 + 131.849 * t;
+    let a2 = 53.09 + 479264.290 * t;
+    sum += 3958.0 * sin_deg(a1) + 1962.0 * sin_deg(l - f) + 318.0 * sin_deg(a2);
+
+    // Nutation in longitude (main term), to match the Sun's apparent longitude
+    let omega = 125.04 - 1934.136 * t;
+    normalize_degrees(l + sum / 1_000_000.0 - 0.00478 * sin_deg(omega))
+}
+
+// Largest periodic terms for the Moon's latitude (Meeus, table 47.B)
+const MOON_LATITUDE_TERMS: &[(i8, i8, i8, i8, i32)] = &[
+    (0, 0, 0, 1, 5128122),
+    (0, 0, 1, 1, 280602),
+    (0, 0, 1, -1, 277693),
+    (2, 0, 0, -1, 173237),
+    (2, 0, -1, 1, 55413),
+    (2, 0, -1, -1, 46271),
+    (2, 0, 0, 1, 32573),
+    (0, 0, 2, 1, 17198),
+    (2, 0, 1, -1, 9266),
+    (0, 0, 2, -1, 8822),
+    (2, -1, 0, -1, 8216),
+    (2, 0, -2, -1, 4324),
+    (2, 0, 1, 1, 4200),
+    (2, 1, 0, -1, -3359),
+    (2, -1, -1, 1, 2463),
+    (2, -1, 0, 1, 2211),
+    (2, -1, -1, -1, 2065),
+    (0, 1, -1, -1, -1870),
+    (4, 0, -1, -1, 1828),
+    (0, 1, 0, 1, -1794),
+    (0, 0, 0, 3, -1749),
+    (0, 1, -1, 1, -1565),
+    (1, 0, 0, 1, -1491),
+    (0, 1, 1, 1, -1475),
+    (0, 1, 1, -1, -1410),
+    (0, 1, 0, -1, -1344),
+    (1, 0, 0, -1, -1335),
+    (0, 0, 3, 1, 1107),
+    (4, 0, 0, -1, 1021),
+    (4, 0, -1, 1, 833),
+];
+
+/// Geocentric ecliptic latitude of the Moon
+pub fn moon_latitude(jd: f64) -> f64 {
+    let t = centuries_tt(jd);
+    let (l, d, m, mp, f, e) = moon_arguments(t);
+    let mut sum = sum_moon_terms(MOON_LATITUDE_TERMS, sin_deg, (d, m, mp, f, e));
+
+    let a1 = 119.75 + 131.849 * t;
+    let a3 = 313.45 + 481266.484 * t;
+    sum += -2235.0 * sin_deg(l)
+        + 382.0 * sin_deg(a3)
+        + 175.0 * sin_deg(a1 - f)
+        + 175.0 * sin_deg(a1 + f)
+        + 127.0 * sin_deg(l - mp)
+        - 115.0 * sin_deg(l + mp);
+    sum / 1_000_000.0
+}
+
+// Largest periodic terms for the Earth-Moon distance (Meeus, table 47.A):
+// cosine coefficients in metres
+const MOON_DISTANCE_TERMS: &[(i8, i8, i8, i8, i32)] = &[
+    (0, 0, 1, 0, -20905355),
+    (2, 0, -1, 0, -3699111),
+    (2, 0, 0, 0, -2955968),
+    (0, 0, 2, 0, -569925),
+    (0, 1, 0, 0, 48888),
+    (0, 0, 0, 2, -3149),
+    (2, 0, -2, 0, 246158),
+    (2, -1, -1, 0, -152138),
+    (2, 0, 1, 0, -170733),
+    (2, -1, 0, 0, -204586),
+    (0, 1, -1, 0, -129620),
+    (1, 0, 0, 0, 108743),
+    (0, 1, 1, 0, 104755),
+    (2, 0, 0, -2, 10321),
+    (0, 0, 1, -2, 79661),
+    (4, 0, -1, 0, -34782),
+    (0, 0, 3, 0, -23210),
+    (4, 0, -2, 0, -21636),
+    (2, 1, -1, 0, 24208),
+    (2, 1, 0, 0, 30824),
+    (1, 0, -1, 0, -8379),
+    (1, 1, 0, 0, -16675),
+    (2, -1, 1, 0, -12831),
+    (2, 0, 2, 0, -10445),
+    (4, 0, 0, 0, -11650),
+    (2, 0, -3, 0, 14403),
+    (0, 1, -2, 0, -7003),
+    (2, -1, -2, 0, 10056),
+    (1, 0, 1, 0, 6322),
+    (2, -2, 0, 0, -9884),
+];
+
+/// Distance between the centres of the Earth and the Moon in kilometres
+pub fn moon_distance(jd: f64) -> f64 {
+    let (_, d, m, mp, f, e) = moon_arguments(centuries_tt(jd));
+    385000.56 + sum_moon_terms(MOON_DISTANCE_TERMS, cos_deg, (d, m, mp, f, e)) / 1000.0
+}
+
+/// Moon's longitude minus the Sun's, in [0, 360)
+pub fn lunar_elongation(jd: f64) -> f64 {
+    normalize_degrees(moon_longitude(jd) - sun_longitude(jd))
+}
+
+/// Lahiri (Chitrapaksha) ayanamsa
+pub fn lahiri_ayanamsa(jd: f64) -> f64 {
+    23.85306 + 0.0139690 * (jd - J2000) / 365.25
+}
+
+/// Sidereal (nirayana) longitude of the Sun
+pub fn sidereal_sun_longitude(jd: f64) -> f64 {
+    normalize_degrees(sun_longitude(jd) - lahiri_ayanamsa(jd))
+}
+
+/// Sidereal (nirayana) longitude of the Moon
+#[cfg(windows)]
+pub fn sidereal_moon_longitude(jd: f64) -> f64 {
+    normalize_degrees(moon_longitude(jd) - lahiri_ayanamsa(jd))
+}
+
+/// The moment nearest to `jd_guess` when `angle` equals `target` degrees,
+/// for an angle growing by about `rate` degrees a day
+pub fn find_angle(angle: impl Fn(f64) -> f64, target: f64, rate: f64, jd_guess: f64) -> f64 {
+    let mut jd = jd_guess;
+    for _ in 0..20 {
+        let error = normalize_signed(angle(jd) - target);
+        let step = error / rate;
+        jd -= step;
+        if step.abs() < 1e-6 {
+            break;
+        }
+    }
+    jd
+}
+
+/// The moment nearest to `jd_guess` when the elongation equals `target` degrees
+pub fn find_elongation(target: f64, jd_guess: f64) -> f64 {
+    // The elongation grows by ~12.19 degrees a day
+    find_angle(lunar_elongation, target, 360.0 / SYNODIC_MONTH, jd_guess)
+}
+
+/// The last new moon at or before `jd`
+pub fn new_moon_before(jd: f64) -> f64 {
+    let guess = jd - lunar_elongation(jd) / 360.0 * SYNODIC_MONTH;
+    let new_moon = find_elongation(0.0, guess);
+    if new_moon > jd {
+        find_elongation(0.0, new_moon - SYNODIC_MONTH)
+    } else {
+        new_moon
+    }
+}
+
+/// Greenwich mean sidereal time in degrees
+pub fn sidereal_time(jd: f64) -> f64 {
+    let t = (jd - J2000) / 36525.0;
+    normalize_degrees(280.46061837 + 360.98564736629 * (jd - J2000) + 0.000387933 * t * t)
+}
+
+/// Mean obliquity of the ecliptic with the main nutation term
+fn obliquity(t: f64) -> f64 {
+    let omega = 125.04 - 1934.136 * t;
+    23.439291 - 0.0130042 * t + 0.00256 * cos_deg(omega)
+}
+
+/// Right ascension and declination of a point on the ecliptic
+fn ecliptic_to_equatorial(longitude: f64, latitude: f64, obliquity: f64) -> (f64, f64) {
+    let ra = (sin_deg(longitude) * cos_deg(obliquity)
+        - latitude.to_radians().tan() * sin_deg(obliquity))
+    .atan2(cos_deg(longitude))
+    .to_degrees();
+    let dec = (sin_deg(latitude) * cos_deg(obliquity)
+        + cos_deg(latitude) * sin_deg(obliquity) * sin_deg(longitude))
+    .asin()
+    .to_degrees();
+    (normalize_degrees(ra), dec)
+}
+
+/// Apparent right ascension and declination of the Sun
+pub fn sun_equatorial(jd: f64) -> (f64, f64) {
+    ecliptic_to_equatorial(sun_longitude(jd), 0.0, obliquity(centuries_tt(jd)))
+}
+
+/// Apparent geocentric right ascension and declination of the Moon
+pub fn moon_equatorial(jd: f64) -> (f64, f64) {
+    ecliptic_to_equatorial(
+        moon_longitude(jd),
+        moon_latitude(jd),
+        obliquity(centuries_tt(jd)),
+    )
+}
+
+/// Altitude above the horizon of a body at `ra`/`dec`
+fn altitude(jd: f64, latitude: f64, longitude: f64, ra: f64, dec: f64) -> f64 {
+    let hour_angle = sidereal_time(jd) + longitude - ra;
+    (sin_deg(latitude) * sin_deg(dec) + cos_deg(latitude) * cos_deg(dec) * cos_deg(hour_angle))
+        .asin()
+        .to_degrees()
+}
+
+/// Geocentric altitude of the Sun's centre
+pub fn sun_altitude(jd: f64, latitude: f64, longitude: f64) -> f64 {
+    let (ra, dec) = sun_equatorial(jd);
+    altitude(jd, latitude, longitude, ra, dec)
+}
+
+/// Declination of the Sun
+#[cfg(windows)]
+pub fn sun_declination(jd: f64) -> f64 {
+    sun_equatorial(jd).1
+}
+
+/// When the Sun's centre reaches `altitude` degrees in the morning (`rising`)
+/// or evening of the local day starting at `day_start` (Julian Day UT of
+/// local midnight). Returns None if it never gets there that day.
+pub fn sun_altitude_crossing(
+    day_start: f64,
+    latitude: f64,
+    longitude: f64,
+    altitude: f64,
+    rising: bool,
+) -> Option<f64> {
+    // Start from 06:00 or 18:00 local time
+    let (mut jd, side) = if rising {
+        (day_start + 0.25, -1.0)
+    } else {
+        (day_start + 0.75, 1.0)
+    };
+    for _ in 0..10 {
+        let (ra, dec) = sun_equatorial(jd);
+        let cos_h0 = (sin_deg(altitude) - sin_deg(latitude) * sin_deg(dec))
+            / (cos_deg(latitude) * cos_deg(dec));
+        if !(-1.0..=1.0).contains(&cos_h0) {
+            return None;
+        }
+        let h0 = cos_h0.acos().to_degrees();
+        let hour_angle = normalize_signed(sidereal_time(jd) + longitude - ra);
+        let step = normalize_signed(side * h0 - hour_angle) / 360.985647;
+        jd += step;
+        if step.abs() < 1e-6 {
+            break;
+        }
+    }
+    Some(jd)
+}
+
+/// Solar noon (the Sun on the meridian) on the local day starting at `day_start`
+#[cfg(windows)]
+pub fn solar_noon(day_start: f64, longitude: f64) -> f64 {
+    let mut jd = day_start + 0.5;
+    for _ in 0..10 {
+        let (ra, _) = sun_equatorial(jd);
+        let hour_angle = normalize_signed(sidereal_time(jd) + longitude - ra);
+        let step = hour_angle / 360.985647;
+        jd -= step;
+        if step.abs() < 1e-6 {
+            break;
+        }
+    }
+    jd
+}
+
+// Altitude of the Sun's centre at sunrise/sunset: upper limb on the horizon, with refraction
+pub const SUNRISE_ALTITUDE: f64 = -0.833;
+
+/// Sunrise on the local day starting at `day_start`
+pub fn sunrise(day_start: f64, latitude: f64, longitude: f64) -> Option<f64> {
+    sun_altitude_crossing(day_start, latitude, longitude, SUNRISE_ALTITUDE, true)
+}
+
+/// Sunset on the local day starting at `day_start`
+#[cfg(windows)]
+pub fn sunset(day_start: f64, latitude: f64, longitude: f64) -> Option<f64> {
+    sun_altitude_crossing(day_start, latitude, longitude, SUNRISE_ALTITUDE, false)
+}
+
+/// Geocentric altitude of the Moon's centre
+pub fn moon_altitude(jd: f64, latitude: f64, longitude: f64) -> f64 {
+    let (ra, dec) = moon_equatorial(jd);
+    altitude(jd, latitude, longitude, ra, dec)
+}
+
+// Geocentric altitude of the Moon's centre at moonrise/moonset: upper limb
+// on the horizon, with mean parallax, semidiameter and refraction
+pub const MOONRISE_ALTITUDE: f64 = 0.125;
+
+/// Moonrise and moonset on the local day starting at `day_start`.
+/// Either can be missing: the Moon rises about 50 minutes later each day.
+#[cfg(windows)]
+pub fn moon_rise_set(day_start: f64, latitude: f64, longitude: f64) -> (Option<f64>, Option<f64>) {
+    const STEPS: i32 = 48;
+    let height = |jd: f64| moon_altitude(jd, latitude, longitude) - MOONRISE_ALTITUDE;
+
+    let mut rise = None;
+    let mut set = None;
+    let mut previous = (day_start, height(day_start));
+    for i in 1..=STEPS {
+        let jd = day_start + i as f64 / STEPS as f64;
+        let current = (jd, height(jd));
+        if (previous.1 < 0.0) != (current.1 < 0.0) {
+            // Bisect the crossing down to a second
+            let (mut low, mut high) = (previous.0, current.0);
+            for _ in 0..20 {
+                let mid = (low + high) / 2.0;
+                if (height(mid) < 0.0) == (previous.1 < 0.0) {
+                    low = mid;
+                } else {
+                    high = mid;
+                }
+            }
+            let crossing = (low + high) / 2.0;
+            if previous.1 < 0.0 {
+                rise.get_or_insert(crossing);
+            } else {
+                set.get_or_insert(crossing);
+            }
+        }
+        previous = current;
+    }
+    (rise, set)
+}
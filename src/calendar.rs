@@ -17,6 +17,49 @@ pub fn to_bangla_number(num: i32) -> String {
         .collect()
 }
 
+/// Parse a whole number written in Bangla or English digits
+pub fn parse_bangla_number(text: &str) -> Option<i32> {
+    let ascii: String = text
+        .trim()
+        .chars()
+        .map(|c| match BANGLA_DIGITS.iter().position(|&d| d == c) {
+            Some(digit) => char::from(b'0' + digit as u8),
+            None => c,
+        })
+        .collect();
+    ascii.parse().ok()
+}
+
+/// Clock time on a 12-hour dial: ৬:৩৮
+#[cfg(windows)]
+pub fn format_clock_time(minute_of_day: i32) -> String {
+    let hour12 = match (minute_of_day / 60) % 12 {
+        0 => 12,
+        h => h,
+    };
+    let minute = minute_of_day % 60;
+    format!(
+        "{}:{}{}",
+        to_bangla_number(hour12),
+        BANGLA_DIGITS[(minute / 10) as usize],
+        BANGLA_DIGITS[(minute % 10) as usize]
+    )
+}
+
+/// Clock time with the part of the day: রাত ৮:১২, ভোর ৫:০৫
+#[cfg(windows)]
+pub fn format_bangla_time(minute_of_day: i32) -> String {
+    let period = match minute_of_day / 60 {
+        4..=5 => "ভোর",
+        6..=11 => "সকাল",
+        12..=14 => "দুপুর",
+        15..=17 => "বিকাল",
+        18..=19 => "সন্ধ্যা",
+        _ => "রাত",
+    };
+    format!("{} {}", period, format_clock_time(minute_of_day))
+}
+
 /// Bangla date structure
 #[derive(Debug, Clone)]
 pub struct BanglaDate {
@@ -85,6 +128,92 @@ fn is_gregorian_leap_year(year: i32) -> bool {
     (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
 }
 
+/// Get the number of days in a Bangla month
+pub fn get_bangla_month_days(month: i32, year: i32) -> i32 {
+    // Bangladesh calendar: first 5 months have 31 days, rest have 30
+    // Falgun (month 10) has 31 days in leap years
+    if month < 5 {
+        31
+    } else if month == 10 {
+        // Falgun - check leap year
+        if is_gregorian_leap_year(year + 594) {
+            31
+        } else {
+            30
+        }
+    } else {
+        30
+    }
+}
+
+/// Number of days since 1970-01-01 for a Gregorian date
+pub fn days_from_civil(year: i32, month: i32, day: i32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year } as i64;
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Gregorian (year, month, day) for a number of days since 1970-01-01
+pub fn civil_from_days(days: i64) -> (i32, i32, i32) {
+    let z = days + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year as i32, month as i32, day as i32)
+}
+
+/// Convert a Bangla date to Gregorian (year, month, day)
+pub fn gregorian_from_bangla(year: i32, month: i32, day: i32) -> (i32, i32, i32) {
+    let mut days = days_from_civil(year + 593, 4, 14);
+    for m in 0..month {
+        days += get_bangla_month_days(m, year) as i64;
+    }
+    civil_from_days(days + day as i64 - 1)
+}
+
+/// The Gregorian months a range of days spans: এপ্রিল–মে ২০২৫, or
+/// ডিসেম্বর ২০২৫–জানুয়ারি ২০২৬
+pub fn gregorian_span(first_days: i64, last_days: i64) -> String {
+    let (first_year, first_month, _) = civil_from_days(first_days);
+    let (last_year, last_month, _) = civil_from_days(last_days);
+    let first_name = GREGORIAN_MONTHS[(first_month - 1) as usize];
+    let last_name = GREGORIAN_MONTHS[(last_month - 1) as usize];
+    if (first_year, first_month) == (last_year, last_month) {
+        format!("{} {}", first_name, to_bangla_number(first_year))
+    } else if first_year == last_year {
+        format!(
+            "{}–{} {}",
+            first_name,
+            last_name,
+            to_bangla_number(last_year)
+        )
+    } else {
+        format!(
+            "{} {}–{} {}",
+            first_name,
+            to_bangla_number(first_year),
+            last_name,
+            to_bangla_number(last_year)
+        )
+    }
+}
+
+/// First and last day numbers of a Bangla year (১ বৈশাখ to the end of চৈত্র)
+pub fn bangla_year_days(year: i32) -> (i64, i64) {
+    let first = days_from_civil(year + 593, 4, 14);
+    let next = days_from_civil(year + 594, 4, 14);
+    (first, next - 1)
+}
+
 fn calculate_bangla_date_bd(
     gregorian_year: i32,
     gregorian_month: i32,
@@ -103,7 +232,8 @@ fn calculate_bangla_date_bd(
         }
     }
 
-    let boishakh_start = if prev_leap { 105 } else { 104 };
+    // 1st Boishakh is always April 14
+    let boishakh_start = if is_leap { 105 } else { 104 };
 
     let bangla_year;
     let day_in_bangla_year;
@@ -114,7 +244,8 @@ fn calculate_bangla_date_bd(
     } else {
         bangla_year = gregorian_year - 594;
         let prev_year_days = if prev_leap { 366 } else { 365 };
-        day_in_bangla_year = (prev_year_days - boishakh_start + 1) + day_of_year;
+        let prev_boishakh_start = if prev_leap { 105 } else { 104 };
+        day_in_bangla_year = (prev_year_days - prev_boishakh_start + 1) + day_of_year;
     }
 
     let mut remaining_days = day_in_bangla_year;
@@ -146,6 +277,126 @@ fn calculate_bangla_date_bd(
     }
 }
 
+/// Bangla date of a day number (days since 1970-01-01)
+pub fn bangla_date_from_days(days: i64) -> BanglaDate {
+    let (year, month, day) = civil_from_days(days);
+    calculate_bangla_date_bd(year, month, day)
+}
+
+// English month names, matched by their first three letters or more
+#[cfg(windows)]
+const ENGLISH_MONTHS: &[&str] = &[
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+// Words a typed date may carry that do not change it
+#[cfg(windows)]
+const DATE_FILLER_WORDS: &[&str] = &["বঙ্গাব্দ", "খ্রিস্টাব্দ", "ইং", "সাল", "তারিখ"];
+
+/// A month named in a typed date, in its calendar (0-11)
+#[cfg(windows)]
+enum NamedMonth {
+    Bangla(i32),
+    Gregorian(i32),
+}
+
+/// A Bangla or Gregorian month name: বৈশাখ, ডিসেম্বর, ডিসে, December, Dec
+#[cfg(windows)]
+fn parse_month_name(word: &str) -> Option<NamedMonth> {
+    if let Some(month) = BANGLA_MONTHS.iter().position(|&name| name == word) {
+        return Some(NamedMonth::Bangla(month as i32));
+    }
+    let lower = word.to_lowercase();
+    GREGORIAN_MONTHS
+        .iter()
+        .position(|&name| name == word)
+        .or_else(|| GREGORIAN_MONTHS_SHORT.iter().position(|&name| name == word))
+        .or_else(|| {
+            ENGLISH_MONTHS
+                .iter()
+                .position(|name| lower.len() >= 3 && name.starts_with(&lower))
+        })
+        .map(|month| NamedMonth::Gregorian(month as i32))
+}
+
+/// A day of the month, also with its ordinal suffix: ১৫, ১৫ই, ১লা, 15th
+#[cfg(windows)]
+fn parse_day_number(word: &str) -> Option<i32> {
+    if let Some(day) = BANGLA_ORDINALS.iter().position(|&ordinal| ordinal == word) {
+        return Some(day as i32);
+    }
+    let trimmed = ["st", "nd", "rd", "th"]
+        .iter()
+        .find_map(|suffix| word.strip_suffix(suffix))
+        .unwrap_or(word);
+    parse_bangla_number(trimmed)
+}
+
+/// Parse a date typed in Bangla or English, as a day number:
+/// ১৫ পৌষ ১৪৩২, ১লা বৈশাখ, 30 December 2025, Dec 30, ৩০/১২/২০২৫ or
+/// 2025-12-30. A Bangla month name makes it a Bangla date; numbers alone are
+/// Gregorian, day first unless the year comes first. Without a year, the
+/// year (Bangla or Gregorian) of `today` is meant.
+#[cfg(windows)]
+pub fn parse_date(text: &str, today: i64) -> Option<i64> {
+    let words: Vec<&str> = text
+        .split(|c: char| c.is_whitespace() || matches!(c, ',' | '/' | '-' | '.'))
+        .filter(|word| !word.is_empty() && !DATE_FILLER_WORDS.contains(word))
+        .collect();
+
+    let mut month = None;
+    let mut numbers = Vec::new();
+    for word in words {
+        if let Some(named) = parse_month_name(word)
+            && month.is_none()
+        {
+            month = Some(named);
+        } else {
+            numbers.push(parse_day_number(word)?);
+        }
+    }
+
+    let (day, month, year) = match (month, numbers.as_slice()) {
+        (Some(month), &[day]) => (day, month, None),
+        // The year is the number that cannot be a day
+        (Some(month), &[first, second]) if first > 31 => (second, month, Some(first)),
+        (Some(month), &[first, second]) => (first, month, Some(second)),
+        (None, &[year, month, day]) if year > 31 => {
+            (day, NamedMonth::Gregorian(month - 1), Some(year))
+        }
+        (None, &[day, month, year]) => (day, NamedMonth::Gregorian(month - 1), Some(year)),
+        (None, &[day, month]) => (day, NamedMonth::Gregorian(month - 1), None),
+        _ => return None,
+    };
+
+    match month {
+        NamedMonth::Bangla(month) => {
+            let year = year.unwrap_or_else(|| bangla_date_from_days(today).year);
+            if day < 1 || day > get_bangla_month_days(month, year) {
+                return None;
+            }
+            let (g_year, g_month, g_day) = gregorian_from_bangla(year, month, day);
+            Some(days_from_civil(g_year, g_month, g_day))
+        }
+        NamedMonth::Gregorian(month) => {
+            let year = year.unwrap_or_else(|| civil_from_days(today).0);
+            let days = days_from_civil(year, month + 1, day);
+            (civil_from_days(days) == (year, month + 1, day)).then_some(days)
+        }
+    }
+}
+
 fn calculate_weekday(year: i32, month: i32, day: i32) -> i32 {
     let mut y = year;
     let mut m = month;
@@ -162,51 +413,61 @@ fn calculate_weekday(year: i32, month: i32, day: i32) -> i32 {
     (h + 6) % 7
 }
 
-pub fn get_current_bangla_date() -> BanglaDate {
-    let now = SystemTime::now()
+// The Bangla date changes at 05:00 Bangladesh time (UTC+6), an hour after
+// midnight UTC
+const DAY_CHANGE_OFFSET_SECONDS: i64 = 3600;
+
+fn unix_seconds() -> i64 {
+    SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap()
-        .as_secs() as i64;
-
-    let bd_timestamp = now + (6 * 3600);
-    let adjusted_timestamp = bd_timestamp - (5 * 3600);
-    let (year, month, day) = timestamp_to_gregorian(adjusted_timestamp);
+        .as_secs() as i64
+}
 
-    calculate_bangla_date_bd(year, month, day)
+/// The day number (days since 1970-01-01) at a Unix time
+fn days_at(seconds: i64) -> i64 {
+    (seconds + DAY_CHANGE_OFFSET_SECONDS).div_euclid(86400)
 }
 
-fn timestamp_to_gregorian(timestamp: i64) -> (i32, i32, i32) {
-    let mut days = (timestamp / 86400) as i32;
-    let mut year = 1970;
+/// Today's day number, shared by the widget, the punjika, the exporters, the
+/// status command and the feed
+pub fn current_days() -> i64 {
+    days_at(unix_seconds())
+}
 
-    loop {
-        let days_in_year = if is_gregorian_leap_year(year) {
-            366
-        } else {
-            365
-        };
-        if days < days_in_year {
-            break;
-        }
-        days -= days_in_year;
-        year += 1;
-    }
+/// Seconds until current_days() moves on to the next day
+pub fn seconds_until_next_day() -> i64 {
+    86400 - (unix_seconds() + DAY_CHANGE_OFFSET_SECONDS).rem_euclid(86400)
+}
 
-    let month_days = if is_gregorian_leap_year(year) {
-        [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-    } else {
-        [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-    };
+pub fn get_current_bangla_date() -> BanglaDate {
+    bangla_date_from_days(current_days())
+}
 
-    let mut month = 1;
-    for (i, &md) in month_days.iter().enumerate() {
-        if days < md {
-            month = (i + 1) as i32;
-            break;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn date_changes_at_five_in_the_morning() {
+        // 2025-10-19 23:00 UTC is 05:00 on the 20th in Bangladesh
+        let change = days_from_civil(2025, 10, 19) * 86400 + 23 * 3600;
+        assert_eq!(days_at(change - 1), days_from_civil(2025, 10, 19));
+        assert_eq!(days_at(change), days_from_civil(2025, 10, 20));
+        // Local midnight (18:00 UTC) still shows the previous day
+        assert_eq!(days_at(change - 5 * 3600), days_from_civil(2025, 10, 19));
+    }
+    #[test]
+    fn boishakh_always_starts_on_april_14() {
+        for year in [2023, 2024, 2025, 2028] {
+            let date = calculate_bangla_date_bd(year, 4, 14);
+            assert_eq!((date.year, date.month, date.day), (year - 593, 0, 1));
+            let eve = calculate_bangla_date_bd(year, 4, 13);
+            assert_eq!((eve.year, eve.month, eve.day), (year - 594, 11, 30));
+            assert_eq!(gregorian_from_bangla(year - 593, 0, 1), (year, 4, 14));
         }
-        days -= md;
+        // Falgun of a leap year has 31 days
+        let date = calculate_bangla_date_bd(2024, 3, 14);
+        assert_eq!((date.month, date.day), (10, 31));
     }
-
-    let day = days + 1;
-    (year, month, day)
 }
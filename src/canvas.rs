@@ -0,0 +1,579 @@
+// canvas.rs - Drawing without GDI, to SVG or PNG
+//
+// A small set of primitives (filled and rounded rectangles, lines, ellipses
+// and shaped Ekush text) with two backends: SVG markup, with each glyph's
+// outline defined once and reused, and a PNG rasterized with tiny-skia.
+// Coordinates are pixels from the top left, like a GDI client area.
+
+use crate::shaping::{ShapedText, ekush_face, shape_text};
+use rustybuzz::ttf_parser::{GlyphId, OutlineBuilder};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use tiny_skia::{FillRule, Paint, PathBuilder, Pixmap, Stroke, Transform};
+
+/// A rectangle in pixels, as in a GDI RECT
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl Rect {
+    pub fn new(left: f32, top: f32, right: f32, bottom: f32) -> Rect {
+        Rect {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    pub fn width(&self) -> f32 {
+        self.right - self.left
+    }
+
+    pub fn height(&self) -> f32 {
+        self.bottom - self.top
+    }
+}
+
+/// Horizontal placement of text in its rectangle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// An Ekush font as GDI would create it: `height` is the character cell
+/// height in pixels (CreateFontW's positive height)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextFont {
+    pub height: f32,
+    pub bold: bool,
+}
+
+impl TextFont {
+    /// Ekush's character cell: the Windows ascender and descender, in font
+    /// units (the descender is negative)
+    fn cell_metrics() -> (f32, f32) {
+        let face = ekush_face();
+        let (ascender, descender) = face
+            .tables()
+            .os2
+            .map(|os2| (os2.windows_ascender(), os2.windows_descender()))
+            .unwrap_or((face.ascender(), face.descender()));
+        (ascender as f32, descender as f32)
+    }
+
+    /// Pixels per em
+    pub fn em_size(&self) -> f32 {
+        let (ascender, descender) = TextFont::cell_metrics();
+        self.height * ekush_face().units_per_em() as f32 / (ascender - descender)
+    }
+
+    /// Pixels from the top of the cell to the baseline
+    pub fn ascent(&self) -> f32 {
+        let (ascender, descender) = TextFont::cell_metrics();
+        self.height * ascender / (ascender - descender)
+    }
+
+    /// Width of a line of text in pixels
+    pub fn measure(&self, text: &str) -> f32 {
+        shape_text(text).width * self.em_size()
+    }
+}
+
+/// The red, green and blue of a COLORREF (0x00BBGGRR)
+pub fn color_rgb(color: u32) -> (u8, u8, u8) {
+    (
+        (color & 0xFF) as u8,
+        ((color >> 8) & 0xFF) as u8,
+        ((color >> 16) & 0xFF) as u8,
+    )
+}
+
+/// Outline pen width for synthesized bold, in ems
+const BOLD_STROKE: f32 = 0.035;
+
+/// A drawing surface
+pub trait Canvas {
+    /// Fill a rectangle, with corners rounded by `radius` pixels
+    fn fill_rect(&mut self, rect: Rect, radius: f32, color: u32);
+
+    /// Outline a rectangle with a one-pixel pen, corners rounded by `radius`
+    fn stroke_rect(&mut self, rect: Rect, radius: f32, color: u32);
+
+    /// A one-pixel line
+    fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, color: u32);
+
+    /// An ellipse with a one-pixel outline, filled unless `fill` is None
+    fn ellipse(&mut self, rect: Rect, fill: Option<u32>, outline: u32);
+
+    /// Draw one glyph with its origin at (x, baseline), `size` pixels per em
+    fn glyph(&mut self, id: u16, x: f32, baseline: f32, size: f32, color: u32, bold: bool);
+
+    /// Draw shaped text starting at (x, baseline)
+    fn shaped_text(
+        &mut self,
+        text: &ShapedText,
+        x: f32,
+        baseline: f32,
+        size: f32,
+        color: u32,
+        bold: bool,
+    ) {
+        for glyph in &text.glyphs {
+            self.glyph(
+                glyph.id,
+                x + glyph.x * size,
+                baseline - glyph.y * size,
+                size,
+                color,
+                bold,
+            );
+        }
+    }
+
+    /// Draw a line of text in a rectangle like DrawTextW with DT_SINGLELINE:
+    /// aligned horizontally, at the top or centered vertically, and cut short
+    /// with "…" if it doesn't fit (DT_END_ELLIPSIS)
+    fn text(
+        &mut self,
+        text: &str,
+        rect: Rect,
+        font: TextFont,
+        color: u32,
+        align: Align,
+        vcenter: bool,
+    ) {
+        let size = font.em_size();
+        let mut shaped = shape_text(text);
+        if shaped.width * size > rect.width() {
+            let mut chars: Vec<char> = text.chars().collect();
+            while !chars.is_empty() && shaped.width * size > rect.width() {
+                chars.pop();
+                let cut: String = chars.iter().collect();
+                shaped = shape_text(&format!("{}…", cut.trim_end()));
+            }
+        }
+
+        let width = shaped.width * size;
+        let x = match align {
+            Align::Left => rect.left,
+            Align::Center => rect.left + (rect.width() - width) / 2.0,
+            Align::Right => rect.right - width,
+        };
+        let top = if vcenter {
+            rect.top + (rect.height() - font.height) / 2.0
+        } else {
+            rect.top
+        };
+        let baseline = top + font.ascent();
+        self.shaped_text(&shaped, x, baseline, size, color, font.bold);
+    }
+}
+
+/// "#RRGGBB" for a COLORREF
+fn svg_color(color: u32) -> String {
+    let (r, g, b) = color_rgb(color);
+    format!("#{:02X}{:02X}{:02X}", r, g, b)
+}
+
+/// Glyph outlines as SVG path data, in font units
+#[derive(Default)]
+struct SvgPath(String);
+
+impl OutlineBuilder for SvgPath {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let _ = write!(self.0, "M{} {}", x, y);
+    }
+    fn line_to(&mut self, x: f32, y: f32) {
+        let _ = write!(self.0, "L{} {}", x, y);
+    }
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let _ = write!(self.0, "Q{} {} {} {}", x1, y1, x, y);
+    }
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let _ = write!(self.0, "C{} {} {} {} {} {}", x1, y1, x2, y2, x, y);
+    }
+    fn close(&mut self) {
+        self.0.push('Z');
+    }
+}
+
+/// Draws into an SVG document
+pub struct SvgCanvas {
+    width: f32,
+    height: f32,
+    body: String,
+    glyphs: HashSet<u16>,
+}
+
+impl SvgCanvas {
+    pub fn new(width: f32, height: f32) -> SvgCanvas {
+        SvgCanvas {
+            width,
+            height,
+            body: String::new(),
+            glyphs: HashSet::new(),
+        }
+    }
+
+    /// The finished document; `scale` multiplies its displayed size
+    pub fn finish(self, scale: f32) -> String {
+        let face = ekush_face();
+        let mut ids: Vec<u16> = self.glyphs.into_iter().collect();
+        ids.sort_unstable();
+        let mut defs = String::new();
+        for id in ids {
+            let mut path = SvgPath::default();
+            face.outline_glyph(GlyphId(id), &mut path);
+            let _ = writeln!(defs, "<path id=\"g{}\" d=\"{}\"/>", id, path.0);
+        }
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n<defs>\n{}</defs>\n{}</svg>\n",
+            self.width * scale,
+            self.height * scale,
+            self.width,
+            self.height,
+            defs,
+            self.body
+        )
+    }
+}
+
+impl Canvas for SvgCanvas {
+    fn fill_rect(&mut self, rect: Rect, radius: f32, color: u32) {
+        let _ = writeln!(
+            self.body,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\" fill=\"{}\"/>",
+            rect.left,
+            rect.top,
+            rect.width(),
+            rect.height(),
+            radius,
+            svg_color(color)
+        );
+    }
+
+    fn stroke_rect(&mut self, rect: Rect, radius: f32, color: u32) {
+        let _ = writeln!(
+            self.body,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\" fill=\"none\" stroke=\"{}\"/>",
+            rect.left + 0.5,
+            rect.top + 0.5,
+            rect.width() - 1.0,
+            rect.height() - 1.0,
+            radius,
+            svg_color(color)
+        );
+    }
+
+    fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, color: u32) {
+        let _ = writeln!(
+            self.body,
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\"/>",
+            x1,
+            y1 + 0.5,
+            x2,
+            y2 + 0.5,
+            svg_color(color)
+        );
+    }
+
+    fn ellipse(&mut self, rect: Rect, fill: Option<u32>, outline: u32) {
+        let _ = writeln!(
+            self.body,
+            "<ellipse cx=\"{}\" cy=\"{}\" rx=\"{}\" ry=\"{}\" fill=\"{}\" stroke=\"{}\"/>",
+            (rect.left + rect.right) / 2.0,
+            (rect.top + rect.bottom) / 2.0,
+            rect.width() / 2.0 - 0.5,
+            rect.height() / 2.0 - 0.5,
+            fill.map_or("none".to_string(), svg_color),
+            svg_color(outline)
+        );
+    }
+
+    fn glyph(&mut self, id: u16, x: f32, baseline: f32, size: f32, color: u32, bold: bool) {
+        let scale = size / ekush_face().units_per_em() as f32;
+        self.glyphs.insert(id);
+        let color = svg_color(color);
+        let stroke = if bold {
+            format!(
+                " stroke=\"{}\" stroke-width=\"{}\"",
+                color,
+                BOLD_STROKE * ekush_face().units_per_em() as f32
+            )
+        } else {
+            String::new()
+        };
+        let _ = writeln!(
+            self.body,
+            "<use xlink:href=\"#g{}\" transform=\"matrix({} 0 0 {} {} {})\" fill=\"{}\"{}/>",
+            id, scale, -scale, x, baseline, color, stroke
+        );
+    }
+}
+
+/// Glyph outlines as tiny-skia paths, in font units
+struct SkiaPath(PathBuilder);
+
+impl OutlineBuilder for SkiaPath {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.0.move_to(x, y);
+    }
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.0.line_to(x, y);
+    }
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.0.quad_to(x1, y1, x, y);
+    }
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.0.cubic_to(x1, y1, x2, y2, x, y);
+    }
+    fn close(&mut self) {
+        self.0.close();
+    }
+}
+
+/// A rectangle path with rounded corners
+fn rounded_rect_path(rect: Rect, radius: f32) -> Option<tiny_skia::Path> {
+    let radius = radius.min(rect.width() / 2.0).min(rect.height() / 2.0);
+    if radius <= 0.0 {
+        return tiny_skia::Rect::from_ltrb(rect.left, rect.top, rect.right, rect.bottom)
+            .map(PathBuilder::from_rect);
+    }
+    // Control points of a quarter circle as a cubic
+    let k = radius * 0.552_284_8;
+    let mut path = PathBuilder::new();
+    path.move_to(rect.left + radius, rect.top);
+    path.line_to(rect.right - radius, rect.top);
+    path.cubic_to(
+        rect.right - radius + k,
+        rect.top,
+        rect.right,
+        rect.top + radius - k,
+        rect.right,
+        rect.top + radius,
+    );
+    path.line_to(rect.right, rect.bottom - radius);
+    path.cubic_to(
+        rect.right,
+        rect.bottom - radius + k,
+        rect.right - radius + k,
+        rect.bottom,
+        rect.right - radius,
+        rect.bottom,
+    );
+    path.line_to(rect.left + radius, rect.bottom);
+    path.cubic_to(
+        rect.left + radius - k,
+        rect.bottom,
+        rect.left,
+        rect.bottom - radius + k,
+        rect.left,
+        rect.bottom - radius,
+    );
+    path.line_to(rect.left, rect.top + radius);
+    path.cubic_to(
+        rect.left,
+        rect.top + radius - k,
+        rect.left + radius - k,
+        rect.top,
+        rect.left + radius,
+        rect.top,
+    );
+    path.close();
+    path.finish()
+}
+
+fn paint(color: u32) -> Paint<'static> {
+    let (r, g, b) = color_rgb(color);
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(r, g, b, 255);
+    paint
+}
+
+/// Draws into a bitmap, `scale` device pixels to a pixel
+pub struct PngCanvas {
+    pixmap: Pixmap,
+    transform: Transform, // scene pixels to image pixels
+    glyphs: HashMap<u16, Option<tiny_skia::Path>>,
+}
+
+impl PngCanvas {
+    /// A transparent canvas, or None if the size is empty or too large
+    pub fn new(width: f32, height: f32, scale: f32) -> Option<PngCanvas> {
+        let pixmap = Pixmap::new(
+            (width * scale).ceil() as u32,
+            (height * scale).ceil() as u32,
+        )?;
+        Some(PngCanvas::on_pixmap(pixmap, 0.0, 0.0, scale))
+    }
+
+    /// A canvas over an existing image, with the scene's top left at (x, y)
+    /// in the image's pixels
+    pub fn on_pixmap(pixmap: Pixmap, x: f32, y: f32, scale: f32) -> PngCanvas {
+        PngCanvas {
+            pixmap,
+            transform: Transform::from_row(scale, 0.0, 0.0, scale, x, y),
+            glyphs: HashMap::new(),
+        }
+    }
+
+    /// The image as a PNG file
+    pub fn finish(self) -> Vec<u8> {
+        self.pixmap.encode_png().unwrap_or_default()
+    }
+
+    /// The image drawn so far
+    pub fn into_pixmap(self) -> Pixmap {
+        self.pixmap
+    }
+
+    fn transform(&self) -> Transform {
+        self.transform
+    }
+}
+
+impl Canvas for PngCanvas {
+    fn fill_rect(&mut self, rect: Rect, radius: f32, color: u32) {
+        if let Some(path) = rounded_rect_path(rect, radius) {
+            self.pixmap.fill_path(
+                &path,
+                &paint(color),
+                FillRule::Winding,
+                self.transform(),
+                None,
+            );
+        }
+    }
+
+    fn stroke_rect(&mut self, rect: Rect, radius: f32, color: u32) {
+        let inset = Rect::new(
+            rect.left + 0.5,
+            rect.top + 0.5,
+            rect.right - 0.5,
+            rect.bottom - 0.5,
+        );
+        if let Some(path) = rounded_rect_path(inset, radius) {
+            self.pixmap.stroke_path(
+                &path,
+                &paint(color),
+                &Stroke::default(),
+                self.transform(),
+                None,
+            );
+        }
+    }
+
+    fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, color: u32) {
+        let mut path = PathBuilder::new();
+        path.move_to(x1, y1 + 0.5);
+        path.line_to(x2, y2 + 0.5);
+        if let Some(path) = path.finish() {
+            self.pixmap.stroke_path(
+                &path,
+                &paint(color),
+                &Stroke::default(),
+                self.transform(),
+                None,
+            );
+        }
+    }
+
+    fn ellipse(&mut self, rect: Rect, fill: Option<u32>, outline: u32) {
+        let Some(oval) = tiny_skia::Rect::from_ltrb(
+            rect.left + 0.5,
+            rect.top + 0.5,
+            rect.right - 0.5,
+            rect.bottom - 0.5,
+        ) else {
+            return;
+        };
+        let Some(path) = PathBuilder::from_oval(oval) else {
+            return;
+        };
+        if let Some(fill) = fill {
+            self.pixmap.fill_path(
+                &path,
+                &paint(fill),
+                FillRule::Winding,
+                self.transform(),
+                None,
+            );
+        }
+        self.pixmap.stroke_path(
+            &path,
+            &paint(outline),
+            &Stroke::default(),
+            self.transform(),
+            None,
+        );
+    }
+
+    fn glyph(&mut self, id: u16, x: f32, baseline: f32, size: f32, color: u32, bold: bool) {
+        let face = ekush_face();
+        let path = self.glyphs.entry(id).or_insert_with(|| {
+            let mut outline = SkiaPath(PathBuilder::new());
+            face.outline_glyph(GlyphId(id), &mut outline)?;
+            outline.0.finish()
+        });
+        let Some(path) = path else {
+            return;
+        };
+        let units_per_em = face.units_per_em() as f32;
+        let scale = size / units_per_em;
+        let transform =
+            Transform::from_row(scale, 0.0, 0.0, -scale, x, baseline).post_concat(self.transform);
+        let paint = paint(color);
+        self.pixmap
+            .fill_path(path, &paint, FillRule::Winding, transform, None);
+        if bold {
+            let stroke = Stroke {
+                width: BOLD_STROKE * units_per_em,
+                ..Stroke::default()
+            };
+            self.pixmap
+                .stroke_path(path, &paint, &stroke, transform, None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn svg_shapes() {
+        let mut canvas = SvgCanvas::new(100.0, 50.0);
+        canvas.fill_rect(Rect::new(10.0, 5.0, 30.0, 25.0), 4.0, 0x000000FF);
+        canvas.stroke_rect(Rect::new(0.0, 0.0, 100.0, 50.0), 0.0, 0x00E0E0E0);
+        canvas.line(0.0, 40.0, 100.0, 40.0, 0x00000000);
+        canvas.ellipse(Rect::new(60.0, 10.0, 70.0, 20.0), None, 0x00FF0000);
+        assert_eq!(
+            canvas.finish(2.0),
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\" width=\"200\" height=\"100\" viewBox=\"0 0 100 50\">\n\
+             <defs>\n</defs>\n\
+             <rect x=\"10\" y=\"5\" width=\"20\" height=\"20\" rx=\"4\" fill=\"#FF0000\"/>\n\
+             <rect x=\"0.5\" y=\"0.5\" width=\"99\" height=\"49\" rx=\"0\" fill=\"none\" stroke=\"#E0E0E0\"/>\n\
+             <line x1=\"0\" y1=\"40.5\" x2=\"100\" y2=\"40.5\" stroke=\"#000000\"/>\n\
+             <ellipse cx=\"65\" cy=\"15\" rx=\"4.5\" ry=\"4.5\" fill=\"none\" stroke=\"#0000FF\"/>\n\
+             </svg>\n"
+        );
+    }
+
+    #[test]
+    fn svg_glyphs_are_defined_once() {
+        let mut canvas = SvgCanvas::new(100.0, 50.0);
+        canvas.glyph(42, 0.0, 20.0, 16.0, 0, false);
+        canvas.glyph(42, 10.0, 20.0, 16.0, 0, true);
+        let svg = canvas.finish(1.0);
+        assert_eq!(svg.matches("<path id=\"g42\"").count(), 1);
+        assert_eq!(svg.matches("xlink:href=\"#g42\"").count(), 2);
+        assert_eq!(svg.matches("stroke-width").count(), 1);
+    }
+}
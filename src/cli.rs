@@ -0,0 +1,491 @@
+// cli.rs - Command line: headless exports for scripts and scheduled tasks
+//
+// With no arguments the program starts the widget. With a command it runs in
+// the console it was started from (or with its output redirected) and exits.
+
+use crate::calendar::{
+    bangla_year_days, civil_from_days, current_days, days_from_civil, get_current_bangla_date,
+    parse_bangla_number,
+};
+#[cfg(not(windows))]
+use crate::constants::DEFAULT_FEED_SERVER_PORT;
+use crate::events::{import_events, load_events};
+use crate::feed_server::run_feed_server;
+use crate::holidays::load_holiday_packs;
+use crate::html::{month_html, year_html};
+use crate::ics::{IcsOptions, calendar_ics};
+use crate::ics_import::{import_summary, parse_ics};
+use crate::image::{Corner, ImageFormat, month_image, widget_image};
+use crate::pdf::{month_pdf, year_pdf};
+use crate::status::{StatusFormat, print_status, watch_status};
+use crate::wallpaper::{wallpaper_path, write_wallpaper};
+#[cfg(windows)]
+use {
+    crate::registry::{
+        load_feed_server_port, load_location, load_wallpaper_corner, load_week_start,
+        load_weekend_days,
+    },
+    crate::wallpaper::{screen_size, set_wallpaper},
+    crate::{LOCATION_SELECTION, WEEK_START, WEEKEND_DAYS},
+    std::sync::atomic::Ordering,
+    windows::Win32::System::Console::{ATTACH_PARENT_PROCESS, AttachConsole},
+};
+
+const USAGE: &str = "\
+ব্যবহার: bangla-calendar [কমান্ড]
+
+কমান্ড ছাড়া চালালে উইজেট খোলে (শুধু উইন্ডোজে)।
+
+  export-ics [--year বছর | --from YYYY-MM-DD --to YYYY-MM-DD]
+             [--holidays] [--events] [--output ফাইল]
+      বাংলা তারিখের .ics ফাইল; বছর বঙ্গাব্দে (ডিফল্ট: চলতি বছর)।
+      --output না দিলে আউটপুটে লেখে।
+
+  export-pdf [--year বছর] [--month ১-১২] [--output ফাইল]
+      ছাপার উপযোগী পিডিএফ ক্যালেন্ডার: --month দিলে সেই মাস (১ = বৈশাখ),
+      না দিলে পুরো বছর, প্রতি পাতায় এক মাস।
+
+  export-image [--widget] [--year বছর] [--month ১-১২]
+               [--format svg|png] [--scale ১-৮] [--output ফাইল]
+      পুঞ্জিকার মাস (ডিফল্ট: চলতি মাস) বা --widget দিলে আজকের উইজেট,
+      SVG বা PNG ছবি হিসেবে; ফরম্যাট না দিলে ফাইলের নাম থেকে বোঝে।
+      --scale ১-৮ দিলে বড় করে (যেমন ২ = দ্বিগুণ)।
+
+  export-html [--year বছর] [--month ১-১২] [--output ফাইল]
+      ওয়েবসাইটে তোলার মতো এক ফাইলের HTML পাতা (ফন্টসহ): --month দিলে
+      সেই মাস, না দিলে পুরো বছর।
+
+  wallpaper ছবি [--corner top-left|top-right|bottom-left|bottom-right]
+            [--size চওড়াxউঁচু] [--output ফাইল] [--set]
+      JPEG বা PNG ছবির কোণে আজকের তারিখ আর চলতি মাস বসিয়ে পর্দার মাপের
+      PNG ওয়ালপেপার বানায়; --set দিলে ডেস্কটপে বসায়। ফাইল না দিলে
+      %APPDATA%\\BanglaCalendar\\wallpaper.png।
+
+  import-ics ফাইল
+      .ics ফাইলের ইভেন্ট ব্যক্তিগত ইভেন্টে যোগ করে; একই ফাইল আবার
+      আমদানি করলে আগের আমদানি বদলে যায়।
+
+  status [--format line|i3bar|waybar|tmux] [--watch]
+      আজকের তারিখ স্ট্যাটাস বারের জন্য: এক লাইন, i3bar/waybar JSON
+      বা tmux অংশ। --watch দিলে চলতে থাকে, তারিখ বদলালেই নতুন লাইন।
+
+  serve [--port পোর্ট]
+      ফিড সার্ভার চালায় (থামাতে Ctrl+C):
+      http://127.0.0.1:পোর্ট/calendar.ics এবং /today.json
+      পোর্ট না দিলে রেজিস্ট্রির FeedServerPort বা ৮৬৪২।
+
+  help
+      এই সাহায্য দেখায়।
+";
+
+enum CliError {
+    Usage(String),  // a command line mistake, shown with the usage
+    Failed(String), // the command itself failed
+}
+
+/// Parse a Gregorian date: 2025-04-14
+fn parse_iso_date(text: &str) -> Option<i64> {
+    let mut parts = text.split('-').map(|part| part.parse::<i32>().ok());
+    let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+    if parts.next().is_some() {
+        return None;
+    }
+    let days = days_from_civil(year, month, day);
+    (civil_from_days(days) == (year, month, day)).then_some(days)
+}
+
+/// Settings and data the commands share with the widget; the settings are
+/// in the registry, so elsewhere the defaults are used
+fn load_data() {
+    #[cfg(windows)]
+    {
+        LOCATION_SELECTION.store(unsafe { load_location() }, Ordering::Relaxed);
+        WEEK_START.store(unsafe { load_week_start() }, Ordering::Relaxed);
+        WEEKEND_DAYS.store(unsafe { load_weekend_days() }, Ordering::Relaxed);
+    }
+    for error in load_holiday_packs() {
+        eprintln!("{}", error);
+    }
+    if let Err(e) = load_events() {
+        eprintln!("{}", e);
+    }
+}
+
+/// The value after an option
+fn option_value<'a>(
+    args: &mut impl Iterator<Item = &'a String>,
+    option: &str,
+) -> Result<&'a String, CliError> {
+    args.next()
+        .ok_or_else(|| CliError::Usage(format!("{} এর পরে মান দিন", option)))
+}
+
+/// Write command output to a file, or to standard output
+fn write_output(output: Option<&String>, contents: &[u8]) -> Result<(), CliError> {
+    match output {
+        Some(path) => {
+            std::fs::write(path, contents).map_err(|e| CliError::Failed(format!("{}: {}", path, e)))
+        }
+        None => {
+            use std::io::Write;
+            std::io::stdout()
+                .write_all(contents)
+                .map_err(|e| CliError::Failed(e.to_string()))
+        }
+    }
+}
+
+fn export_ics(args: &[String]) -> Result<(), CliError> {
+    let mut range = None;
+    let (mut from, mut to) = (None, None);
+    let mut options = IcsOptions::default();
+    let mut output = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--year" => {
+                let value = option_value(&mut args, arg)?;
+                let year = parse_bangla_number(value)
+                    .ok_or_else(|| CliError::Usage(format!("বছর বোঝা যায়নি: {}", value)))?;
+                range = Some(bangla_year_days(year));
+            }
+            "--from" | "--to" => {
+                let value = option_value(&mut args, arg)?;
+                let days = parse_iso_date(value)
+                    .ok_or_else(|| CliError::Usage(format!("তারিখ বোঝা যায়নি: {}", value)))?;
+                if arg == "--from" {
+                    from = Some(days);
+                } else {
+                    to = Some(days);
+                }
+            }
+            "--holidays" => options.holidays = true,
+            "--events" => options.events = true,
+            "--output" | "-o" => output = Some(option_value(&mut args, arg)?),
+            _ => return Err(CliError::Usage(format!("অজানা অপশন: {}", arg))),
+        }
+    }
+
+    load_data();
+    let (first, last) = match (range, from, to) {
+        (Some(range), None, None) => range,
+        (None, Some(from), Some(to)) if from <= to => (from, to),
+        (None, None, None) => bangla_year_days(get_current_bangla_date().year),
+        _ => {
+            return Err(CliError::Usage(
+                "--year অথবা --from ও --to (শুরু ≤ শেষ) দিন".to_string(),
+            ));
+        }
+    };
+
+    write_output(output, calendar_ics(first, last, options).as_bytes())
+}
+
+fn export_pdf(args: &[String]) -> Result<(), CliError> {
+    let mut year = None;
+    let mut month = None;
+    let mut output = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--year" => {
+                let value = option_value(&mut args, arg)?;
+                year = Some(
+                    parse_bangla_number(value)
+                        .ok_or_else(|| CliError::Usage(format!("বছর বোঝা যায়নি: {}", value)))?,
+                );
+            }
+            "--month" => {
+                let value = option_value(&mut args, arg)?;
+                month = Some(
+                    parse_bangla_number(value)
+                        .filter(|m| (1..=12).contains(m))
+                        .ok_or_else(|| CliError::Usage(format!("মাস বোঝা যায়নি: {}", value)))?,
+                );
+            }
+            "--output" | "-o" => output = Some(option_value(&mut args, arg)?),
+            _ => return Err(CliError::Usage(format!("অজানা অপশন: {}", arg))),
+        }
+    }
+
+    load_data();
+    let year = year.unwrap_or_else(|| get_current_bangla_date().year);
+    let pdf = match month {
+        Some(month) => month_pdf(year, month - 1),
+        None => year_pdf(year),
+    };
+    write_output(output, &pdf)
+}
+
+fn export_html(args: &[String]) -> Result<(), CliError> {
+    let mut year = None;
+    let mut month = None;
+    let mut output = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--year" => {
+                let value = option_value(&mut args, arg)?;
+                year = Some(
+                    parse_bangla_number(value)
+                        .ok_or_else(|| CliError::Usage(format!("বছর বোঝা যায়নি: {}", value)))?,
+                );
+            }
+            "--month" => {
+                let value = option_value(&mut args, arg)?;
+                month = Some(
+                    parse_bangla_number(value)
+                        .filter(|m| (1..=12).contains(m))
+                        .ok_or_else(|| CliError::Usage(format!("মাস বোঝা যায়নি: {}", value)))?,
+                );
+            }
+            "--output" | "-o" => output = Some(option_value(&mut args, arg)?),
+            _ => return Err(CliError::Usage(format!("অজানা অপশন: {}", arg))),
+        }
+    }
+
+    load_data();
+    let year = year.unwrap_or_else(|| get_current_bangla_date().year);
+    let html = match month {
+        Some(month) => month_html(year, month - 1),
+        None => year_html(year),
+    };
+    write_output(output, html.as_bytes())
+}
+
+fn export_image(args: &[String]) -> Result<(), CliError> {
+    let mut widget = false;
+    let mut year = None;
+    let mut month = None;
+    let mut format = None;
+    let mut scale = 1.0;
+    let mut output = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--widget" => widget = true,
+            "--year" => {
+                let value = option_value(&mut args, arg)?;
+                year = Some(
+                    parse_bangla_number(value)
+                        .ok_or_else(|| CliError::Usage(format!("বছর বোঝা যায়নি: {}", value)))?,
+                );
+            }
+            "--month" => {
+                let value = option_value(&mut args, arg)?;
+                month = Some(
+                    parse_bangla_number(value)
+                        .filter(|m| (1..=12).contains(m))
+                        .ok_or_else(|| CliError::Usage(format!("মাস বোঝা যায়নি: {}", value)))?,
+                );
+            }
+            "--format" | "-f" => {
+                let value = option_value(&mut args, arg)?;
+                format = Some(
+                    ImageFormat::from_name(value)
+                        .ok_or_else(|| CliError::Usage(format!("অজানা ফরম্যাট: {}", value)))?,
+                );
+            }
+            "--scale" => {
+                let value = option_value(&mut args, arg)?;
+                scale = parse_bangla_number(value)
+                    .filter(|s| (1..=8).contains(s))
+                    .ok_or_else(|| CliError::Usage(format!("স্কেল বোঝা যায়নি: {}", value)))?
+                    as f32;
+            }
+            "--output" | "-o" => output = Some(option_value(&mut args, arg)?),
+            _ => return Err(CliError::Usage(format!("অজানা অপশন: {}", arg))),
+        }
+    }
+
+    // Without --format, the output file's extension decides
+    let format = format
+        .or_else(|| {
+            let extension = std::path::Path::new(output?).extension()?;
+            ImageFormat::from_name(extension.to_str()?)
+        })
+        .unwrap_or(ImageFormat::Svg);
+    load_data();
+    let image = if widget {
+        widget_image(format, scale)
+    } else {
+        let today = get_current_bangla_date();
+        let month = month.map_or(today.month, |m| m - 1);
+        month_image(year.unwrap_or(today.year), month, format, scale)
+    };
+    write_output(output, &image)
+}
+
+fn wallpaper(args: &[String]) -> Result<(), CliError> {
+    let mut source = None;
+    let mut corner = None;
+    let mut size = None;
+    let mut output = None;
+    let mut set = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--corner" | "-c" => {
+                let value = option_value(&mut args, arg)?;
+                corner = Some(
+                    Corner::from_name(value)
+                        .ok_or_else(|| CliError::Usage(format!("অজানা কোণ: {}", value)))?,
+                );
+            }
+            "--size" => {
+                let value = option_value(&mut args, arg)?;
+                size = Some(
+                    value
+                        .split_once(['x', 'X', '×'])
+                        .and_then(|(w, h)| Some((parse_bangla_number(w)?, parse_bangla_number(h)?)))
+                        .filter(|&(w, h)| (1..=16384).contains(&w) && (1..=16384).contains(&h))
+                        .map(|(w, h)| (w as u32, h as u32))
+                        .ok_or_else(|| CliError::Usage(format!("মাপ বোঝা যায়নি: {}", value)))?,
+                );
+            }
+            "--output" | "-o" => {
+                output = Some(std::path::PathBuf::from(option_value(&mut args, arg)?))
+            }
+            "--set" => set = true,
+            _ if source.is_none() && !arg.starts_with('-') => source = Some(arg),
+            _ => return Err(CliError::Usage(format!("অজানা অপশন: {}", arg))),
+        }
+    }
+    let Some(source) = source else {
+        return Err(CliError::Usage("একটি ছবি দিন".to_string()));
+    };
+
+    load_data();
+    #[cfg(windows)]
+    let (corner, size) = (
+        corner.unwrap_or_else(|| Corner::from_index(unsafe { load_wallpaper_corner() })),
+        size.unwrap_or_else(screen_size),
+    );
+    // Without the registry and the screen, the defaults are a corner and the
+    // size has to be given
+    #[cfg(not(windows))]
+    let (corner, size) = (
+        corner.unwrap_or(Corner::BottomRight),
+        size.ok_or_else(|| CliError::Usage("--size দিন".to_string()))?,
+    );
+    let output = output
+        .or_else(wallpaper_path)
+        .ok_or_else(|| CliError::Usage("--output দিন".to_string()))?;
+    write_wallpaper(std::path::Path::new(source), &output, size, corner)
+        .map_err(CliError::Failed)?;
+    if set {
+        #[cfg(windows)]
+        set_wallpaper(&output).map_err(CliError::Failed)?;
+        #[cfg(not(windows))]
+        return Err(CliError::Failed("--set শুধু উইন্ডোজে কাজ করে".to_string()));
+    }
+    println!("{}", output.display());
+    Ok(())
+}
+
+fn import_ics(args: &[String]) -> Result<(), CliError> {
+    let [path] = args else {
+        return Err(CliError::Usage("একটি .ics ফাইল দিন".to_string()));
+    };
+    load_data();
+    let text =
+        std::fs::read_to_string(path).map_err(|e| CliError::Failed(format!("{}: {}", path, e)))?;
+    let import = parse_ics(&text, current_days())
+        .map_err(|e| CliError::Failed(format!("{}: {}", path, e)))?;
+    let (added, replaced) =
+        import_events(import.events).map_err(|e| CliError::Failed(e.to_string()))?;
+    println!("{}", import_summary(added, replaced, import.skipped));
+    Ok(())
+}
+
+fn status(args: &[String]) -> Result<(), CliError> {
+    let mut format = StatusFormat::Line;
+    let mut watch = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" | "-f" => {
+                let value = option_value(&mut args, arg)?;
+                format = StatusFormat::from_name(value)
+                    .ok_or_else(|| CliError::Usage(format!("অজানা ফরম্যাট: {}", value)))?;
+            }
+            "--watch" | "-w" => watch = true,
+            _ => return Err(CliError::Usage(format!("অজানা অপশন: {}", arg))),
+        }
+    }
+    load_data();
+    if watch {
+        watch_status(format).map_err(|e| CliError::Failed(e.to_string()))
+    } else {
+        print_status(format);
+        Ok(())
+    }
+}
+
+fn serve(args: &[String]) -> Result<(), CliError> {
+    #[cfg(windows)]
+    let mut port = unsafe { load_feed_server_port() };
+    #[cfg(not(windows))]
+    let mut port = DEFAULT_FEED_SERVER_PORT;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--port" | "-p" => {
+                let value = option_value(&mut args, arg)?;
+                port = parse_bangla_number(value)
+                    .and_then(|n| u16::try_from(n).ok())
+                    .filter(|&n| n != 0)
+                    .ok_or_else(|| CliError::Usage(format!("পোর্ট বোঝা যায়নি: {}", value)))?;
+            }
+            _ => return Err(CliError::Usage(format!("অজানা অপশন: {}", arg))),
+        }
+    }
+    load_data();
+    println!("http://127.0.0.1:{}/calendar.ics", port);
+    println!("http://127.0.0.1:{}/today.json", port);
+    run_feed_server(port).map_err(|e| CliError::Failed(format!("localhost:{}: {}", port, e)))
+}
+
+/// Run a command if one was given; returns the exit code, or None to start
+/// the widget
+pub fn run_cli(args: &[String]) -> Option<i32> {
+    let (command, rest) = args.split_first()?;
+
+    // A GUI program has no console of its own; use the one it was started from
+    #[cfg(windows)]
+    unsafe {
+        let _ = AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+
+    let result = match command.as_str() {
+        "export-ics" => export_ics(rest),
+        "export-pdf" => export_pdf(rest),
+        "export-image" => export_image(rest),
+        "export-html" => export_html(rest),
+        "wallpaper" => wallpaper(rest),
+        "import-ics" => import_ics(rest),
+        "status" => status(rest),
+        "serve" => serve(rest),
+        "help" | "--help" | "-h" => {
+            print!("{}", USAGE);
+            return Some(0);
+        }
+        _ => Err(CliError::Usage(format!("অজানা কমান্ড: {}", command))),
+    };
+    match result {
+        Ok(()) => Some(0),
+        Err(CliError::Failed(message)) => {
+            eprintln!("{}", message);
+            Some(1)
+        }
+        Err(CliError::Usage(message)) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            Some(2)
+        }
+    }
+}
@@ -1,25 +1,123 @@
 // constants.rs - All application constants
 
+#[cfg(windows)]
 use windows::Win32::UI::WindowsAndMessaging::WM_USER;
 
 // Custom message for tray icon
+#[cfg(windows)]
 pub const WM_TRAYICON: u32 = WM_USER + 1;
 
 // Menu item IDs
+#[cfg(windows)]
 pub const IDM_PUNJIKA: u32 = 1000;
+#[cfg(windows)]
 pub const IDM_AUTOSTART_YES: u32 = 1001;
+#[cfg(windows)]
 pub const IDM_AUTOSTART_NO: u32 = 1002;
+#[cfg(windows)]
 pub const IDM_COUNTRY_BD: u32 = 1003;
+#[cfg(windows)]
 pub const IDM_COUNTRY_IN: u32 = 1004;
+#[cfg(windows)]
 pub const IDM_FONT_LICENSE: u32 = 1005;
+#[cfg(windows)]
 pub const IDM_WEBSITE: u32 = 1006;
+#[cfg(windows)]
 pub const IDM_EXIT: u32 = 1007;
+#[cfg(windows)]
+pub const IDM_SHOW_TITHI: u32 = 1008;
+#[cfg(windows)]
+pub const IDM_SHOW_SUN: u32 = 1009;
+#[cfg(windows)]
+pub const IDM_SHOW_PRAYER: u32 = 1010;
+#[cfg(windows)]
+pub const IDM_SHOW_PROHOR: u32 = 1011;
+#[cfg(windows)]
+pub const IDM_EXPORT_ICS: u32 = 1012;
+#[cfg(windows)]
+pub const IDM_IMPORT_ICS: u32 = 1013;
+#[cfg(windows)]
+pub const IDM_FEED_SERVER_ON: u32 = 1014;
+#[cfg(windows)]
+pub const IDM_FEED_SERVER_OFF: u32 = 1015;
+#[cfg(windows)]
+pub const IDM_FEED_SERVER_OPEN: u32 = 1016;
+#[cfg(windows)]
+pub const IDM_EXPORT_PDF_MONTH: u32 = 1017;
+#[cfg(windows)]
+pub const IDM_EXPORT_PDF_YEAR: u32 = 1018;
+#[cfg(windows)]
+pub const IDM_EXPORT_IMAGE_MONTH: u32 = 1019;
+#[cfg(windows)]
+pub const IDM_EXPORT_IMAGE_WIDGET: u32 = 1020;
+#[cfg(windows)]
+pub const IDM_EXPORT_HTML: u32 = 1021;
+#[cfg(windows)]
+pub const IDM_WALLPAPER_CHOOSE: u32 = 1022;
+#[cfg(windows)]
+pub const IDM_WALLPAPER_OFF: u32 = 1023;
+#[cfg(windows)]
+pub const IDM_PUNJIKA_SIX_ROWS: u32 = 1024;
+#[cfg(windows)]
+pub const IDM_PRAYER_METHOD_BASE: u32 = 1100; // + index into PRAYER_METHODS
+#[cfg(windows)]
+pub const IDM_ASR_HANAFI: u32 = 1120;
+#[cfg(windows)]
+pub const IDM_ASR_STANDARD: u32 = 1121;
+#[cfg(windows)]
+pub const IDM_WALLPAPER_CORNER_BASE: u32 = 1130; // + index into Corner::ALL
+#[cfg(windows)]
+pub const IDM_PUNJIKA_LABEL_BASE: u32 = 1140; // + index into CellLabel::ALL
+#[cfg(windows)]
+pub const IDM_WEEK_START_BASE: u32 = 1150; // + weekday (Sunday = 0)
+#[cfg(windows)]
+pub const IDM_WEEKEND_BASE: u32 = 1160; // + weekday (Sunday = 0)
+#[cfg(windows)]
+pub const IDM_LOCATION_BASE: u32 = 2000; // + index into LOCATIONS
+#[cfg(windows)]
+pub const IDM_EVENT_ADD: u32 = 3000;
+#[cfg(windows)]
+pub const IDM_EVENT_EDIT_BASE: u32 = 3100; // + index into the day's events
 
 // App constants
 pub const APP_NAME: &str = "BanglaCalendar";
+#[cfg(windows)]
 pub const POS_KEY_X: &str = "PosX";
+#[cfg(windows)]
 pub const POS_KEY_Y: &str = "PosY";
+#[cfg(windows)]
 pub const COUNTRY_KEY: &str = "Country";
+#[cfg(windows)]
+pub const WIDGET_LINES_KEY: &str = "WidgetLines";
+#[cfg(windows)]
+pub const LOCATION_KEY: &str = "Location";
+#[cfg(windows)]
+pub const PRAYER_METHOD_KEY: &str = "PrayerMethod";
+#[cfg(windows)]
+pub const ASR_RULE_KEY: &str = "AsrRule";
+#[cfg(windows)]
+pub const FEED_SERVER_KEY: &str = "FeedServer";
+#[cfg(windows)]
+pub const FEED_SERVER_PORT_KEY: &str = "FeedServerPort";
+#[cfg(windows)]
+pub const WALLPAPER_IMAGE_KEY: &str = "WallpaperImage";
+#[cfg(windows)]
+pub const WALLPAPER_CORNER_KEY: &str = "WallpaperCorner";
+#[cfg(windows)]
+pub const PUNJIKA_SIX_ROWS_KEY: &str = "PunjikaSixRows";
+#[cfg(windows)]
+pub const PUNJIKA_CELL_LABEL_KEY: &str = "PunjikaCellLabel";
+#[cfg(windows)]
+pub const WEEK_START_KEY: &str = "WeekStart";
+#[cfg(windows)]
+pub const WEEKEND_DAYS_KEY: &str = "WeekendDays";
+pub const DEFAULT_WEEKEND_DAYS: u32 = 0b110_0000; // Friday and Saturday
+
+// Local feed server (off unless turned on from the tray)
+pub const DEFAULT_FEED_SERVER_PORT: u16 = 8642;
+
+// Bangladesh Standard Time (UTC+06:00)
+pub const BD_UTC_OFFSET_MINUTES: i32 = 360;
 
 // UI Colors - Modern dark theme
 pub const BG_COLOR: u32 = 0x00201A18; // Dark brown-black background
@@ -27,18 +125,83 @@ pub const TEXT_PRIMARY: u32 = 0x00FFFFFF; // White text
 pub const TEXT_SECONDARY: u32 = 0x00B0B0B0; // Light gray
 pub const BORDER_COLOR: u32 = 0x00404040; // Subtle border
 
+// Calendar (punjika) colors, also used by the exporters
+pub const CAL_BG: u32 = 0x00FFFFFF;
+pub const CAL_HEADER_BG: u32 = 0x00006B3C; // Bangladesh green
+pub const CAL_HEADER_TEXT: u32 = 0x00FFFFFF;
+pub const CAL_NAV_BG: u32 = 0x00F5F5F5;
+pub const CAL_NAV_TEXT: u32 = 0x00333333;
+pub const CAL_WEEKDAY_TEXT: u32 = 0x00666666;
+pub const CAL_DATE_TEXT: u32 = 0x00333333;
+pub const CAL_TODAY_BG: u32 = 0x00006B3C;
+pub const CAL_TODAY_TEXT: u32 = 0x00FFFFFF;
+#[cfg(windows)]
+pub const CAL_HOVER_BG: u32 = 0x00E8F5E9;
+#[cfg(windows)]
+pub const CAL_SELECTED_BORDER: u32 = 0x00412AF4; // Bangladesh red - selected day
+pub const CAL_WEEKEND_BG: u32 = 0x00F3F3FB; // Pale rose - weekend days
+pub const CAL_WEEKEND_TEXT: u32 = 0x002828D3; // Red - weekend weekday names
+pub const CAL_HOLIDAY_TEXT: u32 = 0x002828D3; // Red - public holiday
+pub const CAL_OPTIONAL_TEXT: u32 = 0x00227EE6; // Orange - optional holiday
+pub const CAL_OBSERVANCE_TEXT: u32 = 0x00C06515; // Blue - observance
+pub const CAL_FOOTER_TEXT: u32 = 0x00555555;
+pub const CAL_FULL_MOON: u32 = 0x0080D8F5; // Pale yellow - পূর্ণিমা
+pub const CAL_NEW_MOON: u32 = 0x00404040; // Dark gray - অমাবস্যা
+pub const CAL_MOON_OUTLINE: u32 = 0x00A0A0A0;
+pub const CAL_SOLAR_ECLIPSE: u32 = 0x00008CFF; // Orange - সূর্যগ্রহণ
+pub const CAL_LUNAR_ECLIPSE: u32 = 0x002222B2; // Copper red - চন্দ্রগ্রহণ
+
+// Calendar (punjika) dimensions, also used by the image export
+pub const CAL_WIDTH: i32 = 320;
+pub const CAL_HEADER_HEIGHT: i32 = 60;
+pub const CAL_NAV_HEIGHT: i32 = 40;
+pub const CAL_WEEKDAY_HEIGHT: i32 = 30;
+pub const CAL_CELL_SIZE: i32 = 40;
+pub const CAL_PADDING: i32 = 12;
+pub const CAL_FOOTER_HEIGHT: i32 = 26;
+pub const CAL_MOON_SIZE: i32 = 8;
+
+// Ekush font heights in pixels for the widget and the calendar
+pub const WIDGET_LINE1_FONT_SIZE: i32 = 26;
+pub const WIDGET_LINE2_FONT_SIZE: i32 = 22;
+pub const WIDGET_LINE3_FONT_SIZE: i32 = 18;
+pub const CAL_HEADER_FONT_SIZE: i32 = 22;
+pub const CAL_NAV_FONT_SIZE: i32 = 20;
+pub const CAL_WEEKDAY_FONT_SIZE: i32 = 14;
+pub const CAL_DATE_FONT_SIZE: i32 = 18;
+pub const CAL_SUB_FONT_SIZE: i32 = 14;
+
 // Widget dimensions
 pub const WIDGET_HEIGHT: i32 = 90;
 pub const CORNER_RADIUS: i32 = 12;
 pub const PADDING: i32 = 8;
 
+// Optional widget lines below the date (bit flags)
+#[cfg(windows)]
+pub const WIDGET_LINE_TITHI: u32 = 1;
+#[cfg(windows)]
+pub const WIDGET_LINE_SUN: u32 = 2;
+#[cfg(windows)]
+pub const WIDGET_LINE_PRAYER: u32 = 4;
+#[cfg(windows)]
+pub const WIDGET_LINE_PROHOR: u32 = 8;
+#[cfg(windows)]
+pub const WIDGET_EXTRA_LINE_HEIGHT: i32 = 22;
+
 // Owner-drawn menu constants
+#[cfg(windows)]
 pub const MENU_ITEM_HEIGHT: i32 = 28;
+#[cfg(windows)]
 pub const MENU_FONT_SIZE: i32 = 18;
+#[cfg(windows)]
 pub const MENU_BG_COLOR: u32 = 0x00FFFFFF;
+#[cfg(windows)]
 pub const MENU_TEXT_COLOR: u32 = 0x00000000;
+#[cfg(windows)]
 pub const MENU_HIGHLIGHT_BG: u32 = 0x00FFE0C0;
+#[cfg(windows)]
 pub const MENU_DISABLED_TEXT: u32 = 0x00808080;
+#[cfg(windows)]
 pub const MENU_CHECK_COLOR: u32 = 0x00008800;
 
 // Bangla ordinal suffixes (১লা, ২রা, etc.)
@@ -120,5 +283,171 @@ pub const BANGLA_WEEKDAYS: &[&str] = &[
     "শনিবার",    // 6 - Saturday
 ];
 
+// Short weekday names (calendar headers)
+pub const BANGLA_WEEKDAYS_SHORT: &[&str] = &["রবি", "সোম", "মঙ্গল", "বুধ", "বৃহঃ", "শুক্র", "শনি"];
+
+// Weekday initials, for the year view's small months
+#[cfg(windows)]
+pub const BANGLA_WEEKDAYS_LETTER: &[&str] = &["র", "সো", "ম", "বু", "বৃ", "শু", "শ"];
+
+// Ordinals for the four প্রহর of the day and of the night
+#[cfg(windows)]
+pub const PROHOR_ORDINALS: &[&str] = &["প্রথম", "দ্বিতীয়", "তৃতীয়", "চতুর্থ"];
+
 // Bangla numerals
 pub const BANGLA_DIGITS: &[char] = &['০', '১', '২', '৩', '৪', '৫', '৬', '৭', '৮', '৯'];
+
+// Tithi names (তিথি) - the same 14 names in both pakshas, then পূর্ণিমা/অমাবস্যা
+#[cfg(windows)]
+pub const TITHI_NAMES: &[&str] = &[
+    "প্রতিপদ",  // 1
+    "দ্বিতীয়া",  // 2
+    "তৃতীয়া",    // 3
+    "চতুর্থী",   // 4
+    "পঞ্চমী",   // 5
+    "ষষ্ঠী",    // 6
+    "সপ্তমী",   // 7
+    "অষ্টমী",   // 8
+    "নবমী",    // 9
+    "দশমী",    // 10
+    "একাদশী",   // 11
+    "দ্বাদশী",   // 12
+    "ত্রয়োদশী", // 13
+    "চতুর্দশী",  // 14
+    "পূর্ণিমা",   // 15 - শুক্লপক্ষ
+    "অমাবস্যা",   // 15 - কৃষ্ণপক্ষ
+];
+
+// Paksha names (পক্ষ)
+#[cfg(windows)]
+pub const PAKSHA_NAMES: &[&str] = &["শুক্লপক্ষ", "কৃষ্ণপক্ষ"];
+
+// Nakshatra names (নক্ষত্র) - 27 divisions of the Moon's sidereal path
+#[cfg(windows)]
+pub const NAKSHATRA_NAMES: &[&str] = &[
+    "অশ্বিনী",
+    "ভরণী",
+    "কৃত্তিকা",
+    "রোহিণী",
+    "মৃগশিরা",
+    "আর্দ্রা",
+    "পুনর্বসু",
+    "পুষ্যা",
+    "অশ্লেষা",
+    "মঘা",
+    "পূর্বফাল্গুনী",
+    "উত্তরফাল্গুনী",
+    "হস্তা",
+    "চিত্রা",
+    "স্বাতী",
+    "বিশাখা",
+    "অনুরাধা",
+    "জ্যেষ্ঠা",
+    "মূলা",
+    "পূর্বাষাঢ়া",
+    "উত্তরাষাঢ়া",
+    "শ্রবণা",
+    "ধনিষ্ঠা",
+    "শতভিষা",
+    "পূর্বভাদ্রপদ",
+    "উত্তরভাদ্রপদ",
+    "রেবতী",
+];
+
+// Yoga names (যোগ) - 27 divisions of the Sun + Moon sidereal longitude
+#[cfg(windows)]
+pub const YOGA_NAMES: &[&str] = &[
+    "বিষ্কম্ভ",
+    "প্রীতি",
+    "আয়ুষ্মান",
+    "সৌভাগ্য",
+    "শোভন",
+    "অতিগণ্ড",
+    "সুকর্মা",
+    "ধৃতি",
+    "শূল",
+    "গণ্ড",
+    "বৃদ্ধি",
+    "ধ্রুব",
+    "ব্যাঘাত",
+    "হর্ষণ",
+    "বজ্র",
+    "সিদ্ধি",
+    "ব্যতীপাত",
+    "বরীয়ান",
+    "পরিঘ",
+    "শিব",
+    "সিদ্ধ",
+    "সাধ্য",
+    "শুভ",
+    "শুক্ল",
+    "ব্রহ্ম",
+    "ঐন্দ্র",
+    "বৈধৃতি",
+];
+
+// Karana names (করণ) - 7 repeating, then the 4 fixed ones
+#[cfg(windows)]
+pub const KARANA_NAMES: &[&str] = &[
+    "বব",
+    "বালব",
+    "কৌলব",
+    "তৈতিল",
+    "গর",
+    "বণিজ",
+    "বিষ্টি",
+    "শকুনি",
+    "চতুষ্পদ",
+    "নাগ",
+    "কিংস্তুঘ্ন",
+];
+
+// Hijri month names in Bangla
+#[cfg(windows)]
+pub const HIJRI_MONTHS: &[&str] = &[
+    "মহররম",
+    "সফর",
+    "রবিউল আউয়াল",
+    "রবিউস সানি",
+    "জমাদিউল আউয়াল",
+    "জমাদিউস সানি",
+    "রজব",
+    "শাবান",
+    "রমজান",
+    "শাওয়াল",
+    "জিলকদ",
+    "জিলহজ",
+];
+
+// Gregorian month names in Bangla
+pub const GREGORIAN_MONTHS: &[&str] = &[
+    "জানুয়ারি",
+    "ফেব্রুয়ারি",
+    "মার্চ",
+    "এপ্রিল",
+    "মে",
+    "জুন",
+    "জুলাই",
+    "আগস্ট",
+    "সেপ্টেম্বর",
+    "অক্টোবর",
+    "নভেম্বর",
+    "ডিসেম্বর",
+];
+
+// Gregorian month abbreviations, for the punjika's day cells
+#[cfg(windows)]
+pub const GREGORIAN_MONTHS_SHORT: &[&str] = &[
+    "জানু",
+    "ফেব্রু",
+    "মার্চ",
+    "এপ্রি",
+    "মে",
+    "জুন",
+    "জুলা",
+    "আগ",
+    "সেপ্টে",
+    "অক্টো",
+    "নভে",
+    "ডিসে",
+];
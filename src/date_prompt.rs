@@ -0,0 +1,200 @@
+// date_prompt.rs - "Go to date" box for the punjika (G), taking a Bangla or a
+// Gregorian date as calendar::parse_date reads it
+
+use crate::calendar::{current_days, parse_date};
+use crate::event_editor::{control_text, create_control};
+use crate::get_flag_icon;
+use crate::punjika::show_calendar_date;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use windows::{
+    Win32::Foundation::*, Win32::Graphics::Gdi::*, Win32::System::LibraryLoader::GetModuleHandleW,
+    Win32::UI::Input::KeyboardAndMouse::SetFocus, Win32::UI::WindowsAndMessaging::*, core::*,
+};
+
+static PROMPT_HWND_PTR: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(std::ptr::null_mut());
+
+// Window dimensions
+const PROMPT_WIDTH: i32 = 340;
+const PROMPT_HEIGHT: i32 = 190;
+const PROMPT_PADDING: i32 = 16;
+const PROMPT_BUTTON_WIDTH: i32 = 90;
+
+// Control IDs (the go and cancel buttons use IDOK/IDCANCEL so Enter and Esc work)
+const IDC_DATE: i32 = 101;
+
+#[inline]
+fn get_prompt_hwnd() -> HWND {
+    HWND(PROMPT_HWND_PTR.load(Ordering::Relaxed))
+}
+
+#[inline]
+fn set_prompt_hwnd(hwnd: HWND) {
+    PROMPT_HWND_PTR.store(hwnd.0, Ordering::Relaxed);
+}
+
+/// Let the prompt handle Tab, Enter and Esc. Call from the message loop.
+pub fn is_date_prompt_message(message: &MSG) -> bool {
+    let hwnd = get_prompt_hwnd();
+    !hwnd.is_invalid() && unsafe { IsDialogMessageW(hwnd, message).as_bool() }
+}
+
+/// Ask for a date and show its month in the punjika
+pub fn show_date_prompt(parent: HWND) {
+    unsafe {
+        let prompt_hwnd = get_prompt_hwnd();
+        if !prompt_hwnd.is_invalid() && IsWindow(Some(prompt_hwnd)).as_bool() {
+            let _ = SetForegroundWindow(prompt_hwnd);
+            return;
+        }
+
+        let instance = GetModuleHandleW(None).unwrap_or_default();
+        let class_name = w!("BongoDatePromptClass");
+
+        let wc = WNDCLASSEXW {
+            cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+            style: CS_HREDRAW | CS_VREDRAW,
+            lpfnWndProc: Some(prompt_wndproc),
+            hInstance: instance.into(),
+            hCursor: LoadCursorW(None, IDC_ARROW).unwrap_or_default(),
+            hbrBackground: HBRUSH(GetStockObject(WHITE_BRUSH).0),
+            lpszClassName: class_name,
+            hIcon: get_flag_icon(),
+            hIconSm: get_flag_icon(),
+            ..Default::default()
+        };
+        RegisterClassExW(&wc);
+
+        // Centered over the punjika
+        let mut parent_rect = RECT::default();
+        let _ = GetWindowRect(parent, &mut parent_rect);
+        let x = parent_rect.left + (parent_rect.right - parent_rect.left - PROMPT_WIDTH) / 2;
+        let y = parent_rect.top + 80;
+
+        let hwnd = CreateWindowExW(
+            WS_EX_TOPMOST | WS_EX_DLGMODALFRAME | WS_EX_CONTROLPARENT,
+            class_name,
+            w!("তারিখে যান"),
+            WS_POPUP | WS_CAPTION | WS_SYSMENU,
+            x.max(0),
+            y.max(0),
+            PROMPT_WIDTH,
+            PROMPT_HEIGHT,
+            Some(parent),
+            None,
+            Some(instance.into()),
+            None,
+        )
+        .unwrap_or_default();
+
+        set_prompt_hwnd(hwnd);
+        create_controls(hwnd);
+
+        let _ = ShowWindow(hwnd, SW_SHOW);
+        let _ = UpdateWindow(hwnd);
+        if let Ok(edit) = GetDlgItem(Some(hwnd), IDC_DATE) {
+            let _ = SetFocus(Some(edit));
+        }
+    }
+}
+
+fn create_controls(hwnd: HWND) {
+    let left = PROMPT_PADDING;
+    let full = PROMPT_WIDTH - PROMPT_PADDING * 2 - 8;
+
+    create_control(
+        hwnd,
+        w!("STATIC"),
+        "বাংলা বা ইংরেজি তারিখ, যেমন ১৫ পৌষ ১৪৩২ বা 30 Dec 2025",
+        WINDOW_STYLE(0),
+        -1,
+        (left, 12, full, 40),
+    );
+    create_control(
+        hwnd,
+        w!("EDIT"),
+        "",
+        WS_TABSTOP | WS_BORDER | WINDOW_STYLE(ES_AUTOHSCROLL as u32),
+        IDC_DATE,
+        (left, 56, full, 28),
+    );
+
+    let button_y = 100;
+    create_control(
+        hwnd,
+        w!("BUTTON"),
+        "বাতিল",
+        WS_TABSTOP | WINDOW_STYLE(BS_PUSHBUTTON as u32),
+        IDCANCEL.0,
+        (
+            left + full - PROMPT_BUTTON_WIDTH * 2 - 8,
+            button_y,
+            PROMPT_BUTTON_WIDTH,
+            30,
+        ),
+    );
+    create_control(
+        hwnd,
+        w!("BUTTON"),
+        "যান",
+        WS_TABSTOP | WINDOW_STYLE(BS_DEFPUSHBUTTON as u32),
+        IDOK.0,
+        (
+            left + full - PROMPT_BUTTON_WIDTH,
+            button_y,
+            PROMPT_BUTTON_WIDTH,
+            30,
+        ),
+    );
+}
+
+extern "system" fn prompt_wndproc(
+    hwnd: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    unsafe {
+        match message {
+            // Label on the white background
+            WM_CTLCOLORSTATIC => {
+                let hdc = HDC(wparam.0 as *mut std::ffi::c_void);
+                SetBkMode(hdc, TRANSPARENT);
+                LRESULT(GetStockObject(WHITE_BRUSH).0 as isize)
+            }
+
+            WM_COMMAND => {
+                match (wparam.0 & 0xFFFF) as i32 {
+                    id if id == IDOK.0 => {
+                        let text = control_text(hwnd, IDC_DATE);
+                        match parse_date(&text, current_days()) {
+                            Some(days) => {
+                                show_calendar_date(days);
+                                let _ = DestroyWindow(hwnd);
+                            }
+                            None => {
+                                let _ = MessageBoxW(
+                                    Some(hwnd),
+                                    w!("তারিখটি বোঝা যায়নি"),
+                                    w!("তারিখে যান"),
+                                    MB_OK | MB_ICONWARNING,
+                                );
+                            }
+                        }
+                    }
+                    id if id == IDCANCEL.0 => {
+                        let _ = DestroyWindow(hwnd);
+                    }
+                    _ => {}
+                }
+                LRESULT(0)
+            }
+
+            WM_DESTROY => {
+                set_prompt_hwnd(HWND(std::ptr::null_mut()));
+                LRESULT(0)
+            }
+
+            _ => DefWindowProcW(hwnd, message, wparam, lparam),
+        }
+    }
+}
@@ -0,0 +1,525 @@
+// day_view.rs - Day detail popup (দিনের বিবরণ), opened from a punjika date cell
+
+use crate::calendar::*;
+use crate::constants::*;
+use crate::eclipses::{eclipse_days_between, upcoming_eclipses};
+use crate::fonts::{get_cal_date_font, get_cal_header_font, get_cal_sub_font};
+use crate::get_flag_icon;
+use crate::locations::{Location, current_location};
+use crate::panchang::{format_end_time, format_start_time, local_day_minute, panchang_for_day};
+use crate::prayer::{current_prayer_times, is_ramadan};
+use crate::prohor::{day_and_night_length, format_dondo_pol};
+use std::sync::atomic::{AtomicI64, AtomicPtr, Ordering};
+use windows::{
+    Win32::Foundation::*, Win32::Graphics::Dwm::*, Win32::Graphics::Gdi::*,
+    Win32::System::LibraryLoader::GetModuleHandleW, Win32::UI::WindowsAndMessaging::*, core::*,
+};
+
+// The day being shown (days since 1970-01-01)
+static VIEW_DAYS: AtomicI64 = AtomicI64::new(0);
+static DAY_VIEW_HWND_PTR: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(std::ptr::null_mut());
+
+// Window dimensions
+const DAY_WIDTH: i32 = 340;
+const DAY_HEIGHT: i32 = 586;
+const DAY_HEADER_HEIGHT: i32 = 64;
+const DAY_ROW_HEIGHT: i32 = 46;
+const DAY_PADDING: i32 = 16;
+
+// The month's eclipses, listed under the rows with a title line
+const DAY_LIST_LINE_HEIGHT: i32 = 18;
+
+// Colors
+const DAY_BG: u32 = 0x00FFFFFF;
+const DAY_HEADER_BG: u32 = 0x00006B3C; // Bangladesh green
+const DAY_HEADER_TEXT: u32 = 0x00FFFFFF;
+const DAY_LABEL_TEXT: u32 = 0x00888888;
+const DAY_VALUE_TEXT: u32 = 0x00333333;
+const DAY_SEPARATOR: u32 = 0x00EEEEEE;
+
+#[inline]
+fn get_day_view_hwnd() -> HWND {
+    HWND(DAY_VIEW_HWND_PTR.load(Ordering::Relaxed))
+}
+
+#[inline]
+fn set_day_view_hwnd(hwnd: HWND) {
+    DAY_VIEW_HWND_PTR.store(hwnd.0, Ordering::Relaxed);
+}
+
+/// Show the detail popup for a day, beside the punjika
+pub fn show_day_view(parent: HWND, days: i64) {
+    unsafe {
+        VIEW_DAYS.store(days, Ordering::Relaxed);
+        let height = day_view_height(month_eclipses(days, current_location()).len());
+
+        // If already open, just show the new day
+        let day_hwnd = get_day_view_hwnd();
+        if !day_hwnd.is_invalid() && IsWindow(Some(day_hwnd)).as_bool() {
+            let _ = SetWindowPos(
+                day_hwnd,
+                None,
+                0,
+                0,
+                DAY_WIDTH,
+                height,
+                SWP_NOMOVE | SWP_NOZORDER | SWP_NOACTIVATE,
+            );
+            let _ = InvalidateRect(Some(day_hwnd), None, true);
+            let _ = SetForegroundWindow(day_hwnd);
+            return;
+        }
+
+        let instance = GetModuleHandleW(None).unwrap_or_default();
+        let class_name = w!("BongoDayViewClass");
+
+        let wc = WNDCLASSEXW {
+            cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+            style: CS_HREDRAW | CS_VREDRAW,
+            lpfnWndProc: Some(day_view_wndproc),
+            hInstance: instance.into(),
+            hCursor: LoadCursorW(None, IDC_ARROW).unwrap_or_default(),
+            hbrBackground: HBRUSH(std::ptr::null_mut()),
+            lpszClassName: class_name,
+            hIcon: get_flag_icon(),
+            hIconSm: get_flag_icon(),
+            ..Default::default()
+        };
+        RegisterClassExW(&wc);
+
+        // Right of the punjika, or left of it near the screen edge
+        let screen_width = GetSystemMetrics(SM_CXSCREEN);
+        let mut parent_rect = RECT::default();
+        let _ = GetWindowRect(parent, &mut parent_rect);
+        let x = if parent_rect.right + 8 + DAY_WIDTH <= screen_width {
+            parent_rect.right + 8
+        } else {
+            (parent_rect.left - 8 - DAY_WIDTH).max(0)
+        };
+        let y = parent_rect.top;
+
+        let hwnd = CreateWindowExW(
+            WS_EX_TOPMOST | WS_EX_DLGMODALFRAME,
+            class_name,
+            w!("দিনের বিবরণ"),
+            WS_POPUP | WS_VISIBLE | WS_CAPTION | WS_SYSMENU,
+            x,
+            y,
+            DAY_WIDTH,
+            height,
+            Some(parent),
+            None,
+            Some(instance.into()),
+            None,
+        )
+        .unwrap_or_default();
+
+        set_day_view_hwnd(hwnd);
+
+        // Set rounded corners on Windows 11
+        let preference = DWM_WINDOW_CORNER_PREFERENCE(2); // DWMWCP_ROUND
+        let _ = DwmSetWindowAttribute(
+            hwnd,
+            DWMWA_WINDOW_CORNER_PREFERENCE,
+            &preference as *const _ as *const std::ffi::c_void,
+            std::mem::size_of::<DWM_WINDOW_CORNER_PREFERENCE>() as u32,
+        );
+
+        let _ = ShowWindow(hwnd, SW_SHOW);
+        let _ = UpdateWindow(hwnd);
+    }
+}
+
+/// Draw a single line of text in a rectangle
+fn draw_text(hdc: HDC, text: &str, rect: RECT, format: DRAW_TEXT_FORMAT) {
+    unsafe {
+        let mut text_vec: Vec<u16> = text.encode_utf16().collect();
+        let mut text_rect = rect;
+        DrawTextW(
+            hdc,
+            &mut text_vec,
+            &mut text_rect,
+            format | DT_SINGLELINE | DT_END_ELLIPSIS,
+        );
+    }
+}
+
+/// Local time of a rise or set, or a dash when it doesn't happen that day
+fn format_event_time(jd: Option<f64>, utc_offset_minutes: i32) -> String {
+    match jd {
+        Some(jd) => format_bangla_time(local_day_minute(jd, utc_offset_minutes).1),
+        None => "—".to_string(),
+    }
+}
+
+/// Local clock time of a prayer (no part of the day, names say it)
+fn format_prayer_time(jd: Option<f64>, utc_offset_minutes: i32) -> String {
+    match jd {
+        Some(jd) => format_clock_time(local_day_minute(jd, utc_offset_minutes).1),
+        None => "—".to_string(),
+    }
+}
+
+/// The eclipse on a day, or else the next one: (value, note)
+fn eclipse_row(days: i64, location: &Location) -> (String, String) {
+    let offset = location.utc_offset_minutes;
+    let Some(eclipse) = upcoming_eclipses(location.day_start(days), 1, location)
+        .into_iter()
+        .next()
+    else {
+        return ("—".to_string(), String::new());
+    };
+    let (eclipse_day, minute) = local_day_minute(eclipse.greatest, offset);
+    if eclipse_day == days {
+        (
+            eclipse.kind.get_name().to_string(),
+            format!(
+                "{} • {}",
+                format_bangla_time(minute),
+                eclipse.visibility.get_name()
+            ),
+        )
+    } else {
+        let (year, month, day) = civil_from_days(eclipse_day);
+        (
+            format!("পরবর্তী: {}", eclipse.kind.get_name()),
+            format!(
+                "{} {} {} • {}",
+                to_bangla_number(day),
+                GREGORIAN_MONTHS[(month - 1) as usize],
+                to_bangla_number(year),
+                eclipse.visibility.get_name()
+            ),
+        )
+    }
+}
+
+/// The eclipses in the day's Bangla month as (date • kind, visibility)
+fn month_eclipses(days: i64, location: &Location) -> Vec<(String, String)> {
+    let date = bangla_date_from_days(days);
+    let first = days - (date.day - 1) as i64;
+    let last = first + get_bangla_month_days(date.month, date.year) as i64 - 1;
+    eclipse_days_between(first, last, location)
+        .into_iter()
+        .map(|(day, eclipse)| {
+            let date = bangla_date_from_days(day);
+            (
+                format!(
+                    "{} {} • {}",
+                    date.get_ordinal(),
+                    date.get_month_name(),
+                    eclipse.kind.get_name()
+                ),
+                eclipse.visibility.get_name().to_string(),
+            )
+        })
+        .collect()
+}
+
+/// Window height with a list of eclipses (and its title) under the rows
+fn day_view_height(eclipses: usize) -> i32 {
+    if eclipses == 0 {
+        DAY_HEIGHT
+    } else {
+        DAY_HEIGHT + (eclipses as i32 + 1) * DAY_LIST_LINE_HEIGHT + 4
+    }
+}
+
+/// Draw the day's details using cached fonts
+fn draw_day_view(hdc: HDC, rect: &RECT) {
+    unsafe {
+        let days = VIEW_DAYS.load(Ordering::Relaxed);
+        let bangla_date = bangla_date_from_days(days);
+        let (g_year, g_month, g_day) = civil_from_days(days);
+
+        // Background
+        let bg_brush = CreateSolidBrush(COLORREF(DAY_BG));
+        FillRect(hdc, rect, bg_brush);
+        let _ = DeleteObject(bg_brush.into());
+
+        // Header: Bangla date, then weekday and Gregorian date
+        let header_rect = RECT {
+            left: 0,
+            top: 0,
+            right: rect.right,
+            bottom: DAY_HEADER_HEIGHT,
+        };
+        let header_brush = CreateSolidBrush(COLORREF(DAY_HEADER_BG));
+        FillRect(hdc, &header_rect, header_brush);
+        let _ = DeleteObject(header_brush.into());
+
+        SetBkMode(hdc, TRANSPARENT);
+        SetTextColor(hdc, COLORREF(DAY_HEADER_TEXT));
+
+        let old_font = SelectObject(hdc, get_cal_header_font().into());
+        let title = format!(
+            "{} {} {}",
+            bangla_date.get_ordinal(),
+            bangla_date.get_month_name(),
+            bangla_date.get_year_bangla()
+        );
+        draw_text(
+            hdc,
+            &title,
+            RECT {
+                left: DAY_PADDING,
+                top: 8,
+                right: rect.right - DAY_PADDING,
+                bottom: 34,
+            },
+            DT_CENTER,
+        );
+
+        SelectObject(hdc, get_cal_sub_font().into());
+        let subtitle = format!(
+            "{} • {} {} {}",
+            bangla_date.get_weekday_name(),
+            to_bangla_number(g_day),
+            GREGORIAN_MONTHS[(g_month - 1) as usize],
+            to_bangla_number(g_year)
+        );
+        draw_text(
+            hdc,
+            &subtitle,
+            RECT {
+                left: DAY_PADDING,
+                top: 36,
+                right: rect.right - DAY_PADDING,
+                bottom: DAY_HEADER_HEIGHT - 4,
+            },
+            DT_CENTER,
+        );
+
+        // Rows: label and note above, value below
+        let location = current_location();
+        let offset = location.utc_offset_minutes;
+        let panchang = panchang_for_day(days, location);
+        let (sunrise, sunset) = location.sun_times(days);
+        let (moonrise, moonset) = location.moon_times(days);
+        let (day_length, night_length) = day_and_night_length(days, location);
+        let prayers = current_prayer_times(days);
+        let (eclipse, eclipse_note) = eclipse_row(days, location);
+        let eclipses = month_eclipses(days, location);
+        let rows = [
+            (
+                "তিথি",
+                format!(
+                    "{} {}",
+                    panchang.tithi.get_paksha_name(),
+                    panchang.tithi.get_name()
+                ),
+                format!(
+                    "{} {}",
+                    format_start_time(panchang.tithi.start, days, offset),
+                    format_end_time(panchang.tithi.end, days, offset)
+                ),
+            ),
+            (
+                "নক্ষত্র",
+                panchang.nakshatra.name.to_string(),
+                format_end_time(panchang.nakshatra.end, days, offset),
+            ),
+            (
+                "যোগ",
+                panchang.yoga.name.to_string(),
+                format_end_time(panchang.yoga.end, days, offset),
+            ),
+            (
+                "করণ",
+                panchang.karana.name.to_string(),
+                format_end_time(panchang.karana.end, days, offset),
+            ),
+            (
+                "সূর্যোদয় • সূর্যাস্ত",
+                format!(
+                    "{} • {}",
+                    format_event_time(sunrise, offset),
+                    format_event_time(sunset, offset)
+                ),
+                location.name.to_string(),
+            ),
+            (
+                "দিনমান • রাত্রিমান",
+                format!(
+                    "{} • {}",
+                    format_dondo_pol(day_length),
+                    format_dondo_pol(night_length)
+                ),
+                String::new(),
+            ),
+            (
+                "চন্দ্রোদয় • চন্দ্রাস্ত",
+                format!(
+                    "{} • {}",
+                    format_event_time(moonrise, offset),
+                    format_event_time(moonset, offset)
+                ),
+                String::new(),
+            ),
+            (
+                "ফজর • যোহর • আসর",
+                format!(
+                    "{} • {} • {}",
+                    format_prayer_time(prayers[0], offset),
+                    format_prayer_time(prayers[1], offset),
+                    format_prayer_time(prayers[2], offset)
+                ),
+                String::new(),
+            ),
+            (
+                "মাগরিব • এশা",
+                format!(
+                    "{} • {}",
+                    format_prayer_time(prayers[3], offset),
+                    format_prayer_time(prayers[4], offset)
+                ),
+                if is_ramadan(days) {
+                    "রমজান".to_string()
+                } else {
+                    String::new()
+                },
+            ),
+            ("গ্রহণ", eclipse, eclipse_note),
+        ];
+
+        let sep_pen = CreatePen(PS_SOLID, 1, COLORREF(DAY_SEPARATOR));
+        let old_pen = SelectObject(hdc, sep_pen.into());
+
+        for (i, (label, value, note)) in rows.iter().enumerate() {
+            let top = DAY_HEADER_HEIGHT + 8 + i as i32 * DAY_ROW_HEIGHT;
+
+            SelectObject(hdc, get_cal_sub_font().into());
+            SetTextColor(hdc, COLORREF(DAY_LABEL_TEXT));
+            let label_rect = RECT {
+                left: DAY_PADDING,
+                top,
+                right: rect.right - DAY_PADDING,
+                bottom: top + 18,
+            };
+            draw_text(hdc, label, label_rect, DT_LEFT);
+            draw_text(hdc, note, label_rect, DT_RIGHT);
+
+            SelectObject(hdc, get_cal_date_font().into());
+            SetTextColor(hdc, COLORREF(DAY_VALUE_TEXT));
+            draw_text(
+                hdc,
+                value,
+                RECT {
+                    left: DAY_PADDING,
+                    top: top + 18,
+                    right: rect.right - DAY_PADDING,
+                    bottom: top + DAY_ROW_HEIGHT - 4,
+                },
+                DT_LEFT,
+            );
+
+            if i + 1 < rows.len() || !eclipses.is_empty() {
+                let sep_y = top + DAY_ROW_HEIGHT - 1;
+                let _ = MoveToEx(hdc, DAY_PADDING, sep_y, None);
+                let _ = LineTo(hdc, rect.right - DAY_PADDING, sep_y);
+            }
+        }
+
+        // The month's eclipses under the rows: date and kind, then visibility
+        let list_top = DAY_HEADER_HEIGHT + 8 + rows.len() as i32 * DAY_ROW_HEIGHT + 4;
+        let line_rect = |line: usize| {
+            let top = list_top + line as i32 * DAY_LIST_LINE_HEIGHT;
+            RECT {
+                left: DAY_PADDING,
+                top,
+                right: rect.right - DAY_PADDING,
+                bottom: top + DAY_LIST_LINE_HEIGHT,
+            }
+        };
+        SelectObject(hdc, get_cal_sub_font().into());
+        if !eclipses.is_empty() {
+            SetTextColor(hdc, COLORREF(DAY_LABEL_TEXT));
+            draw_text(hdc, "এ মাসের গ্রহণ", line_rect(0), DT_LEFT);
+        }
+        for (i, (eclipse, visibility)) in eclipses.iter().enumerate() {
+            SetTextColor(hdc, COLORREF(DAY_VALUE_TEXT));
+            draw_text(hdc, eclipse, line_rect(i + 1), DT_LEFT);
+            SetTextColor(hdc, COLORREF(DAY_LABEL_TEXT));
+            draw_text(hdc, visibility, line_rect(i + 1), DT_RIGHT);
+        }
+
+        SelectObject(hdc, old_pen);
+        let _ = DeleteObject(sep_pen.into());
+
+        // Restore original font - do NOT delete cached fonts
+        SelectObject(hdc, old_font);
+    }
+}
+
+extern "system" fn day_view_wndproc(
+    hwnd: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    unsafe {
+        match message {
+            WM_PAINT => {
+                let mut ps = PAINTSTRUCT::default();
+                let hdc = BeginPaint(hwnd, &mut ps);
+
+                let mut rect = RECT::default();
+                let _ = GetClientRect(hwnd, &mut rect);
+
+                // Double buffer
+                let mem_dc = CreateCompatibleDC(Some(hdc));
+                let mem_bitmap = CreateCompatibleBitmap(hdc, rect.right, rect.bottom);
+                let old_bitmap = SelectObject(mem_dc, mem_bitmap.into());
+
+                draw_day_view(mem_dc, &rect);
+
+                let _ = BitBlt(
+                    hdc,
+                    0,
+                    0,
+                    rect.right,
+                    rect.bottom,
+                    Some(mem_dc),
+                    0,
+                    0,
+                    SRCCOPY,
+                );
+
+                SelectObject(mem_dc, old_bitmap);
+                let _ = DeleteObject(mem_bitmap.into());
+                let _ = DeleteDC(mem_dc);
+
+                let _ = EndPaint(hwnd, &ps);
+                LRESULT(0)
+            }
+
+            WM_KEYDOWN => {
+                match wparam.0 as i32 {
+                    0x1B => {
+                        // ESC
+                        let _ = DestroyWindow(hwnd);
+                    }
+                    0x25 => {
+                        // Left arrow - previous day
+                        VIEW_DAYS.fetch_sub(1, Ordering::Relaxed);
+                        let _ = InvalidateRect(Some(hwnd), None, true);
+                    }
+                    0x27 => {
+                        // Right arrow - next day
+                        VIEW_DAYS.fetch_add(1, Ordering::Relaxed);
+                        let _ = InvalidateRect(Some(hwnd), None, true);
+                    }
+                    _ => {}
+                }
+                LRESULT(0)
+            }
+
+            WM_DESTROY => {
+                set_day_view_hwnd(HWND(std::ptr::null_mut()));
+                LRESULT(0)
+            }
+
+            _ => DefWindowProcW(hwnd, message, wparam, lparam),
+        }
+    }
+}
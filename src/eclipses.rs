@@ -0,0 +1,307 @@
+// eclipses.rs - Solar and lunar eclipse predictions (গ্রহণ)
+//
+// Eclipses are found from the Moon's argument of latitude at each new and
+// full moon (Meeus, chapter 54). Local visibility is checked by sampling the
+// Sun and Moon above the configured location.
+
+use crate::astronomy::*;
+use crate::locations::Location;
+
+// Mean new moon of January 2000 (JDE) and the mean synodic month (Meeus 49.1)
+const LUNATION_EPOCH: f64 = 2451550.09766;
+const MEAN_LUNATION: f64 = 29.530588861;
+
+const SUN_SEMIDIAMETER: f64 = 0.2666;
+// Moon radius in Earth radii, and the Earth's equatorial radius in km
+const MOON_RADIUS_RATIO: f64 = 0.272481;
+const EARTH_RADIUS_KM: f64 = 6378.14;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EclipseKind {
+    SolarTotal,     // পূর্ণগ্রাস
+    SolarAnnular,   // বলয়গ্রাস
+    SolarHybrid,    // বলয়-পূর্ণগ্রাস
+    SolarPartial,   // খণ্ডগ্রাস
+    LunarTotal,     // পূর্ণগ্রাস
+    LunarPartial,   // খণ্ডগ্রাস
+    LunarPenumbral, // উপচ্ছায়া
+}
+
+impl EclipseKind {
+    #[cfg(windows)]
+    pub fn get_name(self) -> &'static str {
+        match self {
+            EclipseKind::SolarTotal => "পূর্ণগ্রাস সূর্যগ্রহণ",
+            EclipseKind::SolarAnnular => "বলয়গ্রাস সূর্যগ্রহণ",
+            EclipseKind::SolarHybrid => "বলয়-পূর্ণগ্রাস সূর্যগ্রহণ",
+            EclipseKind::SolarPartial => "খণ্ডগ্রাস সূর্যগ্রহণ",
+            EclipseKind::LunarTotal => "পূর্ণগ্রাস চন্দ্রগ্রহণ",
+            EclipseKind::LunarPartial => "খণ্ডগ্রাস চন্দ্রগ্রহণ",
+            EclipseKind::LunarPenumbral => "উপচ্ছায়া চন্দ্রগ্রহণ",
+        }
+    }
+
+    pub fn is_solar(self) -> bool {
+        matches!(
+            self,
+            EclipseKind::SolarTotal
+                | EclipseKind::SolarAnnular
+                | EclipseKind::SolarHybrid
+                | EclipseKind::SolarPartial
+        )
+    }
+}
+
+/// Whether an eclipse can be seen from a place
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    Visible,
+    PartlyVisible, // the Sun or Moon rises or sets during the eclipse
+    NotVisible,
+}
+
+impl Visibility {
+    #[cfg(windows)]
+    pub fn get_name(self) -> &'static str {
+        match self {
+            Visibility::Visible => "দেখা যাবে",
+            Visibility::PartlyVisible => "আংশিক দেখা যাবে",
+            Visibility::NotVisible => "দেখা যাবে না",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Eclipse {
+    pub kind: EclipseKind,
+    pub greatest: f64, // Julian Day (UT) of greatest eclipse
+    pub visibility: Visibility,
+}
+
+/// An eclipse found at lunation `k` (whole for new moons, half for full moons),
+/// with the half-width in days of the stretch worth watching for lunar ones
+fn eclipse_at_lunation(k: f64) -> Option<(EclipseKind, f64, f64)> {
+    let t = k / 1236.85;
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let t4 = t3 * t;
+    let jde = LUNATION_EPOCH + MEAN_LUNATION * k + 0.00015437 * t2 - 0.000000150 * t3
+        + 0.00000000073 * t4;
+    let m = 2.5534 + 29.10535670 * k - 0.0000014 * t2 - 0.00000011 * t3;
+    let mp = 201.5643 + 385.81693528 * k + 0.0107582 * t2 + 0.00001238 * t3 - 0.000000058 * t4;
+    let f = 160.7108 + 390.67050284 * k - 0.0016118 * t2 - 0.00000227 * t3 + 0.000000011 * t4;
+    let omega = 124.7746 - 1.56375588 * k + 0.0020672 * t2 + 0.00000215 * t3;
+    let e = 1.0 - 0.002516 * t - 0.0000074 * t2;
+
+    let sin = |x: f64| x.to_radians().sin();
+    let cos = |x: f64| x.to_radians().cos();
+
+    // Too far from a node for any eclipse
+    if sin(f).abs() > 0.36 {
+        return None;
+    }
+
+    let f1 = f - 0.02665 * sin(omega);
+    let a1 = 299.77 + 0.107408 * k - 0.009173 * t2;
+    let is_solar = k.fract() == 0.0;
+
+    let mut correction = if is_solar {
+        -0.4075 * sin(mp) + 0.1721 * e * sin(m)
+    } else {
+        -0.4065 * sin(mp) + 0.1727 * e * sin(m)
+    };
+    correction += 0.0161 * sin(2.0 * mp) - 0.0097 * sin(2.0 * f1) + 0.0073 * e * sin(mp - m)
+        - 0.0050 * e * sin(mp + m)
+        - 0.0023 * sin(mp - 2.0 * f1)
+        + 0.0021 * e * sin(2.0 * m)
+        + 0.0012 * sin(mp + 2.0 * f1)
+        + 0.0006 * e * sin(2.0 * mp + m)
+        - 0.0004 * sin(3.0 * mp)
+        - 0.0003 * e * sin(m + 2.0 * f1)
+        + 0.0003 * sin(a1)
+        - 0.0002 * e * sin(m - 2.0 * f1)
+        - 0.0002 * e * sin(2.0 * mp - m)
+        - 0.0002 * sin(omega);
+    let greatest = ut_from_tt(jde + correction);
+
+    let p = 0.2070 * e * sin(m) + 0.0024 * e * sin(2.0 * m) - 0.0392 * sin(mp)
+        + 0.0116 * sin(2.0 * mp)
+        - 0.0073 * e * sin(mp + m)
+        + 0.0067 * e * sin(mp - m)
+        + 0.0118 * sin(2.0 * f1);
+    let q = 5.2207 - 0.0048 * e * cos(m) + 0.0020 * e * cos(2.0 * m)
+        - 0.3299 * cos(mp)
+        - 0.0060 * e * cos(mp + m)
+        + 0.0041 * e * cos(mp - m);
+    let w = cos(f1).abs();
+    // Least distance of the Moon's centre from the axis of the shadow, in Earth radii
+    let gamma = ((p * cos(f1) + q * sin(f1)) * (1.0 - 0.0048 * w)).abs();
+    // Radius of the umbral cone in the fundamental plane
+    let u = 0.0059 + 0.0046 * e * cos(m) - 0.0182 * cos(mp) + 0.0004 * cos(2.0 * mp)
+        - 0.0005 * cos(m + mp);
+
+    if is_solar {
+        let kind = if gamma > 1.5433 + u {
+            return None;
+        } else if gamma > 0.9972 + u.abs() {
+            EclipseKind::SolarPartial
+        } else if u < 0.0 {
+            EclipseKind::SolarTotal
+        } else if u > 0.0047 || u >= 0.00464 * (1.0 - gamma * gamma).max(0.0).sqrt() {
+            EclipseKind::SolarAnnular
+        } else {
+            EclipseKind::SolarHybrid
+        };
+        return Some((kind, greatest, 0.0));
+    }
+
+    let penumbral_magnitude = (1.5573 + u - gamma) / 0.5450;
+    let umbral_magnitude = (1.0128 - u - gamma) / 0.5450;
+    // Shadow radii for the semi-durations (minutes) of the visible phase
+    let (kind, radius) = if umbral_magnitude >= 1.0 {
+        (EclipseKind::LunarTotal, 1.0128 - u)
+    } else if umbral_magnitude > 0.0 {
+        (EclipseKind::LunarPartial, 1.0128 - u)
+    } else if penumbral_magnitude > 0.0 {
+        (EclipseKind::LunarPenumbral, 1.5573 + u)
+    } else {
+        return None;
+    };
+    let n = 0.5458 + 0.0400 * cos(mp);
+    let semi_duration = 60.0 / n * (radius * radius - gamma * gamma).sqrt() / 1440.0;
+    Some((kind, greatest, semi_duration))
+}
+
+/// Topocentric separation of the Sun's and Moon's centres, and the Moon's
+/// semidiameter, both in degrees
+fn sun_moon_separation(jd: f64, location: &Location) -> (f64, f64) {
+    let (sun_ra, sun_dec) = sun_equatorial(jd);
+    let (moon_ra, moon_dec) = moon_equatorial(jd);
+    let sin_parallax = EARTH_RADIUS_KM / moon_distance(jd);
+
+    // Shift the Moon for the observer's place on the Earth (Meeus 40.2, 40.3)
+    let phi = location.latitude.to_radians();
+    let reduced = (0.99664719 * phi.tan()).atan();
+    let rho_sin = 0.99664719 * reduced.sin();
+    let rho_cos = reduced.cos();
+    let hour_angle = (sidereal_time(jd) + location.longitude - moon_ra).to_radians();
+    let dec = moon_dec.to_radians();
+    let denominator = dec.cos() - rho_cos * sin_parallax * hour_angle.cos();
+    let delta_ra = (-rho_cos * sin_parallax * hour_angle.sin()).atan2(denominator);
+    let topo_dec = ((dec.sin() - rho_sin * sin_parallax) * delta_ra.cos()).atan2(denominator);
+    let topo_ra = moon_ra.to_radians() + delta_ra;
+
+    let (sun_ra, sun_dec) = (sun_ra.to_radians(), sun_dec.to_radians());
+    let cos_separation =
+        sun_dec.sin() * topo_dec.sin() + sun_dec.cos() * topo_dec.cos() * (sun_ra - topo_ra).cos();
+    let semidiameter = (MOON_RADIUS_RATIO * sin_parallax).asin().to_degrees();
+    (
+        cos_separation.clamp(-1.0, 1.0).acos().to_degrees(),
+        semidiameter,
+    )
+}
+
+/// Visibility from samples of (eclipse in progress here, body above the horizon)
+fn visibility_from(samples: impl Iterator<Item = (bool, bool)>) -> Visibility {
+    let (mut eclipsed, mut seen) = (0, 0);
+    for (in_progress, above) in samples {
+        if in_progress {
+            eclipsed += 1;
+            if above {
+                seen += 1;
+            }
+        }
+    }
+    if seen == 0 {
+        Visibility::NotVisible
+    } else if seen == eclipsed {
+        Visibility::Visible
+    } else {
+        Visibility::PartlyVisible
+    }
+}
+
+fn local_visibility(
+    kind: EclipseKind,
+    greatest: f64,
+    semi_duration: f64,
+    location: &Location,
+) -> Visibility {
+    const STEP: f64 = 2.0 / 1440.0; // two minutes
+    let (lat, lon) = (location.latitude, location.longitude);
+    if kind.is_solar() {
+        // The Moon's shadow takes a few hours to cross the Earth
+        visibility_from((-120..=120).map(|i| {
+            let jd = greatest + i as f64 * STEP;
+            let (separation, moon_semidiameter) = sun_moon_separation(jd, location);
+            (
+                separation < moon_semidiameter + SUN_SEMIDIAMETER,
+                sun_altitude(jd, lat, lon) > SUNRISE_ALTITUDE,
+            )
+        }))
+    } else {
+        let steps = (semi_duration / STEP).ceil() as i32;
+        visibility_from((-steps..=steps).map(|i| {
+            let jd = greatest + i as f64 * STEP;
+            (true, moon_altitude(jd, lat, lon) > MOONRISE_ALTITUDE)
+        }))
+    }
+}
+
+/// Eclipses whose greatest moment falls from `first` up to (not including) `last`,
+/// both Julian Days (UT)
+pub fn eclipses_between(first: f64, last: f64, location: &Location) -> Vec<Eclipse> {
+    let mut k = ((first - 1.0 - LUNATION_EPOCH) / MEAN_LUNATION * 2.0).floor() / 2.0;
+    let mut eclipses = Vec::new();
+    while LUNATION_EPOCH + MEAN_LUNATION * k < last + 1.0 {
+        if let Some((kind, greatest, semi_duration)) = eclipse_at_lunation(k)
+            && (first..last).contains(&greatest)
+        {
+            eclipses.push(Eclipse {
+                kind,
+                greatest,
+                visibility: local_visibility(kind, greatest, semi_duration, location),
+            });
+        }
+        k += 0.5;
+    }
+    eclipses
+}
+
+/// Eclipses on local days between two day numbers (inclusive), with their days
+pub fn eclipse_days_between(
+    first_day: i64,
+    last_day: i64,
+    location: &Location,
+) -> Vec<(i64, Eclipse)> {
+    let offset = location.utc_offset_minutes as f64 / 1440.0;
+    eclipses_between(
+        jd_from_days(first_day) - offset,
+        jd_from_days(last_day + 1) - offset,
+        location,
+    )
+    .into_iter()
+    .map(|eclipse| {
+        (
+            (days_from_jd(eclipse.greatest) + offset).floor() as i64,
+            eclipse,
+        )
+    })
+    .filter(|(day, _)| (first_day..=last_day).contains(day))
+    .collect()
+}
+
+/// The next `count` eclipses after a moment
+#[cfg(windows)]
+pub fn upcoming_eclipses(jd: f64, count: usize, location: &Location) -> Vec<Eclipse> {
+    // There are at least four eclipses every year
+    let mut eclipses = Vec::new();
+    let mut start = jd;
+    while eclipses.len() < count {
+        let end = start + 365.25;
+        eclipses.extend(eclipses_between(start, end, location));
+        start = end;
+    }
+    eclipses.truncate(count);
+    eclipses
+}
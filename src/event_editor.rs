@@ -0,0 +1,498 @@
+// event_editor.rs - Add/edit/delete window for personal events, opened from the punjika
+
+use crate::calendar::{parse_bangla_number, to_bangla_number};
+use crate::events::{
+    EVENT_CALENDARS, EVENT_COLORS, REPEAT_NAMES, REPEATS, Repeat, UserEvent, delete_event,
+    parse_time, save_event, weekday_of,
+};
+use crate::fonts::get_cal_sub_font;
+use crate::get_flag_icon;
+use std::sync::atomic::{AtomicPtr, AtomicU64, Ordering};
+use windows::{
+    Win32::Foundation::*, Win32::Graphics::Dwm::*, Win32::Graphics::Gdi::*,
+    Win32::System::LibraryLoader::GetModuleHandleW, Win32::UI::Input::KeyboardAndMouse::SetFocus,
+    Win32::UI::WindowsAndMessaging::*, core::*,
+};
+
+// The event being edited (0 = a new one)
+static EDITING_ID: AtomicU64 = AtomicU64::new(0);
+static EDITOR_HWND_PTR: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(std::ptr::null_mut());
+
+// Window dimensions
+const EDITOR_WIDTH: i32 = 360;
+const EDITOR_HEIGHT: i32 = 438;
+const EDITOR_PADDING: i32 = 16;
+const EDITOR_LABEL_HEIGHT: i32 = 20;
+const EDITOR_FIELD_HEIGHT: i32 = 28;
+const EDITOR_BUTTON_WIDTH: i32 = 90;
+
+// Control IDs (the save and cancel buttons use IDOK/IDCANCEL so Enter and Esc work)
+const IDC_TITLE: i32 = 101;
+const IDC_CALENDAR: i32 = 102;
+const IDC_DAY: i32 = 103;
+const IDC_MONTH: i32 = 104;
+const IDC_YEAR: i32 = 105;
+const IDC_TIME: i32 = 106;
+const IDC_COLOR: i32 = 107;
+const IDC_NOTES: i32 = 108;
+const IDC_DELETE: i32 = 109;
+const IDC_REPEAT: i32 = 110;
+
+// Calendar choices, in EVENT_CALENDARS order
+const CALENDAR_NAMES: &[&str] = &["বাংলা", "ইংরেজি", "হিজরি"];
+
+#[inline]
+fn get_editor_hwnd() -> HWND {
+    HWND(EDITOR_HWND_PTR.load(Ordering::Relaxed))
+}
+
+#[inline]
+fn set_editor_hwnd(hwnd: HWND) {
+    EDITOR_HWND_PTR.store(hwnd.0, Ordering::Relaxed);
+}
+
+/// Let the editor handle Tab, Enter and Esc. Call from the message loop.
+pub fn is_editor_message(message: &MSG) -> bool {
+    let hwnd = get_editor_hwnd();
+    !hwnd.is_invalid() && unsafe { IsDialogMessageW(hwnd, message).as_bool() }
+}
+
+/// Open the editor for an event (id 0 to add a new one)
+pub fn show_event_editor(parent: HWND, event: &UserEvent) {
+    unsafe {
+        // One editor at a time
+        let editor_hwnd = get_editor_hwnd();
+        if !editor_hwnd.is_invalid() && IsWindow(Some(editor_hwnd)).as_bool() {
+            let _ = DestroyWindow(editor_hwnd);
+        }
+        EDITING_ID.store(event.id, Ordering::Relaxed);
+
+        let instance = GetModuleHandleW(None).unwrap_or_default();
+        let class_name = w!("BongoEventEditorClass");
+
+        let wc = WNDCLASSEXW {
+            cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+            style: CS_HREDRAW | CS_VREDRAW,
+            lpfnWndProc: Some(editor_wndproc),
+            hInstance: instance.into(),
+            hCursor: LoadCursorW(None, IDC_ARROW).unwrap_or_default(),
+            hbrBackground: HBRUSH(GetStockObject(WHITE_BRUSH).0),
+            lpszClassName: class_name,
+            hIcon: get_flag_icon(),
+            hIconSm: get_flag_icon(),
+            ..Default::default()
+        };
+        RegisterClassExW(&wc);
+
+        // Centered over the punjika
+        let mut parent_rect = RECT::default();
+        let _ = GetWindowRect(parent, &mut parent_rect);
+        let x = parent_rect.left + (parent_rect.right - parent_rect.left - EDITOR_WIDTH) / 2;
+        let y = parent_rect.top + 40;
+
+        let title = if event.id == 0 {
+            w!("নতুন ইভেন্ট")
+        } else {
+            w!("ইভেন্ট সম্পাদনা")
+        };
+        let hwnd = CreateWindowExW(
+            WS_EX_TOPMOST | WS_EX_DLGMODALFRAME | WS_EX_CONTROLPARENT,
+            class_name,
+            title,
+            WS_POPUP | WS_CAPTION | WS_SYSMENU,
+            x.max(0),
+            y.max(0),
+            EDITOR_WIDTH,
+            EDITOR_HEIGHT,
+            Some(parent),
+            None,
+            Some(instance.into()),
+            None,
+        )
+        .unwrap_or_default();
+
+        set_editor_hwnd(hwnd);
+        create_controls(hwnd, event);
+
+        // Set rounded corners on Windows 11
+        let preference = DWM_WINDOW_CORNER_PREFERENCE(2); // DWMWCP_ROUND
+        let _ = DwmSetWindowAttribute(
+            hwnd,
+            DWMWA_WINDOW_CORNER_PREFERENCE,
+            &preference as *const _ as *const std::ffi::c_void,
+            std::mem::size_of::<DWM_WINDOW_CORNER_PREFERENCE>() as u32,
+        );
+
+        let _ = ShowWindow(hwnd, SW_SHOW);
+        let _ = UpdateWindow(hwnd);
+        if let Ok(title_edit) = GetDlgItem(Some(hwnd), IDC_TITLE) {
+            let _ = SetFocus(Some(title_edit));
+        }
+    }
+}
+
+/// Create one child control with the Bangla font
+pub fn create_control(
+    parent: HWND,
+    class: PCWSTR,
+    text: &str,
+    style: WINDOW_STYLE,
+    id: i32,
+    rect: (i32, i32, i32, i32),
+) -> HWND {
+    unsafe {
+        let (x, y, width, height) = rect;
+        let control = CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            class,
+            &HSTRING::from(text),
+            WS_CHILD | WS_VISIBLE | style,
+            x,
+            y,
+            width,
+            height,
+            Some(parent),
+            Some(HMENU(id as isize as *mut std::ffi::c_void)),
+            None,
+            None,
+        )
+        .unwrap_or_default();
+        SendMessageW(
+            control,
+            WM_SETFONT,
+            Some(WPARAM(get_cal_sub_font().0 as usize)),
+            Some(LPARAM(1)),
+        );
+        control
+    }
+}
+
+/// Replace a combo box's items and select one
+fn fill_combo(combo: HWND, items: &[&str], selected: usize) {
+    unsafe {
+        SendMessageW(combo, CB_RESETCONTENT, None, None);
+        for item in items {
+            let text = HSTRING::from(*item);
+            SendMessageW(
+                combo,
+                CB_ADDSTRING,
+                None,
+                Some(LPARAM(text.as_ptr() as isize)),
+            );
+        }
+        SendMessageW(combo, CB_SETCURSEL, Some(WPARAM(selected)), None);
+    }
+}
+
+fn create_controls(hwnd: HWND, event: &UserEvent) {
+    let label = |text: &str, x: i32, y: i32, width: i32| {
+        create_control(
+            hwnd,
+            w!("STATIC"),
+            text,
+            WINDOW_STYLE(0),
+            -1,
+            (x, y, width, EDITOR_LABEL_HEIGHT),
+        );
+    };
+    let edit_style = WS_TABSTOP | WS_BORDER | WINDOW_STYLE(ES_AUTOHSCROLL as u32);
+    let combo_style = WS_TABSTOP | WS_VSCROLL | WINDOW_STYLE(CBS_DROPDOWNLIST as u32);
+    let left = EDITOR_PADDING;
+    let full = EDITOR_WIDTH - EDITOR_PADDING * 2 - 8;
+
+    // শিরোনাম
+    label("শিরোনাম", left, 12, full);
+    create_control(
+        hwnd,
+        w!("EDIT"),
+        &event.title,
+        edit_style,
+        IDC_TITLE,
+        (left, 32, full, EDITOR_FIELD_HEIGHT),
+    );
+
+    // তারিখ: calendar, day, month, year
+    label("তারিখ", left, 70, full);
+    let calendar = create_control(
+        hwnd,
+        w!("COMBOBOX"),
+        "",
+        combo_style,
+        IDC_CALENDAR,
+        (left, 90, 80, 200),
+    );
+    let calendar_index = EVENT_CALENDARS
+        .iter()
+        .position(|&c| c == event.calendar)
+        .unwrap_or(0);
+    fill_combo(calendar, CALENDAR_NAMES, calendar_index);
+    create_control(
+        hwnd,
+        w!("EDIT"),
+        &to_bangla_number(event.day),
+        edit_style,
+        IDC_DAY,
+        (left + 86, 90, 44, EDITOR_FIELD_HEIGHT),
+    );
+    let month = create_control(
+        hwnd,
+        w!("COMBOBOX"),
+        "",
+        combo_style,
+        IDC_MONTH,
+        (left + 136, 90, 110, 300),
+    );
+    fill_combo(
+        month,
+        event.calendar.month_names(),
+        (event.month - 1).clamp(0, 11) as usize,
+    );
+    create_control(
+        hwnd,
+        w!("EDIT"),
+        &to_bangla_number(event.year),
+        edit_style,
+        IDC_YEAR,
+        (left + 252, 90, full - 252, EDITOR_FIELD_HEIGHT),
+    );
+
+    // পুনরাবৃত্তি
+    label("পুনরাবৃত্তি", left, 128, full);
+    let repeat = create_control(
+        hwnd,
+        w!("COMBOBOX"),
+        "",
+        combo_style,
+        IDC_REPEAT,
+        (left, 148, full, 200),
+    );
+    let repeat_index = REPEATS.iter().position(|&r| r == event.repeat).unwrap_or(0);
+    fill_combo(repeat, REPEAT_NAMES, repeat_index);
+
+    // সময় and রং
+    label("সময় (ঐচ্ছিক, যেমন ১৮:৩০)", left, 186, 170);
+    label("রং", left + 180, 186, full - 180);
+    create_control(
+        hwnd,
+        w!("EDIT"),
+        event.time.as_deref().unwrap_or(""),
+        edit_style,
+        IDC_TIME,
+        (left, 206, 170, EDITOR_FIELD_HEIGHT),
+    );
+    let color = create_control(
+        hwnd,
+        w!("COMBOBOX"),
+        "",
+        combo_style,
+        IDC_COLOR,
+        (left + 180, 206, full - 180, 200),
+    );
+    let color_names: Vec<&str> = EVENT_COLORS.iter().map(|c| c.get_name()).collect();
+    let color_index = EVENT_COLORS
+        .iter()
+        .position(|&c| c == event.color)
+        .unwrap_or(0);
+    fill_combo(color, &color_names, color_index);
+
+    // নোট
+    label("নোট", left, 244, full);
+    create_control(
+        hwnd,
+        w!("EDIT"),
+        &event.notes,
+        WS_TABSTOP
+            | WS_BORDER
+            | WS_VSCROLL
+            | WINDOW_STYLE((ES_MULTILINE | ES_WANTRETURN | ES_AUTOVSCROLL) as u32),
+        IDC_NOTES,
+        (left, 264, full, 70),
+    );
+
+    // Buttons: মুছুন on the left (existing events only), বাতিল and সংরক্ষণ on the right
+    let button_y = 350;
+    if event.id != 0 {
+        create_control(
+            hwnd,
+            w!("BUTTON"),
+            "মুছুন",
+            WS_TABSTOP | WINDOW_STYLE(BS_PUSHBUTTON as u32),
+            IDC_DELETE,
+            (left, button_y, EDITOR_BUTTON_WIDTH, 30),
+        );
+    }
+    create_control(
+        hwnd,
+        w!("BUTTON"),
+        "বাতিল",
+        WS_TABSTOP | WINDOW_STYLE(BS_PUSHBUTTON as u32),
+        IDCANCEL.0,
+        (
+            left + full - EDITOR_BUTTON_WIDTH * 2 - 8,
+            button_y,
+            EDITOR_BUTTON_WIDTH,
+            30,
+        ),
+    );
+    create_control(
+        hwnd,
+        w!("BUTTON"),
+        "সংরক্ষণ",
+        WS_TABSTOP | WINDOW_STYLE(BS_DEFPUSHBUTTON as u32),
+        IDOK.0,
+        (
+            left + full - EDITOR_BUTTON_WIDTH,
+            button_y,
+            EDITOR_BUTTON_WIDTH,
+            30,
+        ),
+    );
+}
+
+/// Text of a child control
+pub fn control_text(hwnd: HWND, id: i32) -> String {
+    unsafe {
+        let Ok(control) = GetDlgItem(Some(hwnd), id) else {
+            return String::new();
+        };
+        let mut buffer = vec![0u16; GetWindowTextLengthW(control) as usize + 1];
+        let length = GetWindowTextW(control, &mut buffer);
+        String::from_utf16_lossy(&buffer[..length.max(0) as usize])
+    }
+}
+
+/// Selected index of a combo box
+fn combo_selection(hwnd: HWND, id: i32) -> usize {
+    unsafe {
+        GetDlgItem(Some(hwnd), id)
+            .map(|combo| SendMessageW(combo, CB_GETCURSEL, None, None).0.max(0) as usize)
+            .unwrap_or(0)
+    }
+}
+
+/// The event as entered in the form
+fn read_form(hwnd: HWND) -> UserEvent {
+    let calendar =
+        EVENT_CALENDARS[combo_selection(hwnd, IDC_CALENDAR).min(EVENT_CALENDARS.len() - 1)];
+    let time = control_text(hwnd, IDC_TIME);
+    let mut event = UserEvent {
+        id: EDITING_ID.load(Ordering::Relaxed),
+        title: control_text(hwnd, IDC_TITLE).trim().to_string(),
+        calendar,
+        year: parse_bangla_number(&control_text(hwnd, IDC_YEAR)).unwrap_or(0),
+        month: combo_selection(hwnd, IDC_MONTH) as i32 + 1,
+        day: parse_bangla_number(&control_text(hwnd, IDC_DAY)).unwrap_or(0),
+        // Stored as HH:MM in English digits; left as typed if it doesn't parse
+        time: (!time.trim().is_empty()).then(|| match parse_time(&time) {
+            Some(minute) => format!("{:02}:{:02}", minute / 60, minute % 60),
+            None => time.trim().to_string(),
+        }),
+        notes: control_text(hwnd, IDC_NOTES).trim().to_string(),
+        color: EVENT_COLORS[combo_selection(hwnd, IDC_COLOR).min(EVENT_COLORS.len() - 1)],
+        repeat: REPEATS[combo_selection(hwnd, IDC_REPEAT).min(REPEATS.len() - 1)],
+        week: None,
+        weekday: None,
+        uid: None,
+    };
+    // The weekday rule is taken from the date: ১৬ বৈশাখ on a মঙ্গলবার becomes
+    // the third মঙ্গলবার of বৈশাখ; a date in the last seven days, the last one
+    if event.repeat == Repeat::Weekday
+        && let Some(days) = event.days()
+    {
+        let week = (event.day - 1) / 7 + 1;
+        event.week = Some(if week > 4 { -1 } else { week });
+        event.weekday = Some(weekday_of(days));
+    }
+    event
+}
+
+/// Close the editor and redraw the punjika that opened it
+fn close_editor(hwnd: HWND) {
+    unsafe {
+        if let Ok(owner) = GetWindow(hwnd, GW_OWNER) {
+            let _ = InvalidateRect(Some(owner), None, true);
+        }
+        let _ = DestroyWindow(hwnd);
+    }
+}
+
+fn show_error(hwnd: HWND, message: &str) {
+    unsafe {
+        let _ = MessageBoxW(
+            Some(hwnd),
+            &HSTRING::from(message),
+            w!("ইভেন্ট"),
+            MB_OK | MB_ICONWARNING,
+        );
+    }
+}
+
+extern "system" fn editor_wndproc(
+    hwnd: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    unsafe {
+        match message {
+            // Labels on the white background
+            WM_CTLCOLORSTATIC => {
+                let hdc = HDC(wparam.0 as *mut std::ffi::c_void);
+                SetBkMode(hdc, TRANSPARENT);
+                LRESULT(GetStockObject(WHITE_BRUSH).0 as isize)
+            }
+
+            WM_COMMAND => {
+                let id = (wparam.0 & 0xFFFF) as i32;
+                let notification = ((wparam.0 >> 16) & 0xFFFF) as u32;
+                match id {
+                    IDC_CALENDAR if notification == CBN_SELCHANGE => {
+                        // Swap the month names, keeping the month number
+                        let calendar = EVENT_CALENDARS
+                            [combo_selection(hwnd, IDC_CALENDAR).min(EVENT_CALENDARS.len() - 1)];
+                        let month = combo_selection(hwnd, IDC_MONTH);
+                        if let Ok(combo) = GetDlgItem(Some(hwnd), IDC_MONTH) {
+                            fill_combo(combo, calendar.month_names(), month);
+                        }
+                    }
+                    id if id == IDOK.0 => {
+                        let event = read_form(hwnd);
+                        match event.validate() {
+                            Err(message) => show_error(hwnd, message),
+                            Ok(()) => match save_event(event) {
+                                Ok(_) => close_editor(hwnd),
+                                Err(e) => show_error(hwnd, &e.to_string()),
+                            },
+                        }
+                    }
+                    IDC_DELETE => {
+                        let answer = MessageBoxW(
+                            Some(hwnd),
+                            w!("ইভেন্টটি মুছে ফেলবেন?"),
+                            w!("ইভেন্ট"),
+                            MB_YESNO | MB_ICONQUESTION,
+                        );
+                        if answer == IDYES {
+                            match delete_event(EDITING_ID.load(Ordering::Relaxed)) {
+                                Ok(()) => close_editor(hwnd),
+                                Err(e) => show_error(hwnd, &e.to_string()),
+                            }
+                        }
+                    }
+                    id if id == IDCANCEL.0 => {
+                        let _ = DestroyWindow(hwnd);
+                    }
+                    _ => {}
+                }
+                LRESULT(0)
+            }
+
+            WM_DESTROY => {
+                set_editor_hwnd(HWND(std::ptr::null_mut()));
+                LRESULT(0)
+            }
+
+            _ => DefWindowProcW(hwnd, message, wparam, lparam),
+        }
+    }
+}
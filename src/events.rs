@@ -0,0 +1,644 @@
+// events.rs - Personal events (ব্যক্তিগত ইভেন্ট) kept in the user profile
+//
+// Events live in %APPDATA%\BanglaCalendar\events.toml. Each event is dated in
+// the Bangla, Gregorian or (tabular) Hijri calendar; months are written 1-12
+// (১ = বৈশাখ, মহররম) in the file. A repeating event recurs in its own
+// calendar from that date on.
+//
+// An anchor day past the end of a shorter month (৩১ in a ৩০-day Bangla month,
+// 29 February in a common year, ৩০ in a ২৯-day Hijri month) falls on that
+// month's last day.
+
+use crate::calendar::{
+    bangla_date_from_days, civil_from_days, days_from_civil, get_bangla_month_days,
+    gregorian_from_bangla, parse_bangla_number,
+};
+#[cfg(windows)]
+use crate::constants::{BANGLA_MONTHS, GREGORIAN_MONTHS, HIJRI_MONTHS};
+use crate::hijri::{gregorian_to_hijri, hijri_month_days, hijri_to_days};
+use crate::paths::user_data_dir;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Event file format version understood by this build
+const EVENTS_FORMAT_VERSION: u32 = 1;
+const EVENTS_FILE_NAME: &str = "events.toml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventCalendar {
+    #[default]
+    Bangla,
+    Gregorian,
+    Hijri,
+}
+
+impl EventCalendar {
+    /// Days in a month (0-11) of a year
+    fn month_days(self, year: i32, month: i32) -> i32 {
+        match self {
+            EventCalendar::Bangla => get_bangla_month_days(month, year),
+            EventCalendar::Gregorian => {
+                let next = if month == 11 {
+                    days_from_civil(year + 1, 1, 1)
+                } else {
+                    days_from_civil(year, month + 2, 1)
+                };
+                (next - days_from_civil(year, month + 1, 1)) as i32
+            }
+            EventCalendar::Hijri => hijri_month_days(year, month),
+        }
+    }
+
+    /// Day number of a date (month 0-11)
+    fn to_days(self, year: i32, month: i32, day: i32) -> i64 {
+        match self {
+            EventCalendar::Bangla => {
+                let (y, m, d) = gregorian_from_bangla(year, month, day);
+                days_from_civil(y, m, d)
+            }
+            EventCalendar::Gregorian => days_from_civil(year, month + 1, day),
+            EventCalendar::Hijri => hijri_to_days(year, month, day),
+        }
+    }
+
+    /// Date (year, month 0-11, day) of a day number
+    fn date_of(self, days: i64) -> (i32, i32, i32) {
+        match self {
+            EventCalendar::Bangla => {
+                let date = bangla_date_from_days(days);
+                (date.year, date.month, date.day)
+            }
+            EventCalendar::Gregorian => {
+                let (y, m, d) = civil_from_days(days);
+                (y, m - 1, d)
+            }
+            EventCalendar::Hijri => {
+                let (y, m, d) = civil_from_days(days);
+                gregorian_to_hijri(y, m, d)
+            }
+        }
+    }
+
+    #[cfg(windows)]
+    pub fn month_names(self) -> &'static [&'static str] {
+        match self {
+            EventCalendar::Bangla => BANGLA_MONTHS,
+            EventCalendar::Gregorian => GREGORIAN_MONTHS,
+            EventCalendar::Hijri => HIJRI_MONTHS,
+        }
+    }
+}
+
+// In the editor's calendar menu
+#[cfg(windows)]
+pub const EVENT_CALENDARS: &[EventCalendar] = &[
+    EventCalendar::Bangla,
+    EventCalendar::Gregorian,
+    EventCalendar::Hijri,
+];
+
+/// How an event recurs, in its own calendar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Repeat {
+    #[default]
+    Never,
+    Yearly,  // the same day of the same month
+    Monthly, // the same day of every month
+    Weekday, // the `week`th `weekday` of the same month every year
+}
+
+#[cfg(windows)]
+pub const REPEATS: &[Repeat] = &[
+    Repeat::Never,
+    Repeat::Yearly,
+    Repeat::Monthly,
+    Repeat::Weekday,
+];
+
+// In the editor's repeat menu, in REPEATS order
+#[cfg(windows)]
+pub const REPEAT_NAMES: &[&str] = &[
+    "একবার",
+    "প্রতি বছর একই তারিখে",
+    "প্রতি মাসে একই তারিখে",
+    "প্রতি বছর মাসের একই সপ্তাহের একই বারে",
+];
+
+fn is_never(repeat: &Repeat) -> bool {
+    *repeat == Repeat::Never
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventColor {
+    #[default]
+    Green,
+    Red,
+    Blue,
+    Orange,
+    Purple,
+}
+
+// Every color, in menu order
+#[cfg(windows)]
+pub const EVENT_COLORS: &[EventColor] = &[
+    EventColor::Green,
+    EventColor::Red,
+    EventColor::Blue,
+    EventColor::Orange,
+    EventColor::Purple,
+];
+
+impl EventColor {
+    #[cfg(windows)]
+    pub fn get_name(self) -> &'static str {
+        match self {
+            EventColor::Green => "সবুজ",
+            EventColor::Red => "লাল",
+            EventColor::Blue => "নীল",
+            EventColor::Orange => "কমলা",
+            EventColor::Purple => "বেগুনি",
+        }
+    }
+
+    /// COLORREF (0x00BBGGRR) for drawing
+    #[cfg(windows)]
+    pub fn colorref(self) -> u32 {
+        match self {
+            EventColor::Green => 0x00006B3C,
+            EventColor::Red => 0x002828D3,
+            EventColor::Blue => 0x00C06515,
+            EventColor::Orange => 0x00227EE6,
+            EventColor::Purple => 0x00AD448E,
+        }
+    }
+}
+
+/// One personal event
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UserEvent {
+    #[serde(default)]
+    pub id: u64, // 0 until saved
+    pub title: String,
+    #[serde(default)]
+    pub calendar: EventCalendar,
+    pub year: i32,
+    pub month: i32, // 1-12 in either calendar
+    pub day: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>, // "HH:MM", 24-hour
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+    #[serde(default)]
+    pub color: EventColor,
+    #[serde(default, skip_serializing_if = "is_never")]
+    pub repeat: Repeat,
+    // For Repeat::Weekday: 1-4 or -1 for the last one, and 0 = রবিবার … 6 = শনিবার
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub week: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weekday: Option<i32>,
+    // UID of the iCalendar event this was imported from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+}
+
+impl UserEvent {
+    /// Day number of the event's (first) date, if that date exists
+    pub fn days(&self) -> Option<i64> {
+        let month = self.month - 1;
+        let valid = (0..12).contains(&month)
+            && (1..=self.calendar.month_days(self.year, month)).contains(&self.day);
+        valid.then(|| self.calendar.to_days(self.year, month, self.day))
+    }
+
+    /// The day of a month this event falls on in a year, if it does
+    fn day_in_month(&self, year: i32, month: i32) -> Option<i64> {
+        let calendar = self.calendar;
+        let length = calendar.month_days(year, month);
+        match self.repeat {
+            Repeat::Weekday => {
+                let weekday = self.weekday?;
+                let week = self.week?;
+                let first = calendar.to_days(year, month, 1);
+                let offset = if week == -1 {
+                    let last = first + length as i64 - 1;
+                    length as i64 - 1 - (weekday_of(last) - weekday).rem_euclid(7) as i64
+                } else {
+                    (weekday - weekday_of(first)).rem_euclid(7) as i64 + 7 * (week as i64 - 1)
+                };
+                (0..length as i64)
+                    .contains(&offset)
+                    .then_some(first + offset)
+            }
+            _ => Some(calendar.to_days(year, month, self.day.min(length))),
+        }
+    }
+
+    /// Day numbers between two days (inclusive) on which the event falls
+    pub fn occurrences(&self, first: i64, last: i64) -> Vec<i64> {
+        let Some(start) = self.days() else {
+            return Vec::new();
+        };
+        if self.repeat == Repeat::Never {
+            return if (first..=last).contains(&start) {
+                vec![start]
+            } else {
+                Vec::new()
+            };
+        }
+
+        let calendar = self.calendar;
+        let (first_year, first_month, _) = calendar.date_of(first.max(start));
+        let (last_year, last_month, _) = calendar.date_of(last);
+        let months: Vec<(i32, i32)> = match self.repeat {
+            Repeat::Monthly => {
+                let (mut year, mut month) = (first_year, first_month);
+                let mut months = Vec::new();
+                while (year, month) <= (last_year, last_month) {
+                    months.push((year, month));
+                    (year, month) = if month == 11 {
+                        (year + 1, 0)
+                    } else {
+                        (year, month + 1)
+                    };
+                }
+                months
+            }
+            _ => (first_year..=last_year)
+                .map(|year| (year, self.month - 1))
+                .collect(),
+        };
+        months
+            .into_iter()
+            .filter_map(|(year, month)| self.day_in_month(year, month))
+            .filter(|&days| days >= start && (first..=last).contains(&days))
+            .collect()
+    }
+
+    /// Minute of the day of the event's time, if it has a valid one
+    pub fn minute(&self) -> Option<i32> {
+        parse_time(self.time.as_deref()?)
+    }
+
+    /// Why the event can't be saved, if it can't
+    #[cfg(windows)]
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.title.trim().is_empty() {
+            return Err("শিরোনাম লিখুন");
+        }
+        if self.days().is_none() {
+            return Err("তারিখটি সঠিক নয়");
+        }
+        if self.repeat == Repeat::Weekday
+            && !(matches!(self.week, Some(1..=4 | -1)) && matches!(self.weekday, Some(0..=6)))
+        {
+            return Err("কোন সপ্তাহের কোন বার, তা ঠিক করা যায়নি");
+        }
+        if self.time.is_some() && self.minute().is_none() {
+            return Err("সময় ঘণ্টা:মিনিট (যেমন ১৮:৩০) আকারে লিখুন");
+        }
+        Ok(())
+    }
+}
+
+/// Weekday of a day number (0 = রবিবার)
+pub fn weekday_of(days: i64) -> i32 {
+    (days + 4).rem_euclid(7) as i32
+}
+
+/// Parse "HH:MM" (ASCII or Bangla digits) into a minute of the day
+pub fn parse_time(text: &str) -> Option<i32> {
+    let (hour, minute) = text.trim().split_once(':')?;
+    let hour = parse_bangla_number(hour)?;
+    let minute = parse_bangla_number(minute)?;
+    ((0..24).contains(&hour) && (0..60).contains(&minute)).then_some(hour * 60 + minute)
+}
+
+/// On-disk layout of the event file (format = 1)
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EventFile {
+    format: u32,
+    #[serde(default, rename = "event")]
+    events: Vec<UserEvent>,
+}
+
+/// Error while reading or writing the event file
+#[derive(Debug)]
+pub struct EventStoreError {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl std::fmt::Display for EventStoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+fn events_path() -> Option<PathBuf> {
+    user_data_dir().map(|dir| dir.join(EVENTS_FILE_NAME))
+}
+
+// All events, in the order they were added
+static USER_EVENTS: Mutex<Vec<UserEvent>> = Mutex::new(Vec::new());
+// Set when the event file couldn't be read, so saving won't overwrite it
+static STORE_BROKEN: AtomicBool = AtomicBool::new(false);
+
+/// Load the event file. A missing file is an empty store; a broken one is
+/// reported and left untouched (nothing is saved over it this session).
+pub fn load_events() -> Result<(), EventStoreError> {
+    let Some(path) = events_path() else {
+        return Ok(());
+    };
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => {
+            STORE_BROKEN.store(true, Ordering::Relaxed);
+            return Err(EventStoreError {
+                path,
+                message: e.to_string(),
+            });
+        }
+    };
+
+    let error = |message: String| EventStoreError {
+        path: path.clone(),
+        message,
+    };
+    let result = toml::from_str::<EventFile>(&text)
+        .map_err(|e| error(e.to_string()))
+        .and_then(|file| {
+            if file.format == EVENTS_FORMAT_VERSION {
+                Ok(file.events)
+            } else {
+                Err(error(format!(
+                    "unsupported format version {} (this version reads format {})",
+                    file.format, EVENTS_FORMAT_VERSION
+                )))
+            }
+        });
+    match result {
+        Ok(events) => {
+            if let Ok(mut store) = USER_EVENTS.lock() {
+                *store = events;
+            }
+            Ok(())
+        }
+        Err(e) => {
+            STORE_BROKEN.store(true, Ordering::Relaxed);
+            Err(e)
+        }
+    }
+}
+
+/// Write all events, through a temporary file so a crash can't truncate it
+fn write_events(events: &[UserEvent]) -> Result<(), EventStoreError> {
+    let Some(path) = events_path() else {
+        return Err(EventStoreError {
+            path: PathBuf::from(EVENTS_FILE_NAME),
+            message: "APPDATA is not set".to_string(),
+        });
+    };
+    let error = |message: String| EventStoreError {
+        path: path.clone(),
+        message,
+    };
+    if STORE_BROKEN.load(Ordering::Relaxed) {
+        return Err(error(
+            "the file could not be read at startup; fix or remove it first".to_string(),
+        ));
+    }
+
+    let file = EventFile {
+        format: EVENTS_FORMAT_VERSION,
+        events: events.to_vec(),
+    };
+    let text = toml::to_string(&file).map_err(|e| error(e.to_string()))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| error(e.to_string()))?;
+    }
+    let temp = path.with_extension("toml.tmp");
+    std::fs::write(&temp, text).map_err(|e| error(e.to_string()))?;
+    std::fs::rename(&temp, &path).map_err(|e| error(e.to_string()))
+}
+
+/// Add a new event (id 0) or replace the one with the same id; returns its id
+#[cfg(windows)]
+pub fn save_event(mut event: UserEvent) -> Result<u64, EventStoreError> {
+    let mut store = USER_EVENTS.lock().unwrap_or_else(|e| e.into_inner());
+    let mut events = store.clone();
+    if event.id == 0 {
+        event.id = events.iter().map(|e| e.id).max().unwrap_or(0) + 1;
+        events.push(event.clone());
+    } else if let Some(existing) = events.iter_mut().find(|e| e.id == event.id) {
+        // The editor doesn't show the import UID; keep it for re-imports
+        if event.uid.is_none() {
+            event.uid = existing.uid.clone();
+        }
+        *existing = event.clone();
+    } else {
+        events.push(event.clone());
+    }
+    write_events(&events)?;
+    *store = events;
+    Ok(event.id)
+}
+
+/// Events with imported ones added, dropping every event imported earlier
+/// with one of their UIDs; returns them and how many were replaced
+pub fn merge_imported(events: &[UserEvent], imported: Vec<UserEvent>) -> (Vec<UserEvent>, usize) {
+    let uids: HashSet<&str> = imported.iter().filter_map(|e| e.uid.as_deref()).collect();
+    let mut merged: Vec<UserEvent> = events
+        .iter()
+        .filter(|e| !e.uid.as_deref().is_some_and(|uid| uids.contains(uid)))
+        .cloned()
+        .collect();
+    let replaced = events.len() - merged.len();
+
+    let first_id = events.iter().map(|e| e.id).max().unwrap_or(0) + 1;
+    for (id, mut event) in (first_id..).zip(imported) {
+        event.id = id;
+        merged.push(event);
+    }
+    (merged, replaced)
+}
+
+/// Add imported events, replacing every event imported earlier with one of
+/// their UIDs; returns how many were added and how many replaced
+pub fn import_events(imported: Vec<UserEvent>) -> Result<(usize, usize), EventStoreError> {
+    let mut store = USER_EVENTS.lock().unwrap_or_else(|e| e.into_inner());
+    let added = imported.len();
+    let (events, replaced) = merge_imported(&store, imported);
+    write_events(&events)?;
+    *store = events;
+    Ok((added, replaced))
+}
+
+/// Remove an event
+#[cfg(windows)]
+pub fn delete_event(id: u64) -> Result<(), EventStoreError> {
+    let mut store = USER_EVENTS.lock().unwrap_or_else(|e| e.into_inner());
+    let events: Vec<UserEvent> = store.iter().filter(|e| e.id != id).cloned().collect();
+    write_events(&events)?;
+    *store = events;
+    Ok(())
+}
+
+/// Events between two day numbers (inclusive) with their days, sorted by day and time
+pub fn events_between(first: i64, last: i64) -> Vec<(i64, UserEvent)> {
+    let Ok(store) = USER_EVENTS.lock() else {
+        return Vec::new();
+    };
+    let mut events: Vec<(i64, UserEvent)> = store
+        .iter()
+        .flat_map(|event| {
+            event
+                .occurrences(first, last)
+                .into_iter()
+                .map(|days| (days, event.clone()))
+        })
+        .collect();
+    events.sort_by_key(|(days, event)| (*days, event.minute().unwrap_or(-1)));
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(calendar: EventCalendar, date: (i32, i32, i32), repeat: Repeat) -> UserEvent {
+        UserEvent {
+            title: "ইভেন্ট".to_string(),
+            calendar,
+            year: date.0,
+            month: date.1,
+            day: date.2,
+            repeat,
+            ..Default::default()
+        }
+    }
+
+    /// Occurrences from 2025 through 2027 as dates (month 1-12) of the
+    /// event's own calendar
+    fn occurrence_dates(event: &UserEvent) -> Vec<(i32, i32, i32)> {
+        event
+            .occurrences(days_from_civil(2025, 1, 1), days_from_civil(2027, 12, 31))
+            .into_iter()
+            .map(|days| {
+                let (year, month, day) = event.calendar.date_of(days);
+                (year, month + 1, day)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn yearly_events_keep_their_own_calendar_date() {
+        let boishakh = event(EventCalendar::Bangla, (1431, 1, 1), Repeat::Yearly);
+        let days: Vec<(i32, i32, i32)> = boishakh
+            .occurrences(days_from_civil(2025, 1, 1), days_from_civil(2027, 12, 31))
+            .into_iter()
+            .map(civil_from_days)
+            .collect();
+        assert_eq!(days, [(2025, 4, 14), (2026, 4, 14), (2027, 4, 14)]);
+
+        let leap_day = event(EventCalendar::Gregorian, (2024, 2, 29), Repeat::Yearly);
+        assert_eq!(
+            occurrence_dates(&leap_day),
+            [(2025, 2, 28), (2026, 2, 28), (2027, 2, 28)]
+        );
+
+        let ramadan = event(EventCalendar::Hijri, (1445, 9, 1), Repeat::Yearly);
+        assert_eq!(
+            occurrence_dates(&ramadan),
+            [(1446, 9, 1), (1447, 9, 1), (1448, 9, 1)]
+        );
+        // The Hijri year is shorter, so it moves through the Gregorian one
+        let starts =
+            ramadan.occurrences(days_from_civil(2025, 1, 1), days_from_civil(2027, 12, 31));
+        assert_eq!(starts[1] - starts[0], 354);
+    }
+
+    #[test]
+    fn monthly_events_fall_on_the_last_day_of_shorter_months() {
+        let bangla = event(EventCalendar::Bangla, (1431, 1, 31), Repeat::Monthly);
+        let days: Vec<i32> = occurrence_dates(&bangla)
+            .into_iter()
+            .filter(|&(year, _, _)| year == 1432)
+            .map(|(_, _, day)| day)
+            .collect();
+        // বৈশাখ-ভাদ্র have 31 days, the rest 30 (ফাল্গুন 31 in leap years)
+        assert_eq!(days, [31, 31, 31, 31, 31, 30, 30, 30, 30, 30, 30, 30]);
+
+        let gregorian = event(EventCalendar::Gregorian, (2025, 1, 31), Repeat::Monthly);
+        assert_eq!(
+            occurrence_dates(&gregorian)[..4],
+            [(2025, 1, 31), (2025, 2, 28), (2025, 3, 31), (2025, 4, 30)]
+        );
+
+        let hijri = event(EventCalendar::Hijri, (1446, 1, 30), Repeat::Monthly);
+        for (year, month, day) in occurrence_dates(&hijri) {
+            assert_eq!(day, hijri_month_days(year, month - 1).min(30));
+        }
+    }
+
+    #[test]
+    fn a_day_past_the_month_end_clamps_to_its_last_day() {
+        let event = event(EventCalendar::Bangla, (1432, 1, 31), Repeat::Monthly);
+        // আশ্বিন (month 6) has 30 days
+        assert_eq!(
+            event.day_in_month(1432, 5),
+            Some(EventCalendar::Bangla.to_days(1432, 5, 30))
+        );
+        assert_eq!(
+            event.day_in_month(1432, 4),
+            Some(EventCalendar::Bangla.to_days(1432, 4, 31))
+        );
+    }
+
+    #[test]
+    fn weekday_events_pick_the_nth_weekday_of_their_month() {
+        let weekday = |calendar, date, week, weekday| UserEvent {
+            week: Some(week),
+            weekday: Some(weekday),
+            ..event(calendar, date, Repeat::Weekday)
+        };
+
+        // Mother's Day: the second Sunday of May
+        let mothers_day = weekday(EventCalendar::Gregorian, (2025, 5, 11), 2, 0);
+        assert_eq!(
+            occurrence_dates(&mothers_day),
+            [(2025, 5, 11), (2026, 5, 10), (2027, 5, 9)]
+        );
+
+        // The last Friday of বৈশাখ
+        let last_friday = weekday(EventCalendar::Bangla, (1432, 1, 25), -1, 5);
+        let days =
+            last_friday.occurrences(days_from_civil(2025, 1, 1), days_from_civil(2027, 12, 31));
+        assert_eq!(days.len(), 3);
+        for days in days {
+            let date = bangla_date_from_days(days);
+            assert_eq!((date.month, weekday_of(days)), (0, 5));
+            assert!(date.day > 31 - 7);
+        }
+
+        // The first Friday of Ramadan
+        let first_friday = weekday(EventCalendar::Hijri, (1446, 9, 1), 1, 5);
+        let days =
+            first_friday.occurrences(days_from_civil(2025, 1, 1), days_from_civil(2027, 12, 31));
+        assert_eq!(days.len(), 3);
+        for days in days {
+            let (_, month, day) = EventCalendar::Hijri.date_of(days);
+            assert_eq!((month, weekday_of(days)), (8, 5));
+            assert!(day <= 7);
+        }
+    }
+}
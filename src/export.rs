@@ -0,0 +1,224 @@
+// export.rs - Exports and imports from the tray menu
+//
+// Each export asks where to save with the standard Save As dialog and covers
+// the current Bangla year (or month); the command line offers the same exports
+// for any range.
+
+use crate::calendar::{bangla_year_days, current_days, get_current_bangla_date};
+use crate::events::import_events;
+use crate::html::month_html;
+use crate::ics::{IcsOptions, calendar_ics};
+use crate::ics_import::{import_summary, parse_ics};
+use crate::image::{ImageFormat, month_image, widget_image};
+use crate::pdf::{month_pdf, year_pdf};
+use crate::punjika::refresh_calendar;
+use std::path::{Path, PathBuf};
+use windows::{
+    Win32::Foundation::*, Win32::UI::Controls::Dialogs::*, Win32::UI::WindowsAndMessaging::*,
+    core::*,
+};
+
+/// The current Bangla year at the selected location
+fn current_bangla_year() -> i32 {
+    get_current_bangla_date().year
+}
+
+/// Ask for a file to save to (or, with `save` false, to open). `filter` is a
+/// description and a pattern, e.g. ("iCalendar ফাইল", "*.ics").
+pub fn file_dialog(
+    hwnd: HWND,
+    default_name: &str,
+    filter: (&str, &str),
+    extension: &str,
+    save: bool,
+) -> Option<PathBuf> {
+    let mut file_name = [0u16; 1024];
+    for (slot, unit) in file_name
+        .iter_mut()
+        .zip(default_name.encode_utf16().take(1023))
+    {
+        *slot = unit;
+    }
+    // Description\0pattern\0\0
+    let filter_wide: Vec<u16> = format!("{}\0{}\0\0", filter.0, filter.1)
+        .encode_utf16()
+        .collect();
+    let extension_wide: Vec<u16> = extension.encode_utf16().chain(std::iter::once(0)).collect();
+
+    let mut ofn = OPENFILENAMEW {
+        lStructSize: std::mem::size_of::<OPENFILENAMEW>() as u32,
+        hwndOwner: hwnd,
+        lpstrFilter: PCWSTR(filter_wide.as_ptr()),
+        lpstrFile: PWSTR(file_name.as_mut_ptr()),
+        nMaxFile: file_name.len() as u32,
+        lpstrDefExt: PCWSTR(extension_wide.as_ptr()),
+        Flags: if save {
+            OFN_OVERWRITEPROMPT | OFN_PATHMUSTEXIST | OFN_NOCHANGEDIR
+        } else {
+            OFN_FILEMUSTEXIST | OFN_PATHMUSTEXIST | OFN_NOCHANGEDIR
+        },
+        ..Default::default()
+    };
+    let chosen = unsafe {
+        if save {
+            GetSaveFileNameW(&mut ofn)
+        } else {
+            GetOpenFileNameW(&mut ofn)
+        }
+    };
+    if !chosen.as_bool() {
+        return None;
+    }
+    let length = file_name.iter().position(|&c| c == 0).unwrap_or(0);
+    Some(PathBuf::from(String::from_utf16_lossy(
+        &file_name[..length],
+    )))
+}
+
+/// Write an export and tell the user how it went
+fn save_export(hwnd: HWND, path: &Path, contents: &[u8]) {
+    let (message, icon) = match std::fs::write(path, contents) {
+        Ok(()) => (
+            format!("সংরক্ষণ করা হয়েছে:\n{}", path.display()),
+            MB_ICONINFORMATION,
+        ),
+        Err(e) => (
+            format!("সংরক্ষণ করা যায়নি:\n{}\n\n{}", path.display(), e),
+            MB_ICONWARNING,
+        ),
+    };
+    unsafe {
+        let _ = MessageBoxW(
+            Some(hwnd),
+            &HSTRING::from(message),
+            w!("রপ্তানি"),
+            MB_OK | icon,
+        );
+    }
+}
+
+/// The current Bangla year with holidays and events as an .ics file
+pub fn export_ics_file(hwnd: HWND) {
+    let year = current_bangla_year();
+    let default_name = format!("bangla-calendar-{}.ics", year);
+    let Some(path) = file_dialog(
+        hwnd,
+        &default_name,
+        ("iCalendar ফাইল (*.ics)", "*.ics"),
+        "ics",
+        true,
+    ) else {
+        return;
+    };
+    let (first, last) = bangla_year_days(year);
+    let options = IcsOptions {
+        holidays: true,
+        events: true,
+    };
+    save_export(hwnd, &path, calendar_ics(first, last, options).as_bytes());
+}
+
+/// The current Bangla month, or the whole year, as a printable PDF
+pub fn export_pdf_file(hwnd: HWND, whole_year: bool) {
+    let today = get_current_bangla_date();
+    let (default_name, pdf) = if whole_year {
+        (
+            format!("bangla-calendar-{}.pdf", today.year),
+            year_pdf(today.year),
+        )
+    } else {
+        (
+            format!("bangla-calendar-{}-{:02}.pdf", today.year, today.month + 1),
+            month_pdf(today.year, today.month),
+        )
+    };
+    let Some(path) = file_dialog(
+        hwnd,
+        &default_name,
+        ("পিডিএফ ফাইল (*.pdf)", "*.pdf"),
+        "pdf",
+        true,
+    ) else {
+        return;
+    };
+    save_export(hwnd, &path, &pdf);
+}
+
+/// The punjika's current month, or today's widget, as a PNG or SVG image (by
+/// the extension chosen); PNGs are drawn at twice the size for print
+pub fn export_image_file(hwnd: HWND, widget: bool) {
+    let today = get_current_bangla_date();
+    let default_name = if widget {
+        "bangla-calendar-widget.png".to_string()
+    } else {
+        format!("bangla-calendar-{}-{:02}.png", today.year, today.month + 1)
+    };
+    let Some(path) = file_dialog(
+        hwnd,
+        &default_name,
+        ("ছবি (*.png, *.svg)", "*.png;*.svg"),
+        "png",
+        true,
+    ) else {
+        return;
+    };
+    let format = path
+        .extension()
+        .and_then(|extension| ImageFormat::from_name(extension.to_str()?))
+        .unwrap_or(ImageFormat::Png);
+    let scale = if format == ImageFormat::Png { 2.0 } else { 1.0 };
+    let image = if widget {
+        widget_image(format, scale)
+    } else {
+        month_image(today.year, today.month, format, scale)
+    };
+    save_export(hwnd, &path, &image);
+}
+
+/// The current Bangla month as a self-contained web page
+pub fn export_html_file(hwnd: HWND) {
+    let today = get_current_bangla_date();
+    let default_name = format!("bangla-calendar-{}-{:02}.html", today.year, today.month + 1);
+    let Some(path) = file_dialog(
+        hwnd,
+        &default_name,
+        ("ওয়েব পেজ (*.html)", "*.html;*.htm"),
+        "html",
+        true,
+    ) else {
+        return;
+    };
+    save_export(hwnd, &path, month_html(today.year, today.month).as_bytes());
+}
+
+/// Add the events of an .ics file to the personal events
+pub fn import_ics_file(hwnd: HWND) {
+    let Some(path) = file_dialog(hwnd, "", ("iCalendar ফাইল (*.ics)", "*.ics"), "ics", false)
+    else {
+        return;
+    };
+    let result = std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|text| parse_ics(&text, current_days()))
+        .and_then(|import| {
+            import_events(import.events)
+                .map(|(added, replaced)| import_summary(added, replaced, import.skipped))
+                .map_err(|e| e.to_string())
+        });
+    let (message, icon) = match result {
+        Ok(summary) => (summary, MB_ICONINFORMATION),
+        Err(e) => (
+            format!("আমদানি করা যায়নি:\n{}\n\n{}", path.display(), e),
+            MB_ICONWARNING,
+        ),
+    };
+    refresh_calendar();
+    unsafe {
+        let _ = MessageBoxW(
+            Some(hwnd),
+            &HSTRING::from(message),
+            w!("আমদানি"),
+            MB_OK | icon,
+        );
+    }
+}
@@ -0,0 +1,240 @@
+// feed_server.rs - Optional HTTP server on localhost for calendar clients and dashboards
+//
+// Off by default. When turned on from the tray (or run with `serve`) it
+// listens on 127.0.0.1 only and serves:
+//   /calendar.ics  a rolling feed: Bangla dates, holidays and your events
+//   /today.json    today's Bangla date and holidays
+// Requests whose Host isn't localhost are refused, so web pages can't reach
+// the feed through DNS rebinding; only today.json (no personal events) may be
+// read by other origins.
+
+use crate::calendar::{bangla_date_from_days, civil_from_days, current_days};
+use crate::holidays::holidays_between;
+use crate::ics::{IcsOptions, calendar_ics};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+const FEED_PAST_DAYS: i64 = 30;
+const FEED_FUTURE_DAYS: i64 = 365;
+const FEED_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const FEED_MAX_HEADER_LINES: usize = 100;
+
+// Port of the running server (0 = stopped)
+#[cfg(windows)]
+static SERVER_PORT: AtomicU32 = AtomicU32::new(0);
+// Bumped to tell the server thread to stop
+static SERVER_GENERATION: AtomicU32 = AtomicU32::new(0);
+
+/// Escape a JSON string value
+pub fn json_string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Today's Bangla date at the selected location
+fn today_json() -> String {
+    let today = current_days();
+    let date = bangla_date_from_days(today);
+    let (year, month, day) = civil_from_days(today);
+    let holidays: Vec<String> = holidays_between(today, today)
+        .iter()
+        .map(|h| json_string(&h.name))
+        .collect();
+    format!(
+        "{{\"date\":\"{:04}-{:02}-{:02}\",\"bangla\":{{\"day\":{},\"month\":{},\"month_name\":{},\"year\":{},\"weekday\":{},\"season\":{}}},\"line1\":{},\"line2\":{},\"line3\":{},\"holidays\":[{}]}}\n",
+        year,
+        month,
+        day,
+        date.day,
+        date.month + 1,
+        json_string(date.get_month_name()),
+        date.year,
+        json_string(date.get_weekday_name()),
+        json_string(date.get_season()),
+        json_string(&date.format_line1()),
+        json_string(&date.format_line2()),
+        json_string(&date.format_line3()),
+        holidays.join(",")
+    )
+}
+
+fn index_text(port: u16) -> String {
+    format!(
+        "বাংলা ক্যালেন্ডার\n\nhttp://127.0.0.1:{port}/calendar.ics\nhttp://127.0.0.1:{port}/today.json\n"
+    )
+}
+
+fn write_response(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    extra_headers: &str,
+    body: &str,
+    head_only: bool,
+) {
+    let header = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-cache\r\nConnection: close\r\n{}\r\n",
+        status,
+        content_type,
+        body.len(),
+        extra_headers
+    );
+    let _ = stream.write_all(header.as_bytes());
+    if !head_only {
+        let _ = stream.write_all(body.as_bytes());
+    }
+}
+
+/// Answer one request
+fn handle_connection(mut stream: TcpStream, port: u16) {
+    let _ = stream.set_read_timeout(Some(FEED_REQUEST_TIMEOUT));
+    let Ok(reader_stream) = stream.try_clone() else {
+        return;
+    };
+    let mut lines = BufReader::new(reader_stream).lines();
+    let Some(Ok(request_line)) = lines.next() else {
+        return;
+    };
+    let mut host = String::new();
+    for line in lines.take(FEED_MAX_HEADER_LINES) {
+        let Ok(line) = line else {
+            return;
+        };
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.trim().eq_ignore_ascii_case("host")
+        {
+            host = value.trim().to_ascii_lowercase();
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let path = target.split('?').next().unwrap_or("");
+    let head_only = method == "HEAD";
+    let host_name = host
+        .rsplit_once(':')
+        .map_or(host.as_str(), |(name, _)| name);
+
+    if method != "GET" && method != "HEAD" {
+        write_response(
+            &mut stream,
+            "405 Method Not Allowed",
+            "text/plain; charset=utf-8",
+            "Allow: GET, HEAD\r\n",
+            "",
+            head_only,
+        );
+    } else if !matches!(host_name, "127.0.0.1" | "localhost") {
+        write_response(
+            &mut stream,
+            "403 Forbidden",
+            "text/plain; charset=utf-8",
+            "",
+            "",
+            head_only,
+        );
+    } else {
+        match path {
+            "/" => write_response(
+                &mut stream,
+                "200 OK",
+                "text/plain; charset=utf-8",
+                "",
+                &index_text(port),
+                head_only,
+            ),
+            "/calendar.ics" => {
+                let today = current_days();
+                let options = IcsOptions {
+                    holidays: true,
+                    events: true,
+                };
+                let ics = calendar_ics(today - FEED_PAST_DAYS, today + FEED_FUTURE_DAYS, options);
+                write_response(
+                    &mut stream,
+                    "200 OK",
+                    "text/calendar; charset=utf-8",
+                    "",
+                    &ics,
+                    head_only,
+                );
+            }
+            "/today.json" => write_response(
+                &mut stream,
+                "200 OK",
+                "application/json; charset=utf-8",
+                "Access-Control-Allow-Origin: *\r\n",
+                &today_json(),
+                head_only,
+            ),
+            _ => write_response(
+                &mut stream,
+                "404 Not Found",
+                "text/plain; charset=utf-8",
+                "",
+                "",
+                head_only,
+            ),
+        }
+    }
+}
+
+/// Answer requests until the generation changes
+fn serve(listener: TcpListener, port: u16, generation: u32) {
+    for stream in listener.incoming() {
+        if SERVER_GENERATION.load(Ordering::Relaxed) != generation {
+            break;
+        }
+        if let Ok(stream) = stream {
+            handle_connection(stream, port);
+        }
+    }
+}
+
+/// Start the server in the background (restarting it on a new port)
+#[cfg(windows)]
+pub fn start_feed_server(port: u16) -> std::io::Result<()> {
+    if SERVER_PORT.load(Ordering::Relaxed) == port as u32 {
+        return Ok(());
+    }
+    stop_feed_server();
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+    let generation = SERVER_GENERATION.load(Ordering::Relaxed);
+    SERVER_PORT.store(port as u32, Ordering::Relaxed);
+    std::thread::spawn(move || serve(listener, port, generation));
+    Ok(())
+}
+
+/// Stop the background server, if it is running
+#[cfg(windows)]
+pub fn stop_feed_server() {
+    let port = SERVER_PORT.swap(0, Ordering::Relaxed);
+    if port != 0 {
+        SERVER_GENERATION.fetch_add(1, Ordering::Relaxed);
+        // Wake the thread from accept() so it sees the new generation
+        let _ = TcpStream::connect((Ipv4Addr::LOCALHOST, port as u16));
+    }
+}
+
+/// Run the server on this thread until the process ends (for `serve`)
+pub fn run_feed_server(port: u16) -> std::io::Result<()> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+    serve(listener, port, SERVER_GENERATION.load(Ordering::Relaxed));
+    Ok(())
+}
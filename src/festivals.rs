@@ -0,0 +1,299 @@
+// festivals.rs - Hindu festivals on the Bengali lunisolar calendar
+//
+// Lunar months are amanta (new moon to new moon) and take the name of the
+// sidereal sign the Sun is in when the month begins (Sun in Meena -> চৈত্র).
+// A month without a sankranti is মলমাস (adhika) and has no festivals.
+
+use crate::astronomy::*;
+use crate::calendar::days_from_civil;
+use crate::constants::BD_UTC_OFFSET_MINUTES;
+use crate::holidays::HolidayKind;
+use std::sync::Mutex;
+
+// Festival days are fixed for Dhaka, in Bangladesh time
+const REFERENCE_LATITUDE: f64 = 23.8103;
+const REFERENCE_LONGITUDE: f64 = 90.4125;
+const REFERENCE_UTC_OFFSET_MINUTES: i32 = BD_UTC_OFFSET_MINUTES;
+
+/// The moment of the civil day at which the festival's tithi must prevail
+#[derive(Debug, Clone, Copy)]
+enum ObservedAt {
+    Sunrise,
+    Midnight, // নিশীথ - the night that ends the civil day
+}
+
+/// A festival tied to a tithi of a lunar month
+pub struct Festival {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub kind: HolidayKind,
+    month: i32, // 0 = বৈশাখ … 11 = চৈত্র
+    tithi: i32, // 0-14 শুক্লপক্ষ প্রতিপদ…পূর্ণিমা, 15-29 কৃষ্ণপক্ষ প্রতিপদ…অমাবস্যা
+    observed_at: ObservedAt,
+}
+
+pub const FESTIVALS: &[Festival] = &[
+    Festival {
+        id: "saraswati-puja",
+        name: "সরস্বতী পূজা",
+        kind: HolidayKind::Optional,
+        month: 9, // মাঘ শুক্লা পঞ্চমী
+        tithi: 4,
+        observed_at: ObservedAt::Sunrise,
+    },
+    Festival {
+        id: "dol-purnima",
+        name: "দোলযাত্রা",
+        kind: HolidayKind::Optional,
+        month: 10, // ফাল্গুনী পূর্ণিমা
+        tithi: 14,
+        observed_at: ObservedAt::Sunrise,
+    },
+    Festival {
+        id: "buddha-purnima",
+        name: "বুদ্ধ পূর্ণিমা",
+        kind: HolidayKind::Public,
+        month: 0, // বৈশাখী পূর্ণিমা
+        tithi: 14,
+        observed_at: ObservedAt::Sunrise,
+    },
+    Festival {
+        id: "janmashtami",
+        name: "জন্মাষ্টমী",
+        kind: HolidayKind::Public,
+        month: 3, // শ্রাবণ কৃষ্ণা অষ্টমী (amanta)
+        tithi: 22,
+        observed_at: ObservedAt::Midnight,
+    },
+    Festival {
+        id: "durga-sashthi",
+        name: "দুর্গাষষ্ঠী",
+        kind: HolidayKind::Observance,
+        month: 5, // আশ্বিন শুক্লা ষষ্ঠী
+        tithi: 5,
+        observed_at: ObservedAt::Sunrise,
+    },
+    Festival {
+        id: "durga-saptami",
+        name: "মহাসপ্তমী",
+        kind: HolidayKind::Observance,
+        month: 5,
+        tithi: 6,
+        observed_at: ObservedAt::Sunrise,
+    },
+    Festival {
+        id: "durga-ashtami",
+        name: "মহাষ্টমী",
+        kind: HolidayKind::Optional,
+        month: 5,
+        tithi: 7,
+        observed_at: ObservedAt::Sunrise,
+    },
+    Festival {
+        id: "durga-navami",
+        name: "মহানবমী",
+        kind: HolidayKind::Optional,
+        month: 5,
+        tithi: 8,
+        observed_at: ObservedAt::Sunrise,
+    },
+    Festival {
+        id: "bijoya-dashami",
+        name: "বিজয়া দশমী",
+        kind: HolidayKind::Public,
+        month: 5,
+        tithi: 9,
+        observed_at: ObservedAt::Sunrise,
+    },
+    Festival {
+        id: "lakshmi-puja",
+        name: "কোজাগরী লক্ষ্মীপূজা",
+        kind: HolidayKind::Observance,
+        month: 5, // আশ্বিন পূর্ণিমা
+        tithi: 14,
+        observed_at: ObservedAt::Midnight,
+    },
+    Festival {
+        id: "kali-puja",
+        name: "কালীপূজা",
+        kind: HolidayKind::Optional,
+        month: 5, // আশ্বিন অমাবস্যা (amanta), দীপান্বিতা
+        tithi: 29,
+        observed_at: ObservedAt::Midnight,
+    },
+];
+
+/// One lunar month, new moon to new moon
+struct LunarMonth {
+    start: f64,
+    end: f64,
+    month: i32,
+    is_adhika: bool,
+}
+
+/// Julian Day (UT) of local midnight starting a day number
+fn local_day_start(days: i64) -> f64 {
+    jd_from_days(days) - REFERENCE_UTC_OFFSET_MINUTES as f64 / 1440.0
+}
+
+/// Local day number containing a moment
+fn local_day_of(jd: f64) -> i64 {
+    (days_from_jd(jd) + REFERENCE_UTC_OFFSET_MINUTES as f64 / 1440.0).floor() as i64
+}
+
+fn anchor(days: i64, observed_at: ObservedAt) -> f64 {
+    let day_start = local_day_start(days);
+    match observed_at {
+        ObservedAt::Sunrise => {
+            sunrise(day_start, REFERENCE_LATITUDE, REFERENCE_LONGITUDE).unwrap_or(day_start + 0.25)
+        }
+        ObservedAt::Midnight => local_day_start(days + 1),
+    }
+}
+
+/// The civil day on which a tithi lasting from `start` to `end` is observed.
+/// A tithi covering the anchor on two days (বৃদ্ধি তিথি) is observed on the
+/// second, which no new tithi claims, so the festivals of consecutive tithis
+/// stay on consecutive days. A tithi that never covers the anchor (ক্ষয় তিথি)
+/// is kept on the day it begins.
+fn observed_day(start: f64, end: f64, observed_at: ObservedAt) -> i64 {
+    (local_day_of(start) - 1..=local_day_of(end) + 1)
+        .rev()
+        .find(|&day| {
+            let moment = anchor(day, observed_at);
+            moment >= start && moment < end
+        })
+        .unwrap_or_else(|| local_day_of(start))
+}
+
+/// Lunar months that start between two moments
+fn lunar_months_between(first: f64, last: f64) -> Vec<LunarMonth> {
+    let mut new_moons = vec![new_moon_before(first)];
+    while new_moons.len() < 2 || new_moons[new_moons.len() - 2] <= last {
+        let previous = new_moons[new_moons.len() - 1];
+        new_moons.push(find_elongation(0.0, previous + SYNODIC_MONTH));
+    }
+
+    let sign_at = |jd: f64| (sidereal_sun_longitude(jd) / 30.0).floor() as i32;
+    new_moons
+        .windows(2)
+        .map(|pair| LunarMonth {
+            start: pair[0],
+            end: pair[1],
+            month: sign_at(pair[0]),
+            is_adhika: sign_at(pair[0]) == sign_at(pair[1]),
+        })
+        .collect()
+}
+
+// (festival index, day number)
+type FestivalDay = (usize, i64);
+
+// Festival days per Gregorian year
+static FESTIVAL_CACHE: Mutex<Vec<(i32, Vec<FestivalDay>)>> = Mutex::new(Vec::new());
+
+fn calculate_festival_days(year: i32) -> Vec<FestivalDay> {
+    let first_day = days_from_civil(year, 1, 1);
+    let last_day = days_from_civil(year, 12, 31);
+    // Start a month early: a tithi late in December's month can fall in January
+    let months = lunar_months_between(
+        local_day_start(first_day) - SYNODIC_MONTH,
+        local_day_start(last_day + 1),
+    );
+
+    let mut result = Vec::new();
+    for month in months.iter().filter(|m| !m.is_adhika) {
+        for (index, festival) in FESTIVALS.iter().enumerate() {
+            if festival.month != month.month {
+                continue;
+            }
+            let step = SYNODIC_MONTH / 30.0;
+            let guess = month.start + festival.tithi as f64 * step;
+            let start = find_elongation(festival.tithi as f64 * 12.0, guess);
+            let end = if festival.tithi == 29 {
+                month.end
+            } else {
+                find_elongation((festival.tithi + 1) as f64 * 12.0, guess + step)
+            };
+
+            let day = observed_day(start, end, festival.observed_at);
+            if (first_day..=last_day).contains(&day) {
+                result.push((index, day));
+            }
+        }
+    }
+    result.sort_by_key(|&(_, day)| day);
+    result
+}
+
+/// Festivals falling in a Gregorian year, with their day numbers
+pub fn festival_days(year: i32) -> Vec<(&'static Festival, i64)> {
+    let mut cache = FESTIVAL_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let days = match cache.iter().find(|(y, _)| *y == year) {
+        Some((_, days)) => days.clone(),
+        None => {
+            let days = calculate_festival_days(year);
+            cache.push((year, days.clone()));
+            days
+        }
+    };
+    days.into_iter()
+        .map(|(index, day)| (&FESTIVALS[index], day))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day_of(year: i32, id: &str) -> Option<(i32, i32, i32)> {
+        festival_days(year)
+            .into_iter()
+            .find(|(festival, _)| festival.id == id)
+            .map(|(_, days)| crate::calendar::civil_from_days(days))
+    }
+
+    #[test]
+    fn festivals_fall_on_their_published_dates() {
+        assert_eq!(day_of(2025, "durga-sashthi"), Some((2025, 9, 28)));
+        assert_eq!(day_of(2025, "bijoya-dashami"), Some((2025, 10, 2)));
+        assert_eq!(day_of(2025, "kali-puja"), Some((2025, 10, 20)));
+        assert_eq!(day_of(2025, "janmashtami"), Some((2025, 8, 16)));
+        assert_eq!(day_of(2024, "kali-puja"), Some((2024, 10, 31)));
+    }
+
+    #[test]
+    fn a_tithi_at_two_sunrises_is_observed_on_the_second() {
+        // ষষ্ঠী 2026 runs from before sunrise on 16 October to after sunrise
+        // on the 17th; সপ্তমী follows on the 18th
+        let ids = [
+            "durga-sashthi",
+            "durga-saptami",
+            "durga-ashtami",
+            "durga-navami",
+            "bijoya-dashami",
+        ];
+        let days: Vec<_> = ids.iter().filter_map(|id| day_of(2026, id)).collect();
+        assert_eq!(
+            days,
+            [
+                (2026, 10, 17),
+                (2026, 10, 18),
+                (2026, 10, 19),
+                (2026, 10, 20),
+                (2026, 10, 21)
+            ]
+        );
+        // The same at midnight: জন্মাষ্টমী 2021 was on 30 August
+        assert_eq!(day_of(2021, "janmashtami"), Some((2021, 8, 30)));
+    }
+
+    #[test]
+    fn a_tithi_without_a_sunrise_is_kept_on_the_day_it_begins() {
+        let (start, end) = (local_day_start(100) + 0.4, local_day_start(100) + 0.9);
+        assert_eq!(observed_day(start, end, ObservedAt::Sunrise), 100);
+        // Across two sunrises it belongs to the second
+        let (start, end) = (local_day_start(100) + 0.1, local_day_start(101) + 0.3);
+        assert_eq!(observed_day(start, end, ObservedAt::Sunrise), 101);
+    }
+}
@@ -1,12 +1,10 @@
 // fonts.rs - Cached font management for memory efficiency
 
-use crate::constants::MENU_FONT_SIZE;
+use crate::constants::*;
+use crate::shaping::EKUSH_FONT_DATA;
 use std::sync::atomic::{AtomicPtr, Ordering};
 use windows::{Win32::Graphics::Gdi::*, core::*};
 
-// Embed the Ekush font directly into the executable
-const EKUSH_FONT_DATA: &[u8] = include_bytes!("../fonts/Ekush-Regular.ttf");
-
 // Thread-safe font handles using AtomicPtr
 static MENU_FONT_PTR: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(std::ptr::null_mut());
 
@@ -110,34 +108,37 @@ pub fn install_fonts() {
 
             // Widget fonts (cached)
             FONT_LINE1_PTR.store(
-                create_ekush_font(26, FW_SEMIBOLD.0 as i32).0,
+                create_ekush_font(WIDGET_LINE1_FONT_SIZE, FW_SEMIBOLD.0 as i32).0,
                 Ordering::Relaxed,
             );
             FONT_LINE2_PTR.store(
-                create_ekush_font(22, FW_NORMAL.0 as i32).0,
+                create_ekush_font(WIDGET_LINE2_FONT_SIZE, FW_NORMAL.0 as i32).0,
                 Ordering::Relaxed,
             );
             FONT_LINE3_PTR.store(
-                create_ekush_font(18, FW_NORMAL.0 as i32).0,
+                create_ekush_font(WIDGET_LINE3_FONT_SIZE, FW_NORMAL.0 as i32).0,
                 Ordering::Relaxed,
             );
 
             // Calendar fonts (cached)
-            CAL_HEADER_FONT_PTR.store(create_ekush_font(22, FW_BOLD.0 as i32).0, Ordering::Relaxed);
+            CAL_HEADER_FONT_PTR.store(
+                create_ekush_font(CAL_HEADER_FONT_SIZE, FW_BOLD.0 as i32).0,
+                Ordering::Relaxed,
+            );
             CAL_NAV_FONT_PTR.store(
-                create_ekush_font(20, FW_NORMAL.0 as i32).0,
+                create_ekush_font(CAL_NAV_FONT_SIZE, FW_NORMAL.0 as i32).0,
                 Ordering::Relaxed,
             );
             CAL_WEEKDAY_FONT_PTR.store(
-                create_ekush_font(14, FW_SEMIBOLD.0 as i32).0,
+                create_ekush_font(CAL_WEEKDAY_FONT_SIZE, FW_SEMIBOLD.0 as i32).0,
                 Ordering::Relaxed,
             );
             CAL_DATE_FONT_PTR.store(
-                create_ekush_font(18, FW_NORMAL.0 as i32).0,
+                create_ekush_font(CAL_DATE_FONT_SIZE, FW_NORMAL.0 as i32).0,
                 Ordering::Relaxed,
             );
             CAL_SUB_FONT_PTR.store(
-                create_ekush_font(14, FW_NORMAL.0 as i32).0,
+                create_ekush_font(CAL_SUB_FONT_SIZE, FW_NORMAL.0 as i32).0,
                 Ordering::Relaxed,
             );
         }
@@ -0,0 +1,65 @@
+// hijri.rs - Hijri (Islamic) calendar calculation
+
+use crate::calendar::{civil_from_days, days_from_civil};
+
+// Month index of Ramadan (0 = Muharram)
+#[cfg(windows)]
+pub const RAMADAN: i32 = 8;
+
+// Day count (days since 1970-01-01) of 1 Muharram 1 AH, civil epoch (16 July 622 Julian)
+const HIJRI_EPOCH_DAYS: i64 = -492148;
+
+/// Day count of a Hijri date using the tabular (arithmetic) calendar.
+/// Month is 0-11 (0 = Muharram).
+pub fn hijri_to_days(year: i32, month: i32, day: i32) -> i64 {
+    let year = year as i64;
+    let month = month as i64;
+    // ceil(29.5 * month) without floats
+    let month_offset = (59 * month + 1) / 2;
+    HIJRI_EPOCH_DAYS + day as i64 - 1 + month_offset + (year - 1) * 354 + (3 + 11 * year) / 30
+}
+
+/// Convert a Gregorian date to the tabular Hijri calendar.
+/// Returns (year, month 0-11, day).
+pub fn gregorian_to_hijri(year: i32, month: i32, day: i32) -> (i32, i32, i32) {
+    let days = days_from_civil(year, month, day);
+
+    let mut hijri_year = ((30 * (days - HIJRI_EPOCH_DAYS) + 10646) / 10631) as i32;
+    if hijri_to_days(hijri_year, 0, 1) > days {
+        hijri_year -= 1;
+    }
+
+    let mut hijri_month = 11;
+    while hijri_month > 0 && hijri_to_days(hijri_year, hijri_month, 1) > days {
+        hijri_month -= 1;
+    }
+
+    let hijri_day = (days - hijri_to_days(hijri_year, hijri_month, 1)) as i32 + 1;
+
+    (hijri_year, hijri_month, hijri_day)
+}
+
+/// Days in a tabular Hijri month (29 or 30). Month is 0-11.
+pub fn hijri_month_days(year: i32, month: i32) -> i32 {
+    let next = if month == 11 {
+        hijri_to_days(year + 1, 0, 1)
+    } else {
+        hijri_to_days(year, month + 1, 1)
+    };
+    (next - hijri_to_days(year, month, 1)) as i32
+}
+
+/// Convert a tabular Hijri date to Gregorian (year, month, day). Month is 0-11.
+pub fn hijri_to_gregorian(year: i32, month: i32, day: i32) -> (i32, i32, i32) {
+    civil_from_days(hijri_to_days(year, month, day))
+}
+
+/// First and last day numbers of Ramadan in a Hijri year (tabular; the
+/// observed month can start a day later, depending on the moon sighting)
+#[cfg(windows)]
+pub fn ramadan_days(year: i32) -> (i64, i64) {
+    (
+        hijri_to_days(year, RAMADAN, 1),
+        hijri_to_days(year, RAMADAN + 1, 1) - 1,
+    )
+}
@@ -0,0 +1,602 @@
+// holidays.rs - Holiday database: built-in rules plus loadable data packs
+//
+// Built-in rules and the lunisolar festivals give calculated dates. Data packs
+// (TOML files in a `holidays` folder beside the executable or in
+// %APPDATA%\BanglaCalendar) carry the officially announced dates for one
+// Gregorian year. Any id declared in a pack replaces the built-in rule with the
+// same id for that year.
+
+use crate::calendar::{civil_from_days, days_from_civil, gregorian_from_bangla};
+use crate::festivals::festival_days;
+use crate::hijri::{gregorian_to_hijri, hijri_to_gregorian};
+use crate::paths::{exe_dir, user_data_dir};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use toml::Spanned;
+use toml::value::Datetime;
+
+// Data pack format version understood by this build
+const PACK_FORMAT_VERSION: u32 = 1;
+const PACK_DIR_NAME: &str = "holidays";
+const PACK_MAX_SPAN_DAYS: i32 = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HolidayKind {
+    #[default]
+    Public, // সাধারণ ছুটি
+    Optional,   // ঐচ্ছিক ছুটি
+    Observance, // দিবস (no day off)
+}
+
+/// A holiday on one specific day
+#[derive(Debug, Clone)]
+pub struct Holiday {
+    pub id: String,
+    pub name: String,
+    pub kind: HolidayKind,
+    pub days: i64, // days since 1970-01-01
+}
+
+/// How a built-in holiday's date is calculated
+enum HolidayRule {
+    Gregorian { month: i32, day: i32 },
+    Bangla { month: i32, day: i32 }, // month 0-11
+    Hijri { month: i32, day: i32 },  // month 0-11, tabular calendar
+}
+
+struct BuiltinHoliday {
+    id: &'static str,
+    name: &'static str,
+    kind: HolidayKind,
+    rule: HolidayRule,
+}
+
+const BUILTIN_HOLIDAYS: &[BuiltinHoliday] = &[
+    BuiltinHoliday {
+        id: "shaheed-dibosh",
+        name: "শহীদ দিবস",
+        kind: HolidayKind::Public,
+        rule: HolidayRule::Gregorian { month: 2, day: 21 },
+    },
+    BuiltinHoliday {
+        id: "independence-day",
+        name: "স্বাধীনতা দিবস",
+        kind: HolidayKind::Public,
+        rule: HolidayRule::Gregorian { month: 3, day: 26 },
+    },
+    BuiltinHoliday {
+        id: "pohela-boishakh",
+        name: "পহেলা বৈশাখ",
+        kind: HolidayKind::Public,
+        rule: HolidayRule::Bangla { month: 0, day: 1 },
+    },
+    BuiltinHoliday {
+        id: "may-day",
+        name: "মে দিবস",
+        kind: HolidayKind::Public,
+        rule: HolidayRule::Gregorian { month: 5, day: 1 },
+    },
+    BuiltinHoliday {
+        id: "rabindra-jayanti",
+        name: "রবীন্দ্র জয়ন্তী",
+        kind: HolidayKind::Observance,
+        rule: HolidayRule::Bangla { month: 0, day: 25 },
+    },
+    BuiltinHoliday {
+        id: "nazrul-jayanti",
+        name: "নজরুল জয়ন্তী",
+        kind: HolidayKind::Observance,
+        rule: HolidayRule::Bangla { month: 1, day: 11 },
+    },
+    BuiltinHoliday {
+        id: "victory-day",
+        name: "বিজয় দিবস",
+        kind: HolidayKind::Public,
+        rule: HolidayRule::Gregorian { month: 12, day: 16 },
+    },
+    BuiltinHoliday {
+        id: "christmas",
+        name: "বড়দিন",
+        kind: HolidayKind::Public,
+        rule: HolidayRule::Gregorian { month: 12, day: 25 },
+    },
+    BuiltinHoliday {
+        id: "ashura",
+        name: "আশুরা",
+        kind: HolidayKind::Public,
+        rule: HolidayRule::Hijri { month: 0, day: 10 },
+    },
+    BuiltinHoliday {
+        id: "eid-e-miladunnabi",
+        name: "ঈদে মিলাদুন্নবী",
+        kind: HolidayKind::Public,
+        rule: HolidayRule::Hijri { month: 2, day: 12 },
+    },
+    BuiltinHoliday {
+        id: "shab-e-barat",
+        name: "শবে বরাত",
+        kind: HolidayKind::Public,
+        rule: HolidayRule::Hijri { month: 7, day: 15 },
+    },
+    BuiltinHoliday {
+        id: "shab-e-qadr",
+        name: "শবে কদর",
+        kind: HolidayKind::Public,
+        rule: HolidayRule::Hijri { month: 8, day: 27 },
+    },
+    BuiltinHoliday {
+        id: "eid-ul-fitr",
+        name: "ঈদুল ফিতর",
+        kind: HolidayKind::Public,
+        rule: HolidayRule::Hijri { month: 9, day: 1 },
+    },
+    BuiltinHoliday {
+        id: "eid-ul-adha",
+        name: "ঈদুল আজহা",
+        kind: HolidayKind::Public,
+        rule: HolidayRule::Hijri { month: 11, day: 10 },
+    },
+];
+
+impl BuiltinHoliday {
+    /// Calculated dates (as day numbers) of this holiday within a Gregorian year
+    fn occurrences(&self, year: i32) -> Vec<i64> {
+        let first = days_from_civil(year, 1, 1);
+        let last = days_from_civil(year, 12, 31);
+
+        let candidates: Vec<(i32, i32, i32)> = match self.rule {
+            HolidayRule::Gregorian { month, day } => vec![(year, month, day)],
+            HolidayRule::Bangla { month, day } => (year - 594..=year - 593)
+                .map(|bangla_year| gregorian_from_bangla(bangla_year, month, day))
+                .collect(),
+            HolidayRule::Hijri { month, day } => {
+                let first_hijri_year = gregorian_to_hijri(year, 1, 1).0;
+                let last_hijri_year = gregorian_to_hijri(year, 12, 31).0;
+                (first_hijri_year..=last_hijri_year)
+                    .map(|hijri_year| hijri_to_gregorian(hijri_year, month, day))
+                    .collect()
+            }
+        };
+
+        candidates
+            .into_iter()
+            .map(|(y, m, d)| days_from_civil(y, m, d))
+            .filter(|days| (first..=last).contains(days))
+            .collect()
+    }
+
+    fn to_holiday(&self, days: i64) -> Holiday {
+        Holiday {
+            id: self.id.to_string(),
+            name: self.name.to_string(),
+            kind: self.kind,
+            days,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Data packs
+// ---------------------------------------------------------------------------
+
+/// On-disk layout of a data pack (format = 1)
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PackFile {
+    format: Spanned<u32>,
+    name: Spanned<String>,
+    year: Spanned<i32>,
+    #[serde(default, rename = "holiday")]
+    holidays: Vec<PackEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PackEntry {
+    id: Spanned<String>,
+    name: Spanned<String>,
+    date: Spanned<Datetime>,
+    #[serde(default)]
+    days: Option<Spanned<i32>>,
+    #[serde(default)]
+    kind: HolidayKind,
+}
+
+/// A validated data pack for one Gregorian year
+struct DataPack {
+    year: i32,
+    holidays: Vec<Holiday>,
+}
+
+/// Error while loading a data pack
+#[derive(Debug)]
+pub struct PackError {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl std::fmt::Display for PackError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+/// 1-based line number of a byte offset
+fn line_of(text: &str, offset: usize) -> usize {
+    text[..offset.min(text.len())].matches('\n').count() + 1
+}
+
+/// Parse and validate one data pack
+fn parse_pack(path: &Path, text: &str) -> Result<DataPack, PackError> {
+    let error_at = |offset: usize, message: String| PackError {
+        path: path.to_path_buf(),
+        message: format!("line {}: {}", line_of(text, offset), message),
+    };
+
+    let file: PackFile = toml::from_str(text).map_err(|e| PackError {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+
+    if *file.format.get_ref() != PACK_FORMAT_VERSION {
+        return Err(error_at(
+            file.format.span().start,
+            format!(
+                "unsupported format version {} (this version reads format {})",
+                file.format.get_ref(),
+                PACK_FORMAT_VERSION
+            ),
+        ));
+    }
+    if file.name.get_ref().trim().is_empty() {
+        return Err(error_at(
+            file.name.span().start,
+            "pack name must not be empty".to_string(),
+        ));
+    }
+    let year = *file.year.get_ref();
+    if !(1900..=2200).contains(&year) {
+        return Err(error_at(
+            file.year.span().start,
+            format!("year {} is out of range (1900-2200)", year),
+        ));
+    }
+
+    let mut holidays = Vec::new();
+    let mut seen: Vec<(String, i64)> = Vec::new();
+
+    for entry in &file.holidays {
+        let id = entry.id.get_ref();
+        if id.is_empty()
+            || !id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        {
+            return Err(error_at(
+                entry.id.span().start,
+                format!(
+                    "id \"{}\" must use only lowercase letters, digits and '-'",
+                    id
+                ),
+            ));
+        }
+        if entry.name.get_ref().trim().is_empty() {
+            return Err(error_at(
+                entry.name.span().start,
+                format!("holiday \"{}\" has an empty name", id),
+            ));
+        }
+
+        let date_offset = entry.date.span().start;
+        let datetime = entry.date.get_ref();
+        let date = match (&datetime.date, &datetime.time) {
+            (Some(date), None) => date,
+            _ => {
+                return Err(error_at(
+                    date_offset,
+                    format!("date of \"{}\" must be a plain date like 2026-03-20", id),
+                ));
+            }
+        };
+        let (y, m, d) = (date.year as i32, date.month as i32, date.day as i32);
+        let first_day = days_from_civil(y, m, d);
+        if civil_from_days(first_day) != (y, m, d) {
+            return Err(error_at(
+                date_offset,
+                format!("{} is not a valid date", datetime),
+            ));
+        }
+        if y != year {
+            return Err(error_at(
+                date_offset,
+                format!("date {} is outside the pack year {}", datetime, year),
+            ));
+        }
+
+        let span = entry.days.as_ref().map(|s| *s.get_ref()).unwrap_or(1);
+        if !(1..=PACK_MAX_SPAN_DAYS).contains(&span) {
+            let offset = entry.days.as_ref().map_or(date_offset, |s| s.span().start);
+            return Err(error_at(
+                offset,
+                format!("days must be between 1 and {}", PACK_MAX_SPAN_DAYS),
+            ));
+        }
+        let last_day = first_day + span as i64 - 1;
+        if civil_from_days(last_day).0 != year {
+            return Err(error_at(
+                date_offset,
+                format!("\"{}\" runs past the end of {}", id, year),
+            ));
+        }
+
+        if seen.iter().any(|(i, day)| i == id && *day == first_day) {
+            return Err(error_at(
+                entry.id.span().start,
+                format!("\"{}\" on {} is declared twice", id, datetime),
+            ));
+        }
+        seen.push((id.clone(), first_day));
+
+        for days in first_day..=last_day {
+            holidays.push(Holiday {
+                id: id.clone(),
+                name: entry.name.get_ref().clone(),
+                kind: entry.kind,
+                days,
+            });
+        }
+    }
+
+    Ok(DataPack { year, holidays })
+}
+
+/// Pack files in a folder, sorted by file name
+fn pack_files_in(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+// ---------------------------------------------------------------------------
+// Database
+// ---------------------------------------------------------------------------
+
+// Loaded packs, in override order (later packs win)
+static HOLIDAY_PACKS: Mutex<Vec<DataPack>> = Mutex::new(Vec::new());
+
+/// Load all data packs, beside the executable first and then from the user
+/// profile (so a user's pack overrides a shipped one). Invalid packs are
+/// skipped and reported.
+pub fn load_holiday_packs() -> Vec<PackError> {
+    let mut packs = Vec::new();
+    let mut errors = Vec::new();
+
+    let dirs = [exe_dir(), user_data_dir()];
+    for dir in dirs.iter().flatten() {
+        for path in pack_files_in(&dir.join(PACK_DIR_NAME)) {
+            let result = std::fs::read_to_string(&path)
+                .map_err(|e| PackError {
+                    path: path.clone(),
+                    message: e.to_string(),
+                })
+                .and_then(|text| parse_pack(&path, &text));
+            match result {
+                Ok(pack) => packs.push(pack),
+                Err(e) => errors.push(e),
+            }
+        }
+    }
+
+    if let Ok(mut loaded) = HOLIDAY_PACKS.lock() {
+        *loaded = packs;
+    }
+    errors
+}
+
+/// All holidays of a Gregorian year, with pack entries replacing built-in rules
+fn holidays_in_year(packs: &[DataPack], year: i32) -> Vec<Holiday> {
+    let mut holidays: Vec<Holiday> = BUILTIN_HOLIDAYS
+        .iter()
+        .flat_map(|b| {
+            b.occurrences(year)
+                .into_iter()
+                .map(|days| b.to_holiday(days))
+        })
+        .collect();
+
+    // Tithi-based festivals from the lunisolar calendar
+    holidays.extend(
+        festival_days(year)
+            .into_iter()
+            .map(|(festival, days)| Holiday {
+                id: festival.id.to_string(),
+                name: festival.name.to_string(),
+                kind: festival.kind,
+                days,
+            }),
+    );
+
+    for pack in packs.iter().filter(|p| p.year == year) {
+        holidays.retain(|h| !pack.holidays.iter().any(|p| p.id == h.id));
+        holidays.extend(pack.holidays.iter().cloned());
+    }
+
+    holidays.sort_by_key(|h| h.days);
+    holidays
+}
+
+/// Holidays between two day numbers (inclusive), sorted by date
+pub fn holidays_between(first: i64, last: i64) -> Vec<Holiday> {
+    let Ok(packs) = HOLIDAY_PACKS.lock() else {
+        return Vec::new();
+    };
+
+    let first_year = civil_from_days(first).0;
+    let last_year = civil_from_days(last).0;
+
+    (first_year..=last_year)
+        .flat_map(|year| holidays_in_year(&packs, year))
+        .filter(|h| (first..=last).contains(&h.days))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACK_HEADER: &str = "format = 1\nname = \"বাংলাদেশ সরকারি ছুটি\"\nyear = 2026\n";
+
+    fn parse(body: &str) -> Result<DataPack, PackError> {
+        parse_pack(Path::new("2026.toml"), &format!("{}{}", PACK_HEADER, body))
+    }
+
+    fn error(body: &str) -> String {
+        match parse(body) {
+            Ok(_) => panic!("pack should be rejected:\n{}", body),
+            Err(e) => e.message,
+        }
+    }
+
+    #[test]
+    fn a_valid_pack_spans_its_days() {
+        let pack = parse(
+            "
+[[holiday]]
+id = \"eid-ul-fitr\"
+name = \"ঈদুল ফিতর\"
+date = 2026-03-19
+days = 5
+",
+        )
+        .unwrap();
+        assert_eq!(pack.year, 2026);
+        let days: Vec<i64> = pack.holidays.iter().map(|h| h.days).collect();
+        let first = days_from_civil(2026, 3, 19);
+        assert_eq!(days, (first..first + 5).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn an_unknown_format_version_is_rejected() {
+        let message = parse_pack(
+            Path::new("2026.toml"),
+            "name = \"ছুটি\"\nformat = 2\nyear = 2026\n",
+        )
+        .err()
+        .unwrap()
+        .message;
+        assert!(
+            message.starts_with("line 2: unsupported format version 2"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn a_date_outside_the_pack_year_is_rejected() {
+        let message = error(
+            "
+[[holiday]]
+id = \"victory-day\"
+name = \"বিজয় দিবস\"
+date = 2025-12-16
+",
+        );
+        assert_eq!(
+            message,
+            "line 8: date 2025-12-16 is outside the pack year 2026"
+        );
+    }
+
+    #[test]
+    fn a_span_past_the_year_end_is_rejected() {
+        let message = error(
+            "
+[[holiday]]
+id = \"winter-break\"
+name = \"শীতকালীন ছুটি\"
+date = 2026-12-28
+days = 5
+",
+        );
+        assert_eq!(
+            message,
+            "line 8: \"winter-break\" runs past the end of 2026"
+        );
+    }
+
+    #[test]
+    fn a_duplicate_id_on_the_same_day_is_rejected() {
+        let message = error(
+            "
+[[holiday]]
+id = \"may-day\"
+name = \"মে দিবস\"
+date = 2026-05-01
+
+[[holiday]]
+id = \"may-day\"
+name = \"মে দিবস\"
+date = 2026-05-01
+",
+        );
+        assert_eq!(
+            message,
+            "line 11: \"may-day\" on 2026-05-01 is declared twice"
+        );
+    }
+
+    #[test]
+    fn a_pack_replaces_the_built_in_rule_with_its_id() {
+        let pack = parse(
+            "
+[[holiday]]
+id = \"eid-ul-adha\"
+name = \"ঈদুল আজহা\"
+date = 2026-05-26
+days = 2
+",
+        )
+        .unwrap();
+        let calculated: Vec<i64> = holidays_in_year(&[], 2026)
+            .into_iter()
+            .filter(|h| h.id == "eid-ul-adha")
+            .map(|h| h.days)
+            .collect();
+        assert_eq!(calculated.len(), 1);
+
+        let holidays = holidays_in_year(&[pack], 2026);
+        let announced: Vec<i64> = holidays
+            .iter()
+            .filter(|h| h.id == "eid-ul-adha")
+            .map(|h| h.days)
+            .collect();
+        let first = days_from_civil(2026, 5, 26);
+        assert_eq!(announced, [first, first + 1]);
+        // Other built-in holidays stay
+        assert!(holidays.iter().any(|h| h.id == "victory-day"));
+
+        // A bad entry in a pack is reported at its line
+        let message = error(
+            "
+[[holiday]]
+id = \"Eid-Ul-Adha\"
+name = \"ঈদুল আজহা\"
+date = 2026-05-26
+",
+        );
+        assert!(
+            message.starts_with("line 6: id \"Eid-Ul-Adha\""),
+            "{}",
+            message
+        );
+    }
+}
@@ -0,0 +1,252 @@
+// html.rs - Month and year calendars as a self-contained web page
+//
+// The page lays a month out as the punjika does (header with the year and
+// ritu, the Gregorian months, weekday row, day grid) with the Gregorian date
+// in each cell, holidays in their punjika colors and today highlighted, and
+// lists the month's holidays below the grid. The CSS is inline and the glyphs
+// the page uses are embedded as a subset of Ekush, so the one file can be
+// uploaded anywhere as it is.
+
+use crate::calendar::{get_current_bangla_date, gregorian_span, to_bangla_number};
+use crate::canvas::color_rgb;
+use crate::constants::*;
+use crate::holidays::HolidayKind;
+use crate::locations::current_location;
+use crate::month_layout::{MonthLayout, MonthMarks, current_week};
+use crate::shaping::web_font_subset;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+
+/// A COLORREF as a CSS color
+fn css_color(color: u32) -> String {
+    let (r, g, b) = color_rgb(color);
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Class for a holiday kind, colored as in the punjika
+fn holiday_class(kind: Option<HolidayKind>) -> Option<&'static str> {
+    kind.map(|kind| match kind {
+        HolidayKind::Public => "public",
+        HolidayKind::Optional => "optional",
+        HolidayKind::Observance => "observance",
+    })
+}
+
+fn stylesheet() -> String {
+    format!(
+        "\
+body{{margin:0;padding:24px;background:#fafafa;font-family:Ekush,sans-serif;color:{date}}}
+h1{{text-align:center;font-size:32px;margin:0 0 24px}}
+.months{{display:grid;grid-template-columns:repeat(auto-fill,minmax(320px,1fr));gap:24px;max-width:1400px;margin:0 auto}}
+.month{{background:{bg};border-radius:8px;overflow:hidden;box-shadow:0 1px 4px rgba(0,0,0,.15);max-width:640px;width:100%;margin:0 auto}}
+.month>header{{background:{header_bg};color:{header_text};text-align:center;padding:10px 12px 8px}}
+.month h2{{margin:0;font-size:26px}}
+.month>header p{{margin:2px 0 0;font-size:15px}}
+.nav{{background:{nav_bg};color:{nav_text};text-align:center;padding:8px 12px;font-size:18px}}
+.grid{{display:grid;grid-template-columns:repeat(7,1fr);gap:2px;padding:6px 12px 12px}}
+.weekday{{color:{weekday};font-weight:600;text-align:center;padding:4px 0 6px;border-bottom:1px solid #e0e0e0;margin-bottom:4px}}
+.weekday.weekend{{color:{weekend_text}}}
+.day{{position:relative;text-align:center;padding:10px 0 14px;border-radius:4px;font-size:20px}}
+.day small{{position:absolute;right:4px;bottom:2px;font-size:11px;color:{weekday}}}
+.day.weekend{{background:{weekend_bg}}}
+.day.public{{color:{public}}}
+.day.optional{{color:{optional}}}
+.day.observance{{color:{observance}}}
+.day.today{{background:{today_bg};color:{today_text}}}
+.day.today small{{color:{today_text}}}
+.holidays{{list-style:none;margin:0;padding:0 12px 12px;font-size:14px;color:{footer}}}
+.holidays li{{padding:2px 0}}
+.holidays .public{{color:{public}}}
+.holidays .optional{{color:{optional}}}
+.holidays .observance{{color:{observance}}}
+",
+        date = css_color(CAL_DATE_TEXT),
+        bg = css_color(CAL_BG),
+        header_bg = css_color(CAL_HEADER_BG),
+        header_text = css_color(CAL_HEADER_TEXT),
+        nav_bg = css_color(CAL_NAV_BG),
+        nav_text = css_color(CAL_NAV_TEXT),
+        weekday = css_color(CAL_WEEKDAY_TEXT),
+        weekend_text = css_color(CAL_WEEKEND_TEXT),
+        weekend_bg = css_color(CAL_WEEKEND_BG),
+        public = css_color(CAL_HOLIDAY_TEXT),
+        optional = css_color(CAL_OPTIONAL_TEXT),
+        observance = css_color(CAL_OBSERVANCE_TEXT),
+        today_bg = css_color(CAL_TODAY_BG),
+        today_text = css_color(CAL_TODAY_TEXT),
+        footer = css_color(CAL_FOOTER_TEXT),
+    )
+}
+
+/// Text for HTML content or an attribute value
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The page body being written, and every piece of text in it (for the font
+/// subset)
+#[derive(Default)]
+struct PageWriter {
+    body: String,
+    texts: Vec<String>,
+}
+
+impl PageWriter {
+    /// Markup, written as it is
+    fn tag(&mut self, markup: &str) {
+        self.body.push_str(markup);
+    }
+
+    /// Text, escaped
+    fn text(&mut self, text: &str) {
+        self.body.push_str(&escape(text));
+        self.texts.push(text.to_string());
+    }
+
+    /// The whole document, with the stylesheet and the font
+    fn finish(self, title: &str) -> String {
+        let font = web_font_subset(
+            self.texts
+                .iter()
+                .map(String::as_str)
+                .chain(std::iter::once(title)),
+        );
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"bn\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+             <meta name=\"generator\" content=\"Bangla Calendar\">\n\
+             <title>{}</title>\n<style>\n\
+             @font-face{{font-family:Ekush;src:url(data:font/ttf;base64,{}) format(\"truetype\")}}\n\
+             {}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+            escape(title),
+            BASE64.encode(font),
+            stylesheet(),
+            self.body
+        )
+    }
+}
+
+fn write_month(page: &mut PageWriter, year: i32, month: i32) {
+    let layout = MonthLayout::new(
+        year,
+        month,
+        CAL_WIDTH as f32,
+        &get_current_bangla_date(),
+        current_week(),
+        MonthMarks::for_month(year, month, current_location()),
+    );
+
+    // Header: month, then year · ritu, then the Gregorian months
+    page.tag("<section class=\"month\">\n<header><h2>");
+    page.text(BANGLA_MONTHS[month as usize]);
+    page.tag("</h2><p>");
+    page.text(&format!(
+        "{} বঙ্গাব্দ · {}কাল",
+        to_bangla_number(year),
+        BANGLA_SEASONS[month as usize]
+    ));
+    page.tag("</p></header>\n<div class=\"nav\">");
+    page.text(&gregorian_span(layout.first_days, layout.last_days));
+    page.tag("</div>\n<div class=\"grid\">\n");
+
+    for column in 0..7 {
+        let weekday = layout.week.weekday(column);
+        page.tag(if layout.week.is_weekend(weekday) {
+            "<div class=\"weekday weekend\">"
+        } else {
+            "<div class=\"weekday\">"
+        });
+        page.text(BANGLA_WEEKDAYS_SHORT[weekday]);
+        page.tag("</div>");
+    }
+    page.tag("\n");
+
+    for cell in &layout.cells {
+        let mut classes = String::from("day");
+        if let Some(class) = holiday_class(cell.holiday) {
+            classes = classes + " " + class;
+        }
+        if cell.is_weekend {
+            classes += " weekend";
+        }
+        if cell.is_today {
+            classes += " today";
+        }
+        page.tag(&format!("<div class=\"{}\"", classes));
+        if cell.day == 1 && cell.column > 0 {
+            page.tag(&format!(" style=\"grid-column-start:{}\"", cell.column + 1));
+        }
+        let names: Vec<&str> = layout
+            .holidays_on(cell.days)
+            .map(|h| h.name.as_str())
+            .collect();
+        if !names.is_empty() {
+            page.tag(" title=\"");
+            page.text(&names.join(", "));
+            page.tag("\"");
+        }
+        page.tag(">");
+        page.text(&to_bangla_number(cell.day));
+        page.tag("<small>");
+        page.text(&cell.gregorian_label());
+        page.tag("</small></div>\n");
+    }
+    page.tag("</div>\n");
+
+    // The month's holidays, in date order
+    if !layout.holidays.is_empty() {
+        page.tag("<ul class=\"holidays\">\n");
+        for holiday in &layout.holidays {
+            let day = (holiday.days - layout.first_days) as i32 + 1;
+            page.tag(&format!(
+                "<li class=\"{}\">",
+                holiday_class(Some(holiday.kind)).unwrap_or_default()
+            ));
+            page.text(&format!(
+                "{} {} — {}",
+                to_bangla_number(day),
+                BANGLA_MONTHS[month as usize],
+                holiday.name
+            ));
+            page.tag("</li>\n");
+        }
+        page.tag("</ul>\n");
+    }
+    page.tag("</section>\n");
+}
+
+/// একটি মাসের ওয়েব পেজ (month 0-11)
+pub fn month_html(year: i32, month: i32) -> String {
+    let mut page = PageWriter::default();
+    write_month(&mut page, year, month);
+    let title = format!(
+        "{} {}",
+        BANGLA_MONTHS[month as usize],
+        to_bangla_number(year)
+    );
+    page.finish(&title)
+}
+
+/// পুরো বছরের ওয়েব পেজ: বৈশাখ থেকে চৈত্র
+pub fn year_html(year: i32) -> String {
+    let title = format!("{} বঙ্গাব্দ", to_bangla_number(year));
+    let mut page = PageWriter::default();
+    page.tag("<h1>");
+    page.text(&title);
+    page.tag("</h1>\n<div class=\"months\">\n");
+    for month in 0..12 {
+        write_month(&mut page, year, month);
+    }
+    page.tag("</div>\n");
+    page.finish(&title)
+}
@@ -0,0 +1,185 @@
+// ics.rs - iCalendar (RFC 5545) export of Bangla dates, holidays and events
+//
+// Every day becomes an all-day event titled with its Bangla date, so the date
+// shows up in Outlook, Thunderbird and phone calendars. Holidays are all-day
+// events too; personal events with a time are written in UTC, from the time
+// at the selected location.
+
+use crate::astronomy::{days_from_jd, jd_now};
+use crate::calendar::{bangla_date_from_days, civil_from_days};
+use crate::events::events_between;
+use crate::holidays::{HolidayKind, holidays_between};
+use crate::locations::current_location;
+
+const ICS_PRODID: &str = "-//sayed.app//Bangla Calendar//BN";
+const ICS_UID_DOMAIN: &str = "bangla-calendar.sayed.app";
+const ICS_LINE_OCTETS: usize = 75;
+const EVENT_DEFAULT_MINUTES: i64 = 60;
+
+/// What to include besides the Bangla date of each day
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IcsOptions {
+    pub holidays: bool,
+    pub events: bool,
+}
+
+/// Escape a TEXT value: backslash, semicolon, comma and newlines
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Append a content line, folded at 75 octets without splitting a character
+fn push_line(ics: &mut String, line: &str) {
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > ICS_LINE_OCTETS {
+            ics.push_str("\r\n ");
+            octets = 1;
+        }
+        ics.push(c);
+        octets += c.len_utf8();
+    }
+    ics.push_str("\r\n");
+}
+
+/// DATE value: 20250414
+fn format_date(days: i64) -> String {
+    let (year, month, day) = civil_from_days(days);
+    format!("{:04}{:02}{:02}", year, month, day)
+}
+
+/// UTC DATE-TIME value of a minute since 1970-01-01 00:00 UTC: 20250414T063000Z
+fn format_utc_minute(minute: i64) -> String {
+    let days = minute.div_euclid(1440);
+    let minute_of_day = minute.rem_euclid(1440);
+    format!(
+        "{}T{:02}{:02}00Z",
+        format_date(days),
+        minute_of_day / 60,
+        minute_of_day % 60
+    )
+}
+
+/// An all-day VEVENT
+fn push_all_day_event(ics: &mut String, stamp: &str, uid: &str, days: i64, summary: &str) {
+    push_line(ics, "BEGIN:VEVENT");
+    push_line(ics, &format!("UID:{}@{}", uid, ICS_UID_DOMAIN));
+    push_line(ics, &format!("DTSTAMP:{}", stamp));
+    push_line(ics, &format!("DTSTART;VALUE=DATE:{}", format_date(days)));
+    push_line(ics, &format!("DTEND;VALUE=DATE:{}", format_date(days + 1)));
+    push_line(ics, &format!("SUMMARY:{}", escape_text(summary)));
+}
+
+/// iCalendar text for the days between two day numbers (inclusive)
+pub fn calendar_ics(first: i64, last: i64, options: IcsOptions) -> String {
+    let stamp = format_utc_minute((days_from_jd(jd_now()) * 1440.0) as i64);
+    let mut ics = String::new();
+    push_line(&mut ics, "BEGIN:VCALENDAR");
+    push_line(&mut ics, "VERSION:2.0");
+    push_line(&mut ics, &format!("PRODID:{}", ICS_PRODID));
+    push_line(&mut ics, "CALSCALE:GREGORIAN");
+    push_line(&mut ics, "METHOD:PUBLISH");
+    push_line(&mut ics, "X-WR-CALNAME:বাংলা ক্যালেন্ডার");
+
+    // ০৬ই পৌষ, ১৪৩২ বঙ্গাব্দ
+    for days in first..=last {
+        let date = bangla_date_from_days(days);
+        let summary = format!("{} {}", date.format_line1(), date.format_line2());
+        push_all_day_event(
+            &mut ics,
+            &stamp,
+            &format!("bangla-date-{}", format_date(days)),
+            days,
+            &summary,
+        );
+        push_line(
+            &mut ics,
+            &format!("DESCRIPTION:{}", escape_text(&date.format_line3())),
+        );
+        push_line(&mut ics, "TRANSP:TRANSPARENT");
+        push_line(&mut ics, "END:VEVENT");
+    }
+
+    if options.holidays {
+        for holiday in holidays_between(first, last) {
+            push_all_day_event(
+                &mut ics,
+                &stamp,
+                &format!("holiday-{}-{}", holiday.id, format_date(holiday.days)),
+                holiday.days,
+                &holiday.name,
+            );
+            let category = match holiday.kind {
+                HolidayKind::Public => "সাধারণ ছুটি",
+                HolidayKind::Optional => "ঐচ্ছিক ছুটি",
+                HolidayKind::Observance => "দিবস",
+            };
+            push_line(&mut ics, &format!("CATEGORIES:{}", escape_text(category)));
+            let transparency = if holiday.kind == HolidayKind::Observance {
+                "TRANSPARENT"
+            } else {
+                "OPAQUE"
+            };
+            push_line(&mut ics, &format!("TRANSP:{}", transparency));
+            push_line(&mut ics, "END:VEVENT");
+        }
+    }
+
+    if options.events {
+        let offset = current_location().utc_offset_minutes as i64;
+        for (days, event) in events_between(first, last) {
+            let uid = format!(
+                "event-{}-{}@{}",
+                event.id,
+                format_date(days),
+                ICS_UID_DOMAIN
+            );
+            push_line(&mut ics, "BEGIN:VEVENT");
+            push_line(&mut ics, &format!("UID:{}", uid));
+            push_line(&mut ics, &format!("DTSTAMP:{}", stamp));
+            match event.minute() {
+                Some(minute) => {
+                    let start = days * 1440 + minute as i64 - offset;
+                    push_line(&mut ics, &format!("DTSTART:{}", format_utc_minute(start)));
+                    push_line(
+                        &mut ics,
+                        &format!("DTEND:{}", format_utc_minute(start + EVENT_DEFAULT_MINUTES)),
+                    );
+                }
+                None => {
+                    push_line(
+                        &mut ics,
+                        &format!("DTSTART;VALUE=DATE:{}", format_date(days)),
+                    );
+                    push_line(
+                        &mut ics,
+                        &format!("DTEND;VALUE=DATE:{}", format_date(days + 1)),
+                    );
+                }
+            }
+            push_line(&mut ics, &format!("SUMMARY:{}", escape_text(&event.title)));
+            if !event.notes.is_empty() {
+                push_line(
+                    &mut ics,
+                    &format!("DESCRIPTION:{}", escape_text(&event.notes)),
+                );
+            }
+            push_line(&mut ics, "END:VEVENT");
+        }
+    }
+
+    push_line(&mut ics, "END:VCALENDAR");
+    ics
+}
@@ -0,0 +1,1027 @@
+// ics_import.rs - iCalendar (RFC 5545) import of external events
+//
+// VEVENTs become personal events on their dates at the selected location.
+// Yearly, monthly and "nth weekday of a month" rules without an end are kept
+// as repeating events; any other RRULE (weekly meetings, COUNT, UNTIL,
+// intervals, EXDATEs, moved instances) is expanded into one-off events from a
+// year before the import to two years after it. Every event keeps its UID,
+// so importing the same file again replaces what it imported last time.
+
+use crate::calendar::{civil_from_days, days_from_civil, to_bangla_number};
+use crate::events::{EventCalendar, EventColor, Repeat, UserEvent, weekday_of};
+use crate::locations::current_location;
+use std::collections::{HashMap, HashSet};
+
+const IMPORT_PAST_DAYS: i64 = 366;
+const IMPORT_FUTURE_DAYS: i64 = 731;
+const IMPORT_MAX_OCCURRENCES: usize = 1000;
+const IMPORT_COLOR: EventColor = EventColor::Blue;
+
+// Zones written by TZID alone, without a VTIMEZONE (offsets in minutes)
+const KNOWN_TIME_ZONES: &[(&str, i32)] = &[
+    ("Asia/Dhaka", 360),
+    ("Asia/Kolkata", 330),
+    ("Asia/Calcutta", 330),
+    ("Bangladesh Standard Time", 360),
+    ("India Standard Time", 330),
+    ("UTC", 0),
+    ("Etc/UTC", 0),
+    ("GMT", 0),
+];
+
+/// The events of a calendar file
+#[derive(Debug, Default)]
+pub struct IcsImport {
+    pub events: Vec<UserEvent>,
+    pub skipped: usize, // VEVENTs without a usable start date
+}
+
+/// The zone of a DATE-TIME value
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Zone {
+    Utc,
+    Offset(i32),      // minutes east of UTC
+    Vtimezone(usize), // index into the file's time zones
+    Floating,         // local time wherever you are
+}
+
+/// A STANDARD or DAYLIGHT part of a VTIMEZONE
+#[derive(Debug, Default)]
+struct Observance {
+    daylight: bool,
+    start: Option<IcsTime>, // DTSTART, in the time it replaces
+    rule: Option<RecurrenceRule>,
+    offset: Option<i32>, // TZOFFSETTO, minutes east of UTC
+}
+
+impl Observance {
+    /// When this observance last began, at or before a local day and minute
+    fn last_onset(&self, days: i64, minute: i32) -> Option<(i64, i32)> {
+        let start = self.start?;
+        let onset_minute = start.minute.unwrap_or(0);
+        let onsets = match &self.rule {
+            Some(rule) => rule.expand(start.days, days),
+            None => vec![start.days],
+        };
+        onsets
+            .into_iter()
+            .rev()
+            .map(|day| (day, onset_minute))
+            .find(|&onset| onset <= (days, minute))
+    }
+}
+
+/// A VTIMEZONE of the file
+#[derive(Debug, Default)]
+struct TimeZone {
+    tzid: String,
+    observances: Vec<Observance>,
+}
+
+impl TimeZone {
+    /// The UTC offset at a local day and minute: the observance that began
+    /// last, or standard time before any of them
+    fn offset_at(&self, days: i64, minute: i32) -> i32 {
+        self.observances
+            .iter()
+            .filter_map(|o| Some((o.last_onset(days, minute)?, o.offset?)))
+            .max_by_key(|&(onset, _)| onset)
+            .map(|(_, offset)| offset)
+            .or_else(|| {
+                self.observances
+                    .iter()
+                    .find(|o| !o.daylight)
+                    .or(self.observances.first())
+                    .and_then(|o| o.offset)
+            })
+            .unwrap_or(0)
+    }
+
+    /// Whether every observance has the same offset
+    fn is_fixed(&self) -> bool {
+        self.observances
+            .windows(2)
+            .all(|pair| pair[0].offset == pair[1].offset)
+    }
+}
+
+/// A DATE or DATE-TIME value
+#[derive(Debug, Clone, Copy)]
+struct IcsTime {
+    days: i64,
+    minute: Option<i32>, // None for an all-day DATE
+    zone: Zone,
+}
+
+impl IcsTime {
+    /// Day and minute at a UTC offset
+    fn local(&self, offset: i32, zones: &[TimeZone]) -> (i64, Option<i32>) {
+        let Some(minute) = self.minute else {
+            return (self.days, None);
+        };
+        let source = match self.zone {
+            Zone::Utc => 0,
+            Zone::Offset(zone) => zone,
+            Zone::Vtimezone(index) => zones[index].offset_at(self.days, minute),
+            Zone::Floating => offset,
+        };
+        let total = self.days * 1440 + (minute - source + offset) as i64;
+        (total.div_euclid(1440), Some(total.rem_euclid(1440) as i32))
+    }
+
+    /// Whether every instance of a rule keeps the local time of this one
+    fn keeps_local_time(&self, zones: &[TimeZone]) -> bool {
+        match self.zone {
+            Zone::Vtimezone(index) if self.minute.is_some() => zones[index].is_fixed(),
+            _ => true,
+        }
+    }
+}
+
+/// One content line: NAME;PARAM=value:value
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Unfold continuation lines (RFC 5545 §3.1)
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match line.strip_prefix([' ', '\t']) {
+            Some(rest) if !lines.is_empty() => lines.last_mut().unwrap().push_str(rest),
+            _ if line.trim().is_empty() => {}
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+fn parse_property(line: &str) -> Option<Property> {
+    // The value starts at the first colon outside a quoted parameter value
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ':' if !quoted => Some(i),
+        _ => None,
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_ascii_uppercase();
+    let params = parts
+        .filter_map(|part| part.split_once('='))
+        .map(|(key, value)| {
+            (
+                key.to_ascii_uppercase(),
+                value.trim_matches('"').to_string(),
+            )
+        })
+        .collect();
+    Some(Property {
+        name,
+        params,
+        value: value.to_string(),
+    })
+}
+
+/// Undo TEXT escaping
+fn unescape_text(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => {}
+        }
+    }
+    unescaped
+}
+
+/// UTC offset: +0600, -0330, +053000
+fn parse_utc_offset(text: &str) -> Option<i32> {
+    let (sign, digits) = match text.trim().split_at_checked(1)? {
+        ("+", digits) => (1, digits),
+        ("-", digits) => (-1, digits),
+        _ => return None,
+    };
+    let hours: i32 = digits.get(0..2)?.parse().ok()?;
+    let minutes: i32 = digits.get(2..4)?.parse().ok()?;
+    Some(sign * (hours * 60 + minutes))
+}
+
+/// DATE (20250414) or DATE-TIME (20250414T093000, with Z for UTC)
+fn parse_time_value(value: &str, zone: Zone) -> Option<IcsTime> {
+    let value = value.trim();
+    let number = |range: std::ops::Range<usize>| value.get(range)?.parse::<i32>().ok();
+    let (year, month, day) = (number(0..4)?, number(4..6)?, number(6..8)?);
+    let days = days_from_civil(year, month, day);
+    if civil_from_days(days) != (year, month, day) {
+        return None;
+    }
+    if value.len() == 8 {
+        return Some(IcsTime {
+            days,
+            minute: None,
+            zone: Zone::Floating,
+        });
+    }
+    if value.get(8..9) != Some("T") {
+        return None;
+    }
+    let (hour, minute) = (number(9..11)?, number(11..13)?);
+    if !(0..24).contains(&hour) || !(0..60).contains(&minute) {
+        return None;
+    }
+    Some(IcsTime {
+        days,
+        minute: Some(hour * 60 + minute),
+        zone: if value.ends_with('Z') {
+            Zone::Utc
+        } else {
+            zone
+        },
+    })
+}
+
+/// The times of a DTSTART/EXDATE/RECURRENCE-ID property
+fn property_times(property: &Property, zones: &[TimeZone]) -> Vec<IcsTime> {
+    let zone = match property.param("TZID") {
+        Some(tzid) => zones
+            .iter()
+            .position(|zone| zone.tzid == tzid && !zone.observances.is_empty())
+            .map(Zone::Vtimezone)
+            .or_else(|| {
+                KNOWN_TIME_ZONES
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(tzid))
+                    .map(|&(_, offset)| Zone::Offset(offset))
+            })
+            .unwrap_or(Zone::Floating),
+        None => Zone::Floating,
+    };
+    property
+        .value
+        .split(',')
+        .filter_map(|value| parse_time_value(value, zone))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// The parts of an RRULE this importer understands
+#[derive(Debug, Clone)]
+struct RecurrenceRule {
+    frequency: Frequency,
+    interval: i64,
+    count: Option<usize>,
+    until: Option<IcsTime>,
+    by_day: Vec<(Option<i32>, i32)>, // (ordinal, weekday with 0 = Sunday)
+    by_month_day: Vec<i32>,
+    by_month: Vec<i32>,
+}
+
+fn parse_rrule(value: &str) -> Option<RecurrenceRule> {
+    let mut rule = RecurrenceRule {
+        frequency: Frequency::Daily,
+        interval: 1,
+        count: None,
+        until: None,
+        by_day: Vec::new(),
+        by_month_day: Vec::new(),
+        by_month: Vec::new(),
+    };
+    let mut frequency = None;
+    for part in value.split(';') {
+        let Some((key, value)) = part.split_once('=') else {
+            continue;
+        };
+        let numbers = || {
+            value
+                .split(',')
+                .filter_map(|n| n.trim().parse::<i32>().ok())
+        };
+        match key.to_ascii_uppercase().as_str() {
+            "FREQ" => {
+                frequency = match value.to_ascii_uppercase().as_str() {
+                    "DAILY" => Some(Frequency::Daily),
+                    "WEEKLY" => Some(Frequency::Weekly),
+                    "MONTHLY" => Some(Frequency::Monthly),
+                    "YEARLY" => Some(Frequency::Yearly),
+                    _ => None, // HOURLY and finer aren't day events
+                };
+            }
+            "INTERVAL" => rule.interval = value.parse::<i64>().ok()?.max(1),
+            "COUNT" => rule.count = value.parse().ok(),
+            "UNTIL" => rule.until = parse_time_value(value, Zone::Floating),
+            "BYDAY" => {
+                for day in value.split(',') {
+                    let day = day.trim();
+                    let (ordinal, name) = day.split_at_checked(day.len().checked_sub(2)?)?;
+                    let weekday = ["SU", "MO", "TU", "WE", "TH", "FR", "SA"]
+                        .iter()
+                        .position(|&n| n.eq_ignore_ascii_case(name))?;
+                    let ordinal = match ordinal.trim_start_matches('+') {
+                        "" => None,
+                        n => Some(n.parse::<i32>().ok()?),
+                    };
+                    rule.by_day.push((ordinal, weekday as i32));
+                }
+            }
+            "BYMONTHDAY" => rule.by_month_day = numbers().collect(),
+            "BYMONTH" => rule.by_month = numbers().collect(),
+            _ => {}
+        }
+    }
+    rule.frequency = frequency?;
+    Some(rule)
+}
+
+fn month_length(year: i32, month: i32) -> i64 {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    days_from_civil(next_year, next_month, 1) - days_from_civil(year, month, 1)
+}
+
+impl RecurrenceRule {
+    /// Days of a month the rule picks, given the start date's day
+    fn month_days(&self, year: i32, month: i32, start_day: i32) -> Vec<i64> {
+        let first = days_from_civil(year, month, 1);
+        let length = month_length(year, month);
+        let month_day = |day: i32| {
+            let index = if day < 0 {
+                length + day as i64
+            } else {
+                day as i64 - 1
+            };
+            (0..length).contains(&index).then_some(first + index)
+        };
+
+        let mut days: Vec<i64> = if !self.by_day.is_empty() {
+            let mut days = Vec::new();
+            for &(ordinal, weekday) in &self.by_day {
+                let matching: Vec<i64> = (first..first + length)
+                    .filter(|&d| weekday_of(d) == weekday)
+                    .collect();
+                match ordinal {
+                    None => days.extend(&matching),
+                    Some(n) if n > 0 => days.extend(matching.get(n as usize - 1)),
+                    Some(n) => days.extend(
+                        matching
+                            .len()
+                            .checked_sub(n.unsigned_abs() as usize)
+                            .and_then(|i| matching.get(i)),
+                    ),
+                }
+            }
+            if !self.by_month_day.is_empty() {
+                let allowed: Vec<i64> = self
+                    .by_month_day
+                    .iter()
+                    .filter_map(|&d| month_day(d))
+                    .collect();
+                days.retain(|d| allowed.contains(d));
+            }
+            days
+        } else if !self.by_month_day.is_empty() {
+            self.by_month_day
+                .iter()
+                .filter_map(|&d| month_day(d))
+                .collect()
+        } else {
+            // A start on the 31st skips shorter months (RFC 5545)
+            month_day(start_day).into_iter().collect()
+        };
+        days.sort_unstable();
+        days.dedup();
+        days
+    }
+
+    /// Whether a day passes the BYMONTH, BYDAY and BYMONTHDAY filters of a
+    /// daily or weekly rule
+    fn allows(&self, days: i64) -> bool {
+        let (year, month, day) = civil_from_days(days);
+        (self.by_month.is_empty() || self.by_month.contains(&month))
+            && (self.frequency == Frequency::Weekly
+                || self.by_day.is_empty()
+                || self.by_day.iter().any(|&(_, w)| w == weekday_of(days)))
+            && (self.by_month_day.is_empty()
+                || self.by_month_day.iter().any(|&d| {
+                    d == day || (d < 0 && month_length(year, month) + d as i64 + 1 == day as i64)
+                }))
+    }
+
+    /// Days of the instances from `start` up to `last` (inclusive), in the
+    /// zone of the start date
+    fn expand(&self, start: i64, last: i64) -> Vec<i64> {
+        let last = match self.until {
+            Some(until) => last.min(until.days),
+            None => last,
+        };
+        let (start_year, start_month, start_day) = civil_from_days(start);
+        let mut candidates: Vec<i64> = vec![start];
+        let mut period = 0;
+        loop {
+            let days: Vec<i64> = match self.frequency {
+                Frequency::Daily => {
+                    let day = start + period * self.interval;
+                    if day > last {
+                        break;
+                    }
+                    vec![day].into_iter().filter(|&d| self.allows(d)).collect()
+                }
+                Frequency::Weekly => {
+                    // Weeks start on Monday
+                    let week =
+                        start - (weekday_of(start) as i64 + 6) % 7 + period * 7 * self.interval;
+                    if week > last {
+                        break;
+                    }
+                    (week..week + 7)
+                        .filter(|&d| {
+                            if self.by_day.is_empty() {
+                                weekday_of(d) == weekday_of(start)
+                            } else {
+                                self.by_day.iter().any(|&(_, w)| w == weekday_of(d))
+                            }
+                        })
+                        .filter(|&d| self.allows(d))
+                        .collect()
+                }
+                Frequency::Monthly => {
+                    let index = start_month as i64 - 1 + period * self.interval;
+                    let year = start_year + index.div_euclid(12) as i32;
+                    let month = index.rem_euclid(12) as i32 + 1;
+                    if days_from_civil(year, month, 1) > last {
+                        break;
+                    }
+                    if self.by_month.is_empty() || self.by_month.contains(&month) {
+                        self.month_days(year, month, start_day)
+                    } else {
+                        Vec::new()
+                    }
+                }
+                Frequency::Yearly => {
+                    let year = start_year + (period * self.interval) as i32;
+                    if days_from_civil(year, 1, 1) > last {
+                        break;
+                    }
+                    let months = if self.by_month.is_empty() {
+                        vec![start_month]
+                    } else {
+                        self.by_month.clone()
+                    };
+                    months
+                        .iter()
+                        .filter(|m| (1..=12).contains(*m))
+                        .flat_map(|&month| self.month_days(year, month, start_day))
+                        .collect()
+                }
+            };
+            candidates.extend(days.into_iter().filter(|&d| d > start && d <= last));
+            if self.count.is_some_and(|count| candidates.len() >= count) {
+                break;
+            }
+            period += 1;
+        }
+        if let Some(count) = self.count {
+            candidates.truncate(count);
+        }
+        candidates
+    }
+
+    /// The same rule as a repeating personal event, if the store can hold it
+    fn as_repeat(&self, start: i64) -> Option<(Repeat, Option<i32>, Option<i32>)> {
+        if self.interval != 1 || self.count.is_some() || self.until.is_some() {
+            return None;
+        }
+        let (_, start_month, start_day) = civil_from_days(start);
+        let same_month = self.by_month.is_empty() || self.by_month == [start_month];
+        let same_day = self.by_month_day.is_empty() || self.by_month_day == [start_day];
+        match (self.frequency, self.by_day.as_slice()) {
+            // Yearly events move 29 February to the 28th; RFC 5545 skips common years
+            (Frequency::Yearly, [])
+                if same_month && same_day && (start_month, start_day) != (2, 29) =>
+            {
+                Some((Repeat::Yearly, None, None))
+            }
+            (Frequency::Yearly, &[(Some(week @ (1..=4 | -1)), weekday)])
+                if self.by_month == [start_month] && self.by_month_day.is_empty() =>
+            {
+                Some((Repeat::Weekday, Some(week), Some(weekday)))
+            }
+            // Monthly events clamp the 29th-31st to shorter months; RFC 5545 skips them
+            (Frequency::Monthly, []) if self.by_month.is_empty() && same_day && start_day <= 28 => {
+                Some((Repeat::Monthly, None, None))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Properties of one VEVENT
+#[derive(Default)]
+struct RawEvent {
+    uid: Option<String>,
+    summary: String,
+    description: String,
+    location: String,
+    start: Option<IcsTime>,
+    rrule: Option<String>,
+    exdates: Vec<IcsTime>,
+    recurrence_id: Option<IcsTime>,
+    cancelled: bool,
+}
+
+/// Read the VEVENTs (and the VTIMEZONEs they use) of a calendar file, with
+/// how many VEVENTs had no usable start
+fn read_components(text: &str) -> Result<(Vec<RawEvent>, Vec<TimeZone>, usize), String> {
+    let lines = unfold(text);
+    if !lines
+        .first()
+        .is_some_and(|line| line.trim().eq_ignore_ascii_case("BEGIN:VCALENDAR"))
+    {
+        return Err("এটি iCalendar (.ics) ফাইল নয়".to_string());
+    }
+
+    // Time zones first: a VTIMEZONE may come after the events that use it
+    let mut zones: Vec<TimeZone> = Vec::new();
+    let mut observance: Option<Observance> = None;
+    let mut stack: Vec<String> = Vec::new();
+    for property in lines.iter().filter_map(|line| parse_property(line)) {
+        let parent = stack.last().map(String::as_str);
+        match (property.name.as_str(), parent) {
+            ("BEGIN", _) => {
+                let component = property.value.trim().to_ascii_uppercase();
+                match (component.as_str(), parent) {
+                    ("VTIMEZONE", _) => zones.push(TimeZone::default()),
+                    ("STANDARD" | "DAYLIGHT", Some("VTIMEZONE")) => {
+                        observance = Some(Observance {
+                            daylight: component == "DAYLIGHT",
+                            ..Default::default()
+                        })
+                    }
+                    _ => {}
+                }
+                stack.push(component);
+            }
+            ("END", _) => {
+                let component = stack.pop();
+                if matches!(component.as_deref(), Some("STANDARD" | "DAYLIGHT"))
+                    && let (Some(finished), Some(zone)) = (observance.take(), zones.last_mut())
+                {
+                    zone.observances.push(finished);
+                }
+            }
+            ("TZID", Some("VTIMEZONE")) => {
+                if let Some(zone) = zones.last_mut() {
+                    zone.tzid = property.value.trim().to_string();
+                }
+            }
+            (name, Some("STANDARD" | "DAYLIGHT")) => {
+                let Some(observance) = observance.as_mut() else {
+                    continue;
+                };
+                match name {
+                    "TZOFFSETTO" => observance.offset = parse_utc_offset(&property.value),
+                    "DTSTART" => {
+                        observance.start = parse_time_value(&property.value, Zone::Floating)
+                    }
+                    "RRULE" => observance.rule = parse_rrule(&property.value),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    zones.retain(|zone| zone.observances.iter().all(|o| o.offset.is_some()));
+
+    let mut events = Vec::new();
+    let mut skipped = 0;
+    let mut event: Option<RawEvent> = None;
+    stack.clear();
+    for property in lines.iter().filter_map(|line| parse_property(line)) {
+        match property.name.as_str() {
+            "BEGIN" => {
+                let component = property.value.trim().to_ascii_uppercase();
+                if component == "VEVENT" {
+                    event = Some(RawEvent::default());
+                }
+                stack.push(component);
+                continue;
+            }
+            "END" => {
+                if stack.pop().as_deref() == Some("VEVENT")
+                    && let Some(finished) = event.take()
+                {
+                    if finished.start.is_some() {
+                        events.push(finished);
+                    } else {
+                        skipped += 1;
+                    }
+                }
+                continue;
+            }
+            _ => {}
+        }
+        // Only the event's own properties, not those of its VALARMs
+        if stack.last().map(String::as_str) != Some("VEVENT") {
+            continue;
+        }
+        let Some(event) = event.as_mut() else {
+            continue;
+        };
+        match property.name.as_str() {
+            "UID" => event.uid = Some(property.value.trim().to_string()),
+            "SUMMARY" => event.summary = unescape_text(&property.value),
+            "DESCRIPTION" => event.description = unescape_text(&property.value),
+            "LOCATION" => event.location = unescape_text(&property.value),
+            "DTSTART" => event.start = property_times(&property, &zones).first().copied(),
+            "RRULE" => event.rrule = Some(property.value.clone()),
+            "EXDATE" => event.exdates.extend(property_times(&property, &zones)),
+            "RECURRENCE-ID" => {
+                event.recurrence_id = property_times(&property, &zones).first().copied()
+            }
+            "STATUS" => event.cancelled = property.value.trim().eq_ignore_ascii_case("CANCELLED"),
+            _ => {}
+        }
+    }
+    Ok((events, zones, skipped))
+}
+
+/// A personal event on a local day
+fn user_event(raw: &RawEvent, uid: &str, days: i64, minute: Option<i32>) -> UserEvent {
+    let (year, month, day) = civil_from_days(days);
+    let mut notes = raw.description.trim().to_string();
+    if !raw.location.trim().is_empty() {
+        let place = format!("স্থান: {}", raw.location.trim());
+        notes = if notes.is_empty() {
+            place
+        } else {
+            format!("{}\n{}", place, notes)
+        };
+    }
+    UserEvent {
+        title: match raw.summary.trim() {
+            "" => "(শিরোনামহীন)".to_string(),
+            summary => summary.to_string(),
+        },
+        calendar: EventCalendar::Gregorian,
+        year,
+        month,
+        day,
+        time: minute.map(|m| format!("{:02}:{:02}", m / 60, m % 60)),
+        notes,
+        color: IMPORT_COLOR,
+        uid: Some(uid.to_string()),
+        ..Default::default()
+    }
+}
+
+/// Parse a calendar file into personal events at the selected location;
+/// `today` bounds how far expanded recurrences reach
+pub fn parse_ics(text: &str, today: i64) -> Result<IcsImport, String> {
+    let (raw_events, zones, skipped) = read_components(text)?;
+    let offset = current_location().utc_offset_minutes;
+    let (first, last) = (today - IMPORT_PAST_DAYS, today + IMPORT_FUTURE_DAYS);
+
+    // Instances moved or cancelled by a RECURRENCE-ID, by UID
+    let mut overridden: HashMap<&str, HashSet<i64>> = HashMap::new();
+    for raw in &raw_events {
+        if let (Some(uid), Some(recurrence_id)) = (&raw.uid, raw.recurrence_id) {
+            overridden
+                .entry(uid.as_str())
+                .or_default()
+                .insert(recurrence_id.local(offset, &zones).0);
+        }
+    }
+
+    let mut import = IcsImport {
+        skipped,
+        ..Default::default()
+    };
+    for raw in &raw_events {
+        let Some(start) = raw.start else {
+            continue;
+        };
+        if raw.cancelled {
+            continue;
+        }
+        // Without a UID the start and title tell re-imports apart
+        let uid = raw.uid.clone().unwrap_or_else(|| {
+            format!(
+                "{}-{}-{}",
+                start.days,
+                start.minute.unwrap_or(-1),
+                raw.summary
+            )
+        });
+        let (local_start, minute) = start.local(offset, &zones);
+
+        let rule = raw
+            .rrule
+            .as_deref()
+            .filter(|_| raw.recurrence_id.is_none())
+            .and_then(parse_rrule);
+        let Some(rule) = rule else {
+            import
+                .events
+                .push(user_event(raw, &uid, local_start, minute));
+            continue;
+        };
+
+        let excluded: HashSet<i64> = raw
+            .exdates
+            .iter()
+            .map(|exdate| exdate.local(offset, &zones).0)
+            .chain(overridden.get(uid.as_str()).into_iter().flatten().copied())
+            .collect();
+        if excluded.is_empty()
+            && local_start == start.days
+            && start.keeps_local_time(&zones)
+            && let Some((repeat, week, weekday)) = rule.as_repeat(start.days)
+        {
+            let mut event = user_event(raw, &uid, local_start, minute);
+            event.repeat = repeat;
+            event.week = week;
+            event.weekday = weekday;
+            import.events.push(event);
+            continue;
+        }
+
+        // Each instance at its own offset: a zone's daylight time moves it
+        let instances = rule
+            .expand(start.days, last + 1)
+            .into_iter()
+            .map(|days| IcsTime { days, ..start }.local(offset, &zones))
+            .filter(|(days, _)| (first..=last).contains(days) && !excluded.contains(days))
+            .take(IMPORT_MAX_OCCURRENCES);
+        for (days, minute) in instances {
+            import.events.push(user_event(raw, &uid, days, minute));
+        }
+    }
+    Ok(import)
+}
+
+/// ৫টি ইভেন্ট আমদানি হয়েছে, with what was replaced and skipped
+pub fn import_summary(added: usize, replaced: usize, skipped: usize) -> String {
+    let mut summary = format!("{}টি ইভেন্ট আমদানি হয়েছে।", to_bangla_number(added as i32));
+    if replaced > 0 {
+        summary += &format!(
+            "\nআগের আমদানির {}টি ইভেন্ট বদলানো হয়েছে।",
+            to_bangla_number(replaced as i32)
+        );
+    }
+    if skipped > 0 {
+        summary += &format!(
+            "\n{}টি ইভেন্টের তারিখ বোঝা যায়নি, বাদ দেওয়া হয়েছে।",
+            to_bangla_number(skipped as i32)
+        );
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::merge_imported;
+
+    /// A calendar file around some VEVENT (and VTIMEZONE) lines
+    fn calendar(body: &str) -> String {
+        format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{}\r\nEND:VCALENDAR\r\n",
+            body.trim().replace('\n', "\r\n")
+        )
+    }
+
+    fn dates(import: &IcsImport) -> Vec<(i32, i32, i32)> {
+        import
+            .events
+            .iter()
+            .map(|e| (e.year, e.month, e.day))
+            .collect()
+    }
+
+    const TODAY: i64 = 20089; // 2025-01-01
+
+    #[test]
+    fn continuation_lines_are_unfolded() {
+        let lines = unfold("SUMMARY:পয়লা\r\n  বৈশাখ\r\n\tমেলা\r\n\r\nUID:1\r\n");
+        assert_eq!(lines, ["SUMMARY:পয়লা বৈশাখমেলা", "UID:1"]);
+
+        let import = parse_ics(
+            &calendar(
+                "BEGIN:VEVENT\nUID:folded\nDTSTART;VALUE=DA\n TE:20250414\nSUMMARY:Pohela\n  Boishakh\nEND:VEVENT",
+            ),
+            TODAY,
+        )
+        .unwrap();
+        assert_eq!(dates(&import), [(2025, 4, 14)]);
+        assert_eq!(import.events[0].title, "Pohela Boishakh");
+    }
+
+    #[test]
+    fn time_zones_follow_standard_and_daylight_time() {
+        let import = parse_ics(
+            &calendar(
+                "BEGIN:VTIMEZONE
+TZID:America/New_York
+BEGIN:DAYLIGHT
+DTSTART:20070311T020000
+RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=2SU
+TZOFFSETFROM:-0500
+TZOFFSETTO:-0400
+END:DAYLIGHT
+BEGIN:STANDARD
+DTSTART:20071104T020000
+RRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=1SU
+TZOFFSETFROM:-0400
+TZOFFSETTO:-0500
+END:STANDARD
+END:VTIMEZONE
+BEGIN:VEVENT
+UID:meeting
+DTSTART;TZID=America/New_York:20250106T090000
+RRULE:FREQ=MONTHLY;COUNT=12
+END:VEVENT
+BEGIN:VEVENT
+UID:kolkata
+DTSTART;TZID=Asia/Kolkata:20250301T100000
+END:VEVENT
+BEGIN:VEVENT
+UID:utc
+DTSTART:20250301T200000Z
+END:VEVENT",
+            ),
+            TODAY,
+        )
+        .unwrap();
+        let time = |uid: &str, month: i32| {
+            import
+                .events
+                .iter()
+                .find(|e| e.uid.as_deref() == Some(uid) && e.month == month)
+                .and_then(|e| e.time.clone())
+        };
+        // 09:00 in New York is 20:00 in Dhaka in winter and 19:00 in summer
+        assert_eq!(time("meeting", 1).as_deref(), Some("20:00"));
+        assert_eq!(time("meeting", 3).as_deref(), Some("20:00"));
+        assert_eq!(time("meeting", 4).as_deref(), Some("19:00"));
+        assert_eq!(time("meeting", 7).as_deref(), Some("19:00"));
+        assert_eq!(time("meeting", 10).as_deref(), Some("19:00"));
+        assert_eq!(time("meeting", 11).as_deref(), Some("20:00"));
+        assert_eq!(time("kolkata", 3).as_deref(), Some("10:30"));
+        // 20:00 UTC is past midnight in Dhaka
+        let utc = import
+            .events
+            .iter()
+            .find(|e| e.uid.as_deref() == Some("utc"));
+        assert_eq!(
+            utc.map(|e| (e.day, e.time.as_deref())),
+            Some((2, Some("02:00")))
+        );
+    }
+
+    #[test]
+    fn count_and_until_end_a_rule() {
+        let import = parse_ics(
+            &calendar(
+                "BEGIN:VEVENT
+UID:count
+DTSTART;VALUE=DATE:20250105
+RRULE:FREQ=DAILY;COUNT=3
+END:VEVENT
+BEGIN:VEVENT
+UID:until
+DTSTART;VALUE=DATE:20250106
+RRULE:FREQ=WEEKLY;UNTIL=20250120
+END:VEVENT",
+            ),
+            TODAY,
+        )
+        .unwrap();
+        assert_eq!(
+            dates(&import),
+            [
+                (2025, 1, 5),
+                (2025, 1, 6),
+                (2025, 1, 7),
+                (2025, 1, 6),
+                (2025, 1, 13),
+                (2025, 1, 20)
+            ]
+        );
+        assert!(import.events.iter().all(|e| e.repeat == Repeat::Never));
+    }
+
+    #[test]
+    fn exdates_and_recurrence_ids_replace_instances() {
+        let import = parse_ics(
+            &calendar(
+                "BEGIN:VEVENT
+UID:weekly
+DTSTART;VALUE=DATE:20250106
+RRULE:FREQ=WEEKLY;COUNT=5
+EXDATE;VALUE=DATE:20250113
+SUMMARY:Class
+END:VEVENT
+BEGIN:VEVENT
+UID:weekly
+RECURRENCE-ID;VALUE=DATE:20250120
+DTSTART;VALUE=DATE:20250122
+SUMMARY:Class (moved)
+END:VEVENT
+BEGIN:VEVENT
+UID:weekly
+RECURRENCE-ID;VALUE=DATE:20250127
+DTSTART;VALUE=DATE:20250127
+STATUS:CANCELLED
+END:VEVENT",
+            ),
+            TODAY,
+        )
+        .unwrap();
+        assert_eq!(dates(&import), [(2025, 1, 6), (2025, 2, 3), (2025, 1, 22)]);
+        assert_eq!(import.events[2].title, "Class (moved)");
+    }
+
+    #[test]
+    fn yearly_rules_on_leap_day_skip_common_years() {
+        let import = parse_ics(
+            &calendar(
+                "BEGIN:VEVENT
+UID:leap
+DTSTART;VALUE=DATE:20240229
+RRULE:FREQ=YEARLY
+END:VEVENT
+BEGIN:VEVENT
+UID:birthday
+DTSTART;VALUE=DATE:20240301
+RRULE:FREQ=YEARLY
+END:VEVENT",
+            ),
+            TODAY,
+        )
+        .unwrap();
+        // 2024-01-01 to 2027-01-02 has one 29 February
+        assert_eq!(dates(&import), [(2024, 2, 29), (2024, 3, 1)]);
+        assert_eq!(import.events[0].repeat, Repeat::Never);
+        assert_eq!(import.events[1].repeat, Repeat::Yearly);
+    }
+
+    #[test]
+    fn importing_a_file_again_replaces_its_events() {
+        let text = calendar(
+            "BEGIN:VEVENT
+UID:weekly
+DTSTART;VALUE=DATE:20250106
+RRULE:FREQ=WEEKLY;COUNT=3
+END:VEVENT
+BEGIN:VEVENT
+UID:once
+DTSTART;VALUE=DATE:20250210
+END:VEVENT",
+        );
+        let own = UserEvent {
+            id: 1,
+            title: "নিজের".to_string(),
+            ..Default::default()
+        };
+        let first = parse_ics(&text, TODAY).unwrap();
+        let (events, replaced) = merge_imported(&[own], first.events);
+        assert_eq!((events.len(), replaced), (5, 0));
+
+        let again = parse_ics(&text, TODAY).unwrap();
+        let (events, replaced) = merge_imported(&events, again.events);
+        assert_eq!((events.len(), replaced), (5, 4));
+        assert_eq!(events[0].title, "নিজের");
+        let ids: HashSet<u64> = events.iter().map(|e| e.id).collect();
+        assert_eq!(ids.len(), events.len());
+    }
+}
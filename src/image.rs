@@ -0,0 +1,508 @@
+// image.rs - The punjika month and the widget as SVG or PNG images
+//
+// Drawn with the same geometry, fonts and colors as the windows, through the
+// GDI-free canvas, for newsletters and web pages. The month image shows the
+// holidays, today, পূর্ণিমা/অমাবস্যা and eclipses, but not personal events;
+// the navigation bar shows the Gregorian months instead of the buttons.
+// The wallpaper puts the widget's lines and the month over a picture.
+
+use crate::calendar::{get_current_bangla_date, gregorian_span, to_bangla_number};
+use crate::canvas::{Align, Canvas, PngCanvas, Rect, SvgCanvas, TextFont};
+use crate::constants::*;
+use crate::eclipses::Visibility;
+use crate::locations::current_location;
+use crate::month_layout::{MonthLayout, MonthMarks, current_week};
+use crate::panchang::MoonPhase;
+use jpeg_decoder::PixelFormat;
+use tiny_skia::{FilterQuality, IntSize, Pixmap, PixmapPaint, Transform};
+
+const CAL_SEPARATOR: u32 = 0x00E0E0E0;
+const CAL_TODAY_RADIUS: f32 = 4.0;
+
+// Wallpaper layout, in pixels at the reference screen height
+const WALLPAPER_REFERENCE_HEIGHT: f32 = 900.0;
+const WALLPAPER_MARGIN: i32 = 48; // clear of the taskbar
+const WALLPAPER_GAP: i32 = 8; // between the date and the month
+
+/// Image file formats
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageFormat {
+    Svg,
+    Png,
+}
+
+impl ImageFormat {
+    pub fn from_name(name: &str) -> Option<ImageFormat> {
+        match name.to_ascii_lowercase().as_str() {
+            "svg" => Some(ImageFormat::Svg),
+            "png" => Some(ImageFormat::Png),
+            _ => None,
+        }
+    }
+}
+
+fn font(height: i32, bold: bool) -> TextFont {
+    TextFont {
+        height: height as f32,
+        bold,
+    }
+}
+
+/// Draw a scene of the given size into an image file
+fn render(
+    width: f32,
+    height: f32,
+    format: ImageFormat,
+    scale: f32,
+    draw: impl Fn(&mut dyn Canvas),
+) -> Vec<u8> {
+    match format {
+        ImageFormat::Svg => {
+            let mut canvas = SvgCanvas::new(width, height);
+            draw(&mut canvas);
+            canvas.finish(scale).into_bytes()
+        }
+        ImageFormat::Png => match PngCanvas::new(width, height, scale) {
+            Some(mut canvas) => {
+                draw(&mut canvas);
+                canvas.finish()
+            }
+            None => Vec::new(),
+        },
+    }
+}
+
+/// Draw a Bangla month (0-11) as the punjika shows it
+fn draw_month(canvas: &mut dyn Canvas, layout: &MonthLayout, height: f32) {
+    let width = layout.width;
+    let month = layout.month;
+    let padding = CAL_PADDING as f32;
+
+    // Background and header
+    canvas.fill_rect(Rect::new(0.0, 0.0, width, height), 0.0, CAL_BG);
+    canvas.fill_rect(layout.header_rect, 0.0, CAL_HEADER_BG);
+    canvas.text(
+        BANGLA_MONTHS[month as usize],
+        Rect::new(padding, 8.0, width - padding, 32.0),
+        font(CAL_HEADER_FONT_SIZE, true),
+        CAL_HEADER_TEXT,
+        Align::Center,
+        false,
+    );
+    let sub_text = format!(
+        "{} বঙ্গাব্দ · {}কাল",
+        to_bangla_number(layout.year),
+        BANGLA_SEASONS[month as usize]
+    );
+    canvas.text(
+        &sub_text,
+        Rect::new(
+            padding,
+            34.0,
+            width - padding,
+            layout.header_rect.bottom - 4.0,
+        ),
+        font(CAL_SUB_FONT_SIZE, false),
+        CAL_HEADER_TEXT,
+        Align::Center,
+        false,
+    );
+
+    // Navigation bar, with the Gregorian months in place of the buttons
+    let nav_rect = layout.nav_rect;
+    canvas.fill_rect(nav_rect, 0.0, CAL_NAV_BG);
+    canvas.text(
+        &gregorian_span(layout.first_days, layout.last_days),
+        Rect::new(
+            padding,
+            nav_rect.top + 8.0,
+            width - padding,
+            nav_rect.bottom - 8.0,
+        ),
+        font(CAL_NAV_FONT_SIZE, false),
+        CAL_NAV_TEXT,
+        Align::Center,
+        true,
+    );
+
+    // Weekday headers and the separator line
+    for column in 0..7 {
+        let weekday = layout.week.weekday(column);
+        canvas.text(
+            BANGLA_WEEKDAYS_SHORT[weekday],
+            layout.weekday_rect(column),
+            font(CAL_WEEKDAY_FONT_SIZE, true),
+            if layout.week.is_weekend(weekday) {
+                CAL_WEEKEND_TEXT
+            } else {
+                CAL_WEEKDAY_TEXT
+            },
+            Align::Center,
+            true,
+        );
+    }
+    let sep_y = layout.separator_y;
+    canvas.line(padding, sep_y, width - padding, sep_y, CAL_SEPARATOR);
+
+    // Date grid
+    let moon_size = CAL_MOON_SIZE as f32;
+    for cell in &layout.cells {
+        let cell_rect = cell.inner_rect();
+        let text_color = if cell.is_today {
+            canvas.fill_rect(cell_rect, CAL_TODAY_RADIUS, CAL_TODAY_BG);
+            CAL_TODAY_TEXT
+        } else {
+            if cell.is_weekend {
+                canvas.fill_rect(cell_rect, CAL_TODAY_RADIUS, CAL_WEEKEND_BG);
+            }
+            cell.date_color()
+        };
+        canvas.text(
+            &to_bangla_number(cell.day),
+            cell_rect,
+            font(CAL_DATE_FONT_SIZE, false),
+            text_color,
+            Align::Center,
+            true,
+        );
+
+        // Full/new moon dot in the top-right corner
+        if let Some(phase) = cell.moon_phase {
+            let fill = match phase {
+                MoonPhase::Full => CAL_FULL_MOON,
+                MoonPhase::New => CAL_NEW_MOON,
+            };
+            canvas.ellipse(
+                Rect::new(
+                    cell_rect.right - moon_size - 3.0,
+                    cell_rect.top + 3.0,
+                    cell_rect.right - 3.0,
+                    cell_rect.top + 3.0 + moon_size,
+                ),
+                Some(fill),
+                CAL_MOON_OUTLINE,
+            );
+        }
+
+        // Eclipse dot in the top-left corner, hollow when not visible here
+        if let Some(eclipse) = cell.eclipse {
+            let color = if eclipse.kind.is_solar() {
+                CAL_SOLAR_ECLIPSE
+            } else {
+                CAL_LUNAR_ECLIPSE
+            };
+            let fill = (eclipse.visibility != Visibility::NotVisible).then_some(color);
+            canvas.ellipse(
+                Rect::new(
+                    cell_rect.left + 3.0,
+                    cell_rect.top + 3.0,
+                    cell_rect.left + 3.0 + moon_size,
+                    cell_rect.top + 3.0 + moon_size,
+                ),
+                fill,
+                color,
+            );
+        }
+    }
+
+    // Footer: today's holidays
+    if let Some(today) = layout.cells.iter().find(|cell| cell.is_today) {
+        let names: Vec<&str> = layout
+            .holidays_on(today.days)
+            .map(|h| h.name.as_str())
+            .collect();
+        if !names.is_empty() {
+            let footer_text = format!(
+                "{} {}: {}",
+                to_bangla_number(today.day),
+                BANGLA_MONTHS[month as usize],
+                names.join(", ")
+            );
+            canvas.text(
+                &footer_text,
+                layout.footer_rect(height),
+                font(CAL_SUB_FONT_SIZE, false),
+                CAL_FOOTER_TEXT,
+                Align::Center,
+                true,
+            );
+        }
+    }
+}
+
+/// A Bangla month (0-11) as the punjika draws it; the image is as tall as the
+/// month's rows need
+pub fn month_image(year: i32, month: i32, format: ImageFormat, scale: f32) -> Vec<u8> {
+    let layout = MonthLayout::new(
+        year,
+        month,
+        CAL_WIDTH as f32,
+        &get_current_bangla_date(),
+        current_week(),
+        MonthMarks::for_month(year, month, current_location()),
+    );
+    layout_image(&layout, format, scale)
+}
+
+/// A laid out month as an image, as tall as its rows need
+fn layout_image(layout: &MonthLayout, format: ImageFormat, scale: f32) -> Vec<u8> {
+    let height = layout.height();
+    render(layout.width, height, format, scale, |canvas| {
+        draw_month(canvas, layout, height)
+    })
+}
+
+/// A line of the widget: text, top, bottom, font and color
+type WidgetLine = (String, f32, f32, TextFont, u32);
+
+/// Today's lines, as the widget paints them
+fn widget_lines() -> [WidgetLine; 3] {
+    let date = get_current_bangla_date();
+    [
+        (
+            date.format_line1(),
+            12.0,
+            38.0,
+            font(WIDGET_LINE1_FONT_SIZE, true),
+            TEXT_PRIMARY,
+        ),
+        (
+            date.format_line2(),
+            38.0,
+            60.0,
+            font(WIDGET_LINE2_FONT_SIZE, false),
+            TEXT_PRIMARY,
+        ),
+        (
+            date.format_line3(),
+            62.0,
+            82.0,
+            font(WIDGET_LINE3_FONT_SIZE, false),
+            TEXT_SECONDARY,
+        ),
+    ]
+}
+
+/// The widget's rounded box and lines, `width` wide
+fn draw_widget(canvas: &mut dyn Canvas, width: f32, lines: &[WidgetLine]) {
+    let rect = Rect::new(0.0, 0.0, width, WIDGET_HEIGHT as f32);
+    let radius = CORNER_RADIUS as f32;
+    canvas.fill_rect(rect, radius, BG_COLOR);
+    canvas.stroke_rect(rect, radius, BORDER_COLOR);
+    for (text, top, bottom, font, color) in lines {
+        canvas.text(
+            text,
+            Rect::new(PADDING as f32, *top, width - PADDING as f32, *bottom),
+            *font,
+            *color,
+            Align::Center,
+            false,
+        );
+    }
+}
+
+/// Today's three-line widget, on a transparent background
+pub fn widget_image(format: ImageFormat, scale: f32) -> Vec<u8> {
+    let lines = widget_lines();
+
+    // As wide as the longest line, but no narrower than the widget gets
+    let text_width = lines
+        .iter()
+        .map(|(text, _, _, font, _)| font.measure(text))
+        .fold(120.0, f32::max)
+        .ceil();
+    let width = text_width + (PADDING * 2) as f32;
+    let height = WIDGET_HEIGHT as f32;
+
+    render(width, height, format, scale, |canvas| {
+        draw_widget(canvas, width, &lines)
+    })
+}
+
+/// Screen corner for the wallpaper's calendar
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Corner {
+    #[cfg(windows)]
+    pub const ALL: [Corner; 4] = [
+        Corner::TopLeft,
+        Corner::TopRight,
+        Corner::BottomLeft,
+        Corner::BottomRight,
+    ];
+
+    /// A stored index into ALL; bottom right if it is out of range
+    #[cfg(windows)]
+    pub fn from_index(index: u32) -> Corner {
+        Corner::ALL
+            .get(index as usize)
+            .copied()
+            .unwrap_or(Corner::BottomRight)
+    }
+
+    pub fn from_name(name: &str) -> Option<Corner> {
+        match name.to_ascii_lowercase().as_str() {
+            "top-left" => Some(Corner::TopLeft),
+            "top-right" => Some(Corner::TopRight),
+            "bottom-left" => Some(Corner::BottomLeft),
+            "bottom-right" => Some(Corner::BottomRight),
+            _ => None,
+        }
+    }
+}
+
+/// Decode a PNG or JPEG file
+fn decode_image(data: &[u8]) -> Result<Pixmap, String> {
+    if data.starts_with(b"\x89PNG") {
+        return Pixmap::decode_png(data).map_err(|e| e.to_string());
+    }
+    let mut decoder = jpeg_decoder::Decoder::new(data);
+    let pixels = decoder.decode().map_err(|e| e.to_string())?;
+    let info = decoder.info().ok_or("ছবির মাপ বোঝা যায়নি")?;
+    let rgba: Vec<u8> = match info.pixel_format {
+        PixelFormat::L8 => pixels.iter().flat_map(|&l| [l, l, l, 255]).collect(),
+        PixelFormat::L16 => pixels
+            .chunks_exact(2)
+            .flat_map(|l| [l[0], l[0], l[0], 255])
+            .collect(),
+        PixelFormat::RGB24 => pixels
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        PixelFormat::CMYK32 => pixels
+            .chunks_exact(4)
+            .flat_map(|p| {
+                let k = 255 - p[3] as u32;
+                let channel = |c: u8| ((255 - c as u32) * k / 255) as u8;
+                [channel(p[0]), channel(p[1]), channel(p[2]), 255]
+            })
+            .collect(),
+    };
+    let size = IntSize::from_wh(info.width as u32, info.height as u32).ok_or("ছবির মাপ বোঝা যায়নি")?;
+    Pixmap::from_vec(rgba, size).ok_or_else(|| "ছবির মাপ বোঝা যায়নি".to_string())
+}
+
+/// A wallpaper: `source` (PNG or JPEG) cropped to fill the screen, with
+/// today's date and the current month in one corner, sized for the screen
+pub fn wallpaper_image(
+    source: &[u8],
+    width: u32,
+    height: u32,
+    corner: Corner,
+) -> Result<Vec<u8>, String> {
+    let picture = decode_image(source)?;
+    let mut pixmap = Pixmap::new(width, height).ok_or("পর্দার মাপ ঠিক নেই")?;
+
+    // Fill the screen, cropping the picture evenly
+    let fill = (width as f32 / picture.width() as f32).max(height as f32 / picture.height() as f32);
+    let transform = Transform::from_row(
+        fill,
+        0.0,
+        0.0,
+        fill,
+        (width as f32 - picture.width() as f32 * fill) / 2.0,
+        (height as f32 - picture.height() as f32 * fill) / 2.0,
+    );
+    let paint = PixmapPaint {
+        quality: FilterQuality::Bicubic,
+        ..PixmapPaint::default()
+    };
+    pixmap.draw_pixmap(0, 0, picture.as_ref(), &paint, transform, None);
+
+    // The widget's lines above the month, scaled with the screen
+    let today = get_current_bangla_date();
+    let layout = MonthLayout::new(
+        today.year,
+        today.month,
+        CAL_WIDTH as f32,
+        &today,
+        current_week(),
+        MonthMarks::for_month(today.year, today.month, current_location()),
+    );
+    let panel_width = layout.width;
+    let month_top = (WIDGET_HEIGHT + WALLPAPER_GAP) as f32;
+    let month_height = layout.height();
+    let panel_height = month_top + month_height;
+    let scale = (height as f32 / WALLPAPER_REFERENCE_HEIGHT).max(1.0);
+    let margin = WALLPAPER_MARGIN as f32 * scale;
+    let x = match corner {
+        Corner::TopLeft | Corner::BottomLeft => margin,
+        Corner::TopRight | Corner::BottomRight => width as f32 - margin - panel_width * scale,
+    };
+    let y = match corner {
+        Corner::TopLeft | Corner::TopRight => margin,
+        Corner::BottomLeft | Corner::BottomRight => height as f32 - margin - panel_height * scale,
+    };
+
+    let mut canvas = PngCanvas::on_pixmap(pixmap, x, y, scale);
+    draw_widget(&mut canvas, panel_width, &widget_lines());
+    let mut canvas = PngCanvas::on_pixmap(canvas.into_pixmap(), x, y + month_top * scale, scale);
+    draw_month(&mut canvas, &layout, month_height);
+    Ok(canvas.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::BanglaDate;
+    use crate::month_layout::Week;
+    use std::path::PathBuf;
+
+    /// Compare with a file in src/snapshots, or rewrite it when
+    /// UPDATE_SNAPSHOTS is set
+    fn assert_snapshot(name: &str, actual: &str) {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/snapshots")
+            .join(name);
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::write(&path, actual).unwrap();
+            return;
+        }
+        let expected =
+            std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+        assert!(
+            expected == actual,
+            "{} differs; rerun with UPDATE_SNAPSHOTS=1 to accept the change",
+            path.display()
+        );
+    }
+
+    /// Boishakh 1432 with ৩ বৈশাখ as today, a full moon on ১৩ and no holidays
+    fn boishakh(week: Week) -> String {
+        let today = BanglaDate {
+            day: 3,
+            month: 0,
+            year: 1432,
+            weekday: 3,
+        };
+        let marks = MonthMarks {
+            moon_phases: vec![(
+                crate::calendar::days_from_civil(2025, 4, 26),
+                MoonPhase::Full,
+            )],
+            ..MonthMarks::default()
+        };
+        let layout = MonthLayout::new(1432, 0, CAL_WIDTH as f32, &today, week, marks);
+        String::from_utf8(layout_image(&layout, ImageFormat::Svg, 1.0)).unwrap()
+    }
+
+    #[test]
+    fn month_svg_matches_snapshot() {
+        assert_snapshot("month_1432_boishakh.svg", &boishakh(Week::default()));
+    }
+
+    #[test]
+    fn month_svg_from_saturday_matches_snapshot() {
+        let week = Week {
+            start: 6,
+            weekend: 1 << 5,
+        };
+        assert_snapshot("month_1432_boishakh_saturday.svg", &boishakh(week));
+    }
+}
@@ -0,0 +1,195 @@
+// locations.rs - Built-in places for sun/moon times and the panchang:
+// the 64 districts of Bangladesh and major cities of West Bengal
+
+use crate::LOCATION_SELECTION;
+#[cfg(windows)]
+use crate::astronomy::{jd_from_days, moon_rise_set, sunrise, sunset};
+use crate::constants::BD_UTC_OFFSET_MINUTES;
+use std::sync::atomic::Ordering;
+
+// India Standard Time (UTC+05:30)
+const IN_UTC_OFFSET_MINUTES: i32 = 330;
+
+/// Menu groups, in order: the eight divisions, then West Bengal
+#[cfg(windows)]
+pub const LOCATION_REGIONS: &[&str] = &[
+    "ঢাকা বিভাগ",
+    "চট্টগ্রাম বিভাগ",
+    "রাজশাহী বিভাগ",
+    "খুলনা বিভাগ",
+    "বরিশাল বিভাগ",
+    "সিলেট বিভাগ",
+    "রংপুর বিভাগ",
+    "ময়মনসিংহ বিভাগ",
+    "পশ্চিমবঙ্গ",
+];
+
+/// A place with its coordinates (degrees, east positive) and time zone
+pub struct Location {
+    // The menu's; the commands only need the coordinates
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub id: &'static str,
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub name: &'static str,
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub region: usize, // index into LOCATION_REGIONS
+    pub latitude: f64,
+    pub longitude: f64,
+    pub utc_offset_minutes: i32,
+}
+
+/// A district of Bangladesh
+const fn bd(id: &'static str, name: &'static str, region: usize, lat: f64, lon: f64) -> Location {
+    Location {
+        id,
+        name,
+        region,
+        latitude: lat,
+        longitude: lon,
+        utc_offset_minutes: BD_UTC_OFFSET_MINUTES,
+    }
+}
+
+/// A city of West Bengal
+const fn wb(id: &'static str, name: &'static str, lat: f64, lon: f64) -> Location {
+    Location {
+        id,
+        name,
+        region: 8,
+        latitude: lat,
+        longitude: lon,
+        utc_offset_minutes: IN_UTC_OFFSET_MINUTES,
+    }
+}
+
+// Ordered by region; the first entry (Dhaka) is the default
+pub const LOCATIONS: &[Location] = &[
+    // ঢাকা বিভাগ
+    bd("dhaka", "ঢাকা", 0, 23.8103, 90.4125),
+    bd("faridpur", "ফরিদপুর", 0, 23.6071, 89.8429),
+    bd("gazipur", "গাজীপুর", 0, 23.9999, 90.4203),
+    bd("gopalganj", "গোপালগঞ্জ", 0, 23.0050, 89.8266),
+    bd("kishoreganj", "কিশোরগঞ্জ", 0, 24.4449, 90.7766),
+    bd("madaripur", "মাদারীপুর", 0, 23.1641, 90.1897),
+    bd("manikganj", "মানিকগঞ্জ", 0, 23.8617, 90.0003),
+    bd("munshiganj", "মুন্সীগঞ্জ", 0, 23.5422, 90.5305),
+    bd("narayanganj", "নারায়ণগঞ্জ", 0, 23.6238, 90.5000),
+    bd("narsingdi", "নরসিংদী", 0, 23.9322, 90.7151),
+    bd("rajbari", "রাজবাড়ী", 0, 23.7574, 89.6445),
+    bd("shariatpur", "শরীয়তপুর", 0, 23.2423, 90.4348),
+    bd("tangail", "টাঙ্গাইল", 0, 24.2513, 89.9167),
+    // চট্টগ্রাম বিভাগ
+    bd("bandarban", "বান্দরবান", 1, 22.1953, 92.2184),
+    bd("brahmanbaria", "ব্রাহ্মণবাড়িয়া", 1, 23.9571, 91.1119),
+    bd("chandpur", "চাঁদপুর", 1, 23.2333, 90.6713),
+    bd("chattogram", "চট্টগ্রাম", 1, 22.3569, 91.7832),
+    bd("cumilla", "কুমিল্লা", 1, 23.4607, 91.1809),
+    bd("coxs-bazar", "কক্সবাজার", 1, 21.4272, 92.0058),
+    bd("feni", "ফেনী", 1, 23.0159, 91.3976),
+    bd("khagrachhari", "খাগড়াছড়ি", 1, 23.1193, 91.9847),
+    bd("lakshmipur", "লক্ষ্মীপুর", 1, 22.9447, 90.8282),
+    bd("noakhali", "নোয়াখালী", 1, 22.8696, 91.0995),
+    bd("rangamati", "রাঙ্গামাটি", 1, 22.6533, 92.1750),
+    // রাজশাহী বিভাগ
+    bd("bogura", "বগুড়া", 2, 24.8465, 89.3773),
+    bd("joypurhat", "জয়পুরহাট", 2, 25.0968, 89.0227),
+    bd("naogaon", "নওগাঁ", 2, 24.7936, 88.9318),
+    bd("natore", "নাটোর", 2, 24.4206, 89.0003),
+    bd("chapainawabganj", "চাঁপাইনবাবগঞ্জ", 2, 24.5965, 88.2775),
+    bd("pabna", "পাবনা", 2, 24.0064, 89.2372),
+    bd("rajshahi", "রাজশাহী", 2, 24.3745, 88.6042),
+    bd("sirajganj", "সিরাজগঞ্জ", 2, 24.4534, 89.7007),
+    // খুলনা বিভাগ
+    bd("bagerhat", "বাগেরহাট", 3, 22.6516, 89.7859),
+    bd("chuadanga", "চুয়াডাঙ্গা", 3, 23.6402, 88.8418),
+    bd("jashore", "যশোর", 3, 23.1664, 89.2081),
+    bd("jhenaidah", "ঝিনাইদহ", 3, 23.5450, 89.1726),
+    bd("khulna", "খুলনা", 3, 22.8456, 89.5403),
+    bd("kushtia", "কুষ্টিয়া", 3, 23.9013, 89.1204),
+    bd("magura", "মাগুরা", 3, 23.4873, 89.4199),
+    bd("meherpur", "মেহেরপুর", 3, 23.7622, 88.6318),
+    bd("narail", "নড়াইল", 3, 23.1725, 89.5127),
+    bd("satkhira", "সাতক্ষীরা", 3, 22.7185, 89.0705),
+    // বরিশাল বিভাগ
+    bd("barguna", "বরগুনা", 4, 22.0953, 90.1121),
+    bd("barishal", "বরিশাল", 4, 22.7010, 90.3535),
+    bd("bhola", "ভোলা", 4, 22.6859, 90.6482),
+    bd("jhalokati", "ঝালকাঠি", 4, 22.6406, 90.1987),
+    bd("patuakhali", "পটুয়াখালী", 4, 22.3596, 90.3299),
+    bd("pirojpur", "পিরোজপুর", 4, 22.5841, 89.9720),
+    // সিলেট বিভাগ
+    bd("habiganj", "হবিগঞ্জ", 5, 24.3840, 91.4169),
+    bd("moulvibazar", "মৌলভীবাজার", 5, 24.4829, 91.7774),
+    bd("sunamganj", "সুনামগঞ্জ", 5, 25.0658, 91.3950),
+    bd("sylhet", "সিলেট", 5, 24.8949, 91.8687),
+    // রংপুর বিভাগ
+    bd("dinajpur", "দিনাজপুর", 6, 25.6217, 88.6354),
+    bd("gaibandha", "গাইবান্ধা", 6, 25.3288, 89.5286),
+    bd("kurigram", "কুড়িগ্রাম", 6, 25.8054, 89.6362),
+    bd("lalmonirhat", "লালমনিরহাট", 6, 25.9923, 89.2847),
+    bd("nilphamari", "নীলফামারী", 6, 25.9310, 88.8560),
+    bd("panchagarh", "পঞ্চগড়", 6, 26.3411, 88.5542),
+    bd("rangpur", "রংপুর", 6, 25.7439, 89.2752),
+    bd("thakurgaon", "ঠাকুরগাঁও", 6, 26.0336, 88.4616),
+    // ময়মনসিংহ বিভাগ
+    bd("jamalpur", "জামালপুর", 7, 24.9375, 89.9372),
+    bd("mymensingh", "ময়মনসিংহ", 7, 24.7471, 90.4203),
+    bd("netrokona", "নেত্রকোনা", 7, 24.8703, 90.7279),
+    bd("sherpur", "শেরপুর", 7, 25.0205, 90.0153),
+    // পশ্চিমবঙ্গ
+    wb("kolkata", "কলকাতা", 22.5726, 88.3639),
+    wb("howrah", "হাওড়া", 22.5958, 88.2636),
+    wb("siliguri", "শিলিগুড়ি", 26.7271, 88.3953),
+    wb("darjeeling", "দার্জিলিং", 27.0410, 88.2663),
+    wb("asansol", "আসানসোল", 23.6739, 86.9524),
+    wb("durgapur", "দুর্গাপুর", 23.5204, 87.3119),
+    wb("bardhaman", "বর্ধমান", 23.2324, 87.8615),
+    wb("kharagpur", "খড়গপুর", 22.3460, 87.2320),
+    wb("haldia", "হলদিয়া", 22.0667, 88.0698),
+    wb("krishnanagar", "কৃষ্ণনগর", 23.4058, 88.4901),
+    wb("baharampur", "বহরমপুর", 24.1000, 88.2500),
+    wb("malda", "মালদা", 25.0108, 88.1411),
+    wb("cooch-behar", "কোচবিহার", 26.3452, 89.4482),
+    wb("jalpaiguri", "জলপাইগুড়ি", 26.5167, 88.7196),
+    wb("santiniketan", "শান্তিনিকেতন", 23.6803, 87.6855),
+    wb("medinipur", "মেদিনীপুর", 22.4257, 87.3199),
+    wb("bankura", "বাঁকুড়া", 23.2324, 87.0746),
+    wb("purulia", "পুরুলিয়া", 23.3321, 86.3652),
+];
+
+/// Index of a location by id
+#[cfg(windows)]
+pub fn find_location(id: &str) -> Option<usize> {
+    LOCATIONS.iter().position(|l| l.id == id)
+}
+
+/// The location chosen in the menu
+pub fn current_location() -> &'static Location {
+    LOCATIONS
+        .get(LOCATION_SELECTION.load(Ordering::Relaxed) as usize)
+        .unwrap_or(&LOCATIONS[0])
+}
+
+impl Location {
+    /// Julian Day (UT) of local midnight starting a day number
+    #[cfg(windows)]
+    pub fn day_start(&self, days: i64) -> f64 {
+        jd_from_days(days) - self.utc_offset_minutes as f64 / 1440.0
+    }
+
+    /// Sunrise and sunset on a local day
+    #[cfg(windows)]
+    pub fn sun_times(&self, days: i64) -> (Option<f64>, Option<f64>) {
+        let day_start = self.day_start(days);
+        (
+            sunrise(day_start, self.latitude, self.longitude),
+            sunset(day_start, self.latitude, self.longitude),
+        )
+    }
+
+    /// Moonrise and moonset on a local day (either may not happen)
+    #[cfg(windows)]
+    pub fn moon_times(&self, days: i64) -> (Option<f64>, Option<f64>) {
+        moon_rise_set(self.day_start(days), self.latitude, self.longitude)
+    }
+}
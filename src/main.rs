@@ -1,49 +1,128 @@
 #![windows_subsystem = "windows"]
 
+mod astronomy;
 mod calendar;
+mod canvas;
+mod cli;
 mod constants;
+#[cfg(windows)]
+mod date_prompt;
+#[cfg(windows)]
+mod day_view;
+mod eclipses;
+#[cfg(windows)]
+mod event_editor;
+mod events;
+#[cfg(windows)]
+mod export;
+mod feed_server;
+mod festivals;
+#[cfg(windows)]
 mod fonts;
+mod hijri;
+mod holidays;
+mod html;
+mod ics;
+mod ics_import;
+mod image;
+mod locations;
+#[cfg(windows)]
 mod menu;
+mod month_layout;
+mod panchang;
+mod paths;
+mod pdf;
+#[cfg(windows)]
+mod prayer;
+#[cfg(windows)]
+mod prohor;
+#[cfg(windows)]
 mod punjika;
+#[cfg(windows)]
+mod ramadan;
+#[cfg(windows)]
 mod registry;
+mod shaping;
+mod status;
+#[cfg(windows)]
 mod ui;
-
-use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicU32, Ordering};
+mod wallpaper;
+
+#[cfg(windows)]
+use std::sync::atomic::AtomicPtr;
+#[cfg(windows)]
+use std::sync::atomic::Ordering;
+use std::sync::atomic::{AtomicBool, AtomicU32};
+#[cfg(windows)]
 use windows::{
     Win32::Foundation::*, Win32::Graphics::Dwm::*, Win32::Graphics::Gdi::*,
     Win32::System::LibraryLoader::GetModuleHandleW, Win32::UI::WindowsAndMessaging::*, core::*,
 };
 
+use cli::run_cli;
 use constants::*;
-use fonts::install_fonts;
-use menu::*;
-use punjika::show_calendar;
-use registry::*;
-use ui::*;
+#[cfg(windows)]
+use {
+    date_prompt::is_date_prompt_message,
+    event_editor::is_editor_message,
+    events::load_events,
+    export::{
+        export_html_file, export_ics_file, export_image_file, export_pdf_file, import_ics_file,
+    },
+    feed_server::{start_feed_server, stop_feed_server},
+    fonts::install_fonts,
+    holidays::load_holiday_packs,
+    image::Corner,
+    locations::LOCATIONS,
+    menu::*,
+    month_layout::CellLabel,
+    prayer::PRAYER_METHODS,
+    punjika::{refresh_calendar, resize_calendar, show_calendar},
+    registry::*,
+    ui::*,
+    wallpaper::{choose_wallpaper_image, disable_wallpaper, refresh_wallpaper},
+};
 
 // Embed the ICO file
+#[cfg(windows)]
 const FLAG_ICO_DATA: &[u8] = include_bytes!("../assets/Flag_of_Bangladesh.ico");
 
 // Global state (thread-safe)
 pub static AUTOSTART_ENABLED: AtomicBool = AtomicBool::new(false);
 pub static COUNTRY_SELECTION: AtomicU32 = AtomicU32::new(0); // 0 = Bangladesh, 1 = India
+pub static WIDGET_LINES: AtomicU32 = AtomicU32::new(0); // WIDGET_LINE_* flags
+pub static LOCATION_SELECTION: AtomicU32 = AtomicU32::new(0); // Index into LOCATIONS
+pub static PRAYER_METHOD: AtomicU32 = AtomicU32::new(0); // Index into PRAYER_METHODS
+pub static ASR_RULE: AtomicU32 = AtomicU32::new(0); // 0 = Hanafi, 1 = Standard
+pub static FEED_SERVER_ENABLED: AtomicBool = AtomicBool::new(false);
+pub static FEED_SERVER_PORT: AtomicU32 = AtomicU32::new(DEFAULT_FEED_SERVER_PORT as u32);
+pub static WALLPAPER_ENABLED: AtomicBool = AtomicBool::new(false);
+pub static WALLPAPER_CORNER: AtomicU32 = AtomicU32::new(3); // Index into Corner::ALL
+pub static PUNJIKA_SIX_ROWS: AtomicBool = AtomicBool::new(false);
+pub static PUNJIKA_CELL_LABEL: AtomicU32 = AtomicU32::new(0); // Index into CellLabel::ALL
+pub static WEEK_START: AtomicU32 = AtomicU32::new(0); // Sunday = 0
+pub static WEEKEND_DAYS: AtomicU32 = AtomicU32::new(DEFAULT_WEEKEND_DAYS); // Bit per weekday
 
 // Thread-safe handle for flag icon
+#[cfg(windows)]
 static FLAG_ICON_PTR: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(std::ptr::null_mut());
 
 // Storage for owner-drawn menu item strings
 pub static MENU_STRINGS: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(Vec::new());
 
+#[cfg(windows)]
 #[inline]
 pub fn get_flag_icon() -> HICON {
     HICON(FLAG_ICON_PTR.load(Ordering::Relaxed))
 }
 
+#[cfg(windows)]
 #[inline]
 fn set_flag_icon(icon: HICON) {
     FLAG_ICON_PTR.store(icon.0, Ordering::Relaxed);
 }
 
+#[cfg(windows)]
 /// Load Bangladesh flag icon from embedded ICO file
 fn create_flag_icon() {
     unsafe {
@@ -55,7 +134,35 @@ fn create_flag_icon() {
     }
 }
 
+#[cfg(windows)]
+/// Start the local feed server and remember it; a port in use is reported
+fn enable_feed_server(owner: Option<HWND>) {
+    let port = FEED_SERVER_PORT.load(Ordering::Relaxed) as u16;
+    match start_feed_server(port) {
+        Ok(()) => {
+            FEED_SERVER_ENABLED.store(true, Ordering::Relaxed);
+            unsafe { save_feed_server(true) };
+        }
+        Err(e) => unsafe {
+            FEED_SERVER_ENABLED.store(false, Ordering::Relaxed);
+            let _ = MessageBoxW(
+                owner,
+                &HSTRING::from(format!("localhost:{}\n\n{}", port, e)),
+                w!("ফিড সার্ভার চালু করা যায়নি"),
+                MB_OK | MB_ICONWARNING,
+            );
+        },
+    }
+}
+
+#[cfg(windows)]
 fn main() -> Result<()> {
+    // Commands run headless and exit without touching the widget
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = run_cli(&args) {
+        std::process::exit(code);
+    }
+
     unsafe {
         // Enable autostart by default on first run
         let autostart_status = is_autostart_enabled();
@@ -65,11 +172,57 @@ fn main() -> Result<()> {
         }
         AUTOSTART_ENABLED.store(is_autostart_enabled(), Ordering::Relaxed);
         COUNTRY_SELECTION.store(load_country_selection(), Ordering::Relaxed);
+        WIDGET_LINES.store(load_widget_lines(), Ordering::Relaxed);
+        LOCATION_SELECTION.store(load_location(), Ordering::Relaxed);
+        PRAYER_METHOD.store(load_prayer_method(), Ordering::Relaxed);
+        ASR_RULE.store(load_asr_rule(), Ordering::Relaxed);
+        FEED_SERVER_ENABLED.store(load_feed_server(), Ordering::Relaxed);
+        FEED_SERVER_PORT.store(load_feed_server_port() as u32, Ordering::Relaxed);
+        WALLPAPER_ENABLED.store(load_wallpaper_image().is_some(), Ordering::Relaxed);
+        WALLPAPER_CORNER.store(load_wallpaper_corner(), Ordering::Relaxed);
+        PUNJIKA_SIX_ROWS.store(load_punjika_six_rows(), Ordering::Relaxed);
+        PUNJIKA_CELL_LABEL.store(load_punjika_cell_label(), Ordering::Relaxed);
+        WEEK_START.store(load_week_start(), Ordering::Relaxed);
+        WEEKEND_DAYS.store(load_weekend_days(), Ordering::Relaxed);
 
         // Install fonts once at startup - no more per-paint allocations
         install_fonts();
         create_flag_icon();
 
+        // Load holiday data packs; a broken pack is skipped but reported
+        let pack_errors = load_holiday_packs();
+        if !pack_errors.is_empty() {
+            let message = pack_errors
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            let _ = MessageBoxW(
+                None,
+                &HSTRING::from(message),
+                w!("ছুটির তালিকা লোড করা যায়নি"),
+                MB_OK | MB_ICONWARNING,
+            );
+        }
+
+        // Personal events; a broken file is reported and left as it is
+        if let Err(e) = load_events() {
+            let _ = MessageBoxW(
+                None,
+                &HSTRING::from(e.to_string()),
+                w!("ইভেন্ট লোড করা যায়নি"),
+                MB_OK | MB_ICONWARNING,
+            );
+        }
+
+        // Local feed server, if it was turned on
+        if FEED_SERVER_ENABLED.load(Ordering::Relaxed) {
+            enable_feed_server(None);
+        }
+
+        // Calendar wallpaper, if it was turned on (the date may have changed)
+        refresh_wallpaper(false);
+
         let instance = GetModuleHandleW(None)?;
         let window_class = w!("BanglaCalendarClass");
 
@@ -93,8 +246,10 @@ fn main() -> Result<()> {
         let screen_width = GetSystemMetrics(SM_CXSCREEN);
         let screen_height = GetSystemMetrics(SM_CYSCREEN);
 
-        // Calculate widget width based on text
+        // Calculate widget size based on text
+        refresh_extra_lines();
         let widget_width = calculate_widget_width();
+        let widget_height = widget_height();
 
         let (saved_x, saved_y) = load_position();
         let x = if saved_x >= 0 && saved_x < screen_width - 50 {
@@ -105,7 +260,7 @@ fn main() -> Result<()> {
         let y = if saved_y >= 0 && saved_y < screen_height - 50 {
             saved_y
         } else {
-            screen_height - WIDGET_HEIGHT - 80
+            screen_height - widget_height - 80
         };
 
         let hwnd = CreateWindowExW(
@@ -116,7 +271,7 @@ fn main() -> Result<()> {
             x,
             y,
             widget_width,
-            WIDGET_HEIGHT,
+            widget_height,
             None,
             None,
             Some(instance.into()),
@@ -144,10 +299,13 @@ fn main() -> Result<()> {
 
         set_desktop_level(hwnd);
         create_tray_icon(hwnd)?;
-        SetTimer(Some(hwnd), 1, 60000, None);
+        SetTimer(Some(hwnd), 1, widget_timer_interval(), None);
 
         let mut message = MSG::default();
         while GetMessageW(&mut message, None, 0, 0).into() {
+            if is_editor_message(&message) || is_date_prompt_message(&message) {
+                continue;
+            }
             let _ = TranslateMessage(&message);
             DispatchMessageW(&message);
         }
@@ -159,6 +317,7 @@ fn main() -> Result<()> {
     }
 }
 
+#[cfg(windows)]
 extern "system" fn wndproc(hwnd: HWND, message: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
     unsafe {
         match message {
@@ -169,7 +328,14 @@ extern "system" fn wndproc(hwnd: HWND, message: u32, wparam: WPARAM, lparam: LPA
             WM_DRAWITEM => handle_draw_item(lparam),
 
             WM_TIMER => {
-                let _ = InvalidateRect(Some(hwnd), None, true);
+                // The optional lines change length through the day
+                resize_widget(hwnd);
+                refresh_wallpaper(false);
+                LRESULT(0)
+            }
+
+            WM_DISPLAYCHANGE => {
+                refresh_wallpaper(true);
                 LRESULT(0)
             }
 
@@ -230,6 +396,82 @@ extern "system" fn wndproc(hwnd: HWND, message: u32, wparam: WPARAM, lparam: LPA
                     IDM_PUNJIKA => {
                         show_calendar(hwnd);
                     }
+                    IDM_EXPORT_ICS => {
+                        export_ics_file(hwnd);
+                    }
+                    IDM_EXPORT_PDF_MONTH => {
+                        export_pdf_file(hwnd, false);
+                    }
+                    IDM_EXPORT_PDF_YEAR => {
+                        export_pdf_file(hwnd, true);
+                    }
+                    IDM_EXPORT_IMAGE_MONTH => {
+                        export_image_file(hwnd, false);
+                    }
+                    IDM_EXPORT_IMAGE_WIDGET => {
+                        export_image_file(hwnd, true);
+                    }
+                    IDM_EXPORT_HTML => {
+                        export_html_file(hwnd);
+                    }
+                    IDM_IMPORT_ICS => {
+                        import_ics_file(hwnd);
+                    }
+                    IDM_FEED_SERVER_ON => {
+                        enable_feed_server(Some(hwnd));
+                    }
+                    IDM_FEED_SERVER_OFF => {
+                        stop_feed_server();
+                        FEED_SERVER_ENABLED.store(false, Ordering::Relaxed);
+                        save_feed_server(false);
+                    }
+                    IDM_FEED_SERVER_OPEN => {
+                        let port = FEED_SERVER_PORT.load(Ordering::Relaxed);
+                        open_url(&format!("http://127.0.0.1:{}/", port));
+                    }
+                    IDM_WALLPAPER_CHOOSE => {
+                        choose_wallpaper_image(hwnd);
+                    }
+                    IDM_WALLPAPER_OFF => {
+                        disable_wallpaper();
+                    }
+                    id if (IDM_WALLPAPER_CORNER_BASE
+                        ..IDM_WALLPAPER_CORNER_BASE + Corner::ALL.len() as u32)
+                        .contains(&id) =>
+                    {
+                        let corner = id - IDM_WALLPAPER_CORNER_BASE;
+                        WALLPAPER_CORNER.store(corner, Ordering::Relaxed);
+                        save_wallpaper_corner(corner);
+                        refresh_wallpaper(true);
+                    }
+                    IDM_PUNJIKA_SIX_ROWS => {
+                        let six_rows = !PUNJIKA_SIX_ROWS.fetch_xor(true, Ordering::Relaxed);
+                        save_punjika_six_rows(six_rows);
+                        resize_calendar();
+                    }
+                    id if (IDM_PUNJIKA_LABEL_BASE
+                        ..IDM_PUNJIKA_LABEL_BASE + CellLabel::ALL.len() as u32)
+                        .contains(&id) =>
+                    {
+                        let label = id - IDM_PUNJIKA_LABEL_BASE;
+                        PUNJIKA_CELL_LABEL.store(label, Ordering::Relaxed);
+                        save_punjika_cell_label(label);
+                        resize_calendar();
+                    }
+                    id if (IDM_WEEK_START_BASE..IDM_WEEK_START_BASE + 7).contains(&id) => {
+                        let weekday = id - IDM_WEEK_START_BASE;
+                        WEEK_START.store(weekday, Ordering::Relaxed);
+                        save_week_start(weekday);
+                        resize_calendar();
+                        refresh_wallpaper(true);
+                    }
+                    id if (IDM_WEEKEND_BASE..IDM_WEEKEND_BASE + 7).contains(&id) => {
+                        let bit = 1 << (id - IDM_WEEKEND_BASE);
+                        let days = WEEKEND_DAYS.fetch_xor(bit, Ordering::Relaxed) ^ bit;
+                        save_weekend_days(days);
+                        refresh_calendar();
+                        refresh_wallpaper(true);
+                    }
                     IDM_AUTOSTART_YES => {
                         toggle_autostart(true);
                     }
@@ -244,6 +486,54 @@ extern "system" fn wndproc(hwnd: HWND, message: u32, wparam: WPARAM, lparam: LPA
                     IDM_COUNTRY_IN => {
                         // India - disabled for now
                     }
+                    IDM_SHOW_TITHI => {
+                        let lines = WIDGET_LINES.fetch_xor(WIDGET_LINE_TITHI, Ordering::Relaxed)
+                            ^ WIDGET_LINE_TITHI;
+                        save_widget_lines(lines);
+                        resize_widget(hwnd);
+                    }
+                    IDM_SHOW_SUN => {
+                        let lines = WIDGET_LINES.fetch_xor(WIDGET_LINE_SUN, Ordering::Relaxed)
+                            ^ WIDGET_LINE_SUN;
+                        save_widget_lines(lines);
+                        resize_widget(hwnd);
+                    }
+                    IDM_SHOW_PRAYER => {
+                        let lines = WIDGET_LINES.fetch_xor(WIDGET_LINE_PRAYER, Ordering::Relaxed)
+                            ^ WIDGET_LINE_PRAYER;
+                        save_widget_lines(lines);
+                        resize_widget(hwnd);
+                    }
+                    IDM_SHOW_PROHOR => {
+                        let lines = WIDGET_LINES.fetch_xor(WIDGET_LINE_PROHOR, Ordering::Relaxed)
+                            ^ WIDGET_LINE_PROHOR;
+                        save_widget_lines(lines);
+                        SetTimer(Some(hwnd), 1, widget_timer_interval(), None);
+                        resize_widget(hwnd);
+                    }
+                    id if (IDM_PRAYER_METHOD_BASE
+                        ..IDM_PRAYER_METHOD_BASE + PRAYER_METHODS.len() as u32)
+                        .contains(&id) =>
+                    {
+                        let method = id - IDM_PRAYER_METHOD_BASE;
+                        PRAYER_METHOD.store(method, Ordering::Relaxed);
+                        save_prayer_method(method);
+                        resize_widget(hwnd);
+                    }
+                    IDM_ASR_HANAFI | IDM_ASR_STANDARD => {
+                        let rule = if cmd == IDM_ASR_STANDARD { 1 } else { 0 };
+                        ASR_RULE.store(rule, Ordering::Relaxed);
+                        save_asr_rule(rule);
+                        resize_widget(hwnd);
+                    }
+                    id if (IDM_LOCATION_BASE..IDM_LOCATION_BASE + LOCATIONS.len() as u32)
+                        .contains(&id) =>
+                    {
+                        let index = id - IDM_LOCATION_BASE;
+                        LOCATION_SELECTION.store(index, Ordering::Relaxed);
+                        save_location(index);
+                        resize_widget(hwnd);
+                    }
                     IDM_FONT_LICENSE => {
                         open_url("https://codepotro.com/font/ekush/");
                     }
@@ -267,3 +557,13 @@ extern "system" fn wndproc(hwnd: HWND, message: u32, wparam: WPARAM, lparam: LPA
         }
     }
 }
+
+/// Elsewhere there is no widget: without a command, show the usage
+#[cfg(not(windows))]
+fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() {
+        args.push("help".to_string());
+    }
+    std::process::exit(run_cli(&args).unwrap_or(0));
+}
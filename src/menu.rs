@@ -2,7 +2,13 @@
 
 use crate::constants::*;
 use crate::fonts::get_menu_font;
-use crate::{AUTOSTART_ENABLED, COUNTRY_SELECTION, MENU_STRINGS};
+use crate::locations::{LOCATION_REGIONS, LOCATIONS};
+use crate::prayer::PRAYER_METHODS;
+use crate::{
+    ASR_RULE, AUTOSTART_ENABLED, COUNTRY_SELECTION, FEED_SERVER_ENABLED, FEED_SERVER_PORT,
+    LOCATION_SELECTION, MENU_STRINGS, PRAYER_METHOD, PUNJIKA_CELL_LABEL, PUNJIKA_SIX_ROWS,
+    WALLPAPER_CORNER, WALLPAPER_ENABLED, WEEK_START, WEEKEND_DAYS, WIDGET_LINES,
+};
 use std::sync::atomic::Ordering;
 use windows::{
     Win32::Foundation::*, Win32::Graphics::Gdi::*, Win32::UI::Shell::*,
@@ -85,19 +91,100 @@ pub fn show_context_menu(hwnd: HWND) {
     {
         let mut strings = MENU_STRINGS.lock().unwrap();
         strings.clear();
-        strings.reserve(12); // Pre-allocate for expected menu items
+        strings.reserve(25 + LOCATIONS.len()); // Pre-allocate for expected menu items
     }
 
     unsafe {
         let menu = CreatePopupMenu().unwrap();
         let autostart = AUTOSTART_ENABLED.load(Ordering::Relaxed);
         let country = COUNTRY_SELECTION.load(Ordering::Relaxed);
+        let widget_lines = WIDGET_LINES.load(Ordering::Relaxed);
+        let location = LOCATION_SELECTION.load(Ordering::Relaxed) as usize;
+        let prayer_method = PRAYER_METHOD.load(Ordering::Relaxed) as usize;
+        let asr_rule = ASR_RULE.load(Ordering::Relaxed);
+        let feed_server = FEED_SERVER_ENABLED.load(Ordering::Relaxed);
+        let feed_port = FEED_SERVER_PORT.load(Ordering::Relaxed);
+        let wallpaper = WALLPAPER_ENABLED.load(Ordering::Relaxed);
+        let wallpaper_corner = WALLPAPER_CORNER.load(Ordering::Relaxed);
+        let six_rows = PUNJIKA_SIX_ROWS.load(Ordering::Relaxed);
+        let cell_label = PUNJIKA_CELL_LABEL.load(Ordering::Relaxed);
+        let week_start = WEEK_START.load(Ordering::Relaxed);
+        let weekend_days = WEEKEND_DAYS.load(Ordering::Relaxed);
 
         // পুঞ্জিকা (Calendar)
         add_owner_drawn_item(menu, 0, IDM_PUNJIKA, "পুঞ্জিকা", false, false, false);
 
+        // রপ্তানি - Submenu
+        let export_submenu = CreatePopupMenu().unwrap();
+        add_owner_drawn_item(
+            export_submenu,
+            0,
+            IDM_EXPORT_ICS,
+            "ক্যালেন্ডার ফাইল (.ics)",
+            false,
+            false,
+            false,
+        );
+        add_owner_drawn_item(
+            export_submenu,
+            1,
+            IDM_EXPORT_PDF_MONTH,
+            "এই মাসের পিডিএফ",
+            false,
+            false,
+            false,
+        );
+        add_owner_drawn_item(
+            export_submenu,
+            2,
+            IDM_EXPORT_PDF_YEAR,
+            "এই বছরের পিডিএফ",
+            false,
+            false,
+            false,
+        );
+        add_owner_drawn_item(
+            export_submenu,
+            3,
+            IDM_EXPORT_IMAGE_MONTH,
+            "এই মাসের ছবি",
+            false,
+            false,
+            false,
+        );
+        add_owner_drawn_item(
+            export_submenu,
+            4,
+            IDM_EXPORT_IMAGE_WIDGET,
+            "উইজেটের ছবি",
+            false,
+            false,
+            false,
+        );
+        add_owner_drawn_item(
+            export_submenu,
+            5,
+            IDM_EXPORT_HTML,
+            "এই মাসের ওয়েব পেজ",
+            false,
+            false,
+            false,
+        );
+        add_owner_drawn_submenu(menu, 1, export_submenu, "রপ্তানি");
+
+        // ইভেন্ট আমদানি
+        add_owner_drawn_item(
+            menu,
+            2,
+            IDM_IMPORT_ICS,
+            "ইভেন্ট আমদানি (.ics)",
+            false,
+            false,
+            false,
+        );
+
         // Separator
-        add_owner_drawn_item(menu, 1, 0, "", false, false, true);
+        add_owner_drawn_item(menu, 3, 0, "", false, false, true);
 
         // বুট হওয়ার সময়ে খোলো - Submenu
         let autostart_submenu = CreatePopupMenu().unwrap();
@@ -119,7 +206,7 @@ pub fn show_context_menu(hwnd: HWND) {
             false,
             false,
         );
-        add_owner_drawn_submenu(menu, 2, autostart_submenu, "বুট হওয়ার সময়ে খোলো");
+        add_owner_drawn_submenu(menu, 4, autostart_submenu, "বুট হওয়ার সময়ে খোলো");
 
         // দেশ - Submenu
         let country_submenu = CreatePopupMenu().unwrap();
@@ -141,22 +228,263 @@ pub fn show_context_menu(hwnd: HWND) {
             true,
             false,
         );
-        add_owner_drawn_submenu(menu, 3, country_submenu, "দেশ");
+        add_owner_drawn_submenu(menu, 5, country_submenu, "দেশ");
+
+        // জেলা - Submenu per division, then West Bengal
+        let location_submenu = CreatePopupMenu().unwrap();
+        for (region_index, region) in LOCATION_REGIONS.iter().enumerate() {
+            let region_submenu = CreatePopupMenu().unwrap();
+            let places = LOCATIONS
+                .iter()
+                .enumerate()
+                .filter(|(_, l)| l.region == region_index);
+            for (position, (index, place)) in places.enumerate() {
+                add_owner_drawn_item(
+                    region_submenu,
+                    position as u32,
+                    IDM_LOCATION_BASE + index as u32,
+                    place.name,
+                    index == location,
+                    false,
+                    false,
+                );
+            }
+            add_owner_drawn_submenu(
+                location_submenu,
+                region_index as u32,
+                region_submenu,
+                region,
+            );
+        }
+        add_owner_drawn_submenu(menu, 6, location_submenu, "জেলা");
+
+        // নামাজের সময় - Submenu: calculation method, then Asr rule
+        let prayer_submenu = CreatePopupMenu().unwrap();
+        for (index, method) in PRAYER_METHODS.iter().enumerate() {
+            add_owner_drawn_item(
+                prayer_submenu,
+                index as u32,
+                IDM_PRAYER_METHOD_BASE + index as u32,
+                method.name,
+                index == prayer_method,
+                false,
+                false,
+            );
+        }
+        let asr_position = PRAYER_METHODS.len() as u32;
+        add_owner_drawn_item(prayer_submenu, asr_position, 0, "", false, false, true);
+        add_owner_drawn_item(
+            prayer_submenu,
+            asr_position + 1,
+            IDM_ASR_HANAFI,
+            "আসর: হানাফি",
+            asr_rule == 0,
+            false,
+            false,
+        );
+        add_owner_drawn_item(
+            prayer_submenu,
+            asr_position + 2,
+            IDM_ASR_STANDARD,
+            "আসর: শাফেয়ি, মালেকি, হাম্বলি",
+            asr_rule == 1,
+            false,
+            false,
+        );
+        add_owner_drawn_submenu(menu, 7, prayer_submenu, "নামাজের সময়");
+
+        // উইজেটে দেখাও - Submenu of optional widget lines
+        let lines_submenu = CreatePopupMenu().unwrap();
+        add_owner_drawn_item(
+            lines_submenu,
+            0,
+            IDM_SHOW_TITHI,
+            "তিথি",
+            widget_lines & WIDGET_LINE_TITHI != 0,
+            false,
+            false,
+        );
+        add_owner_drawn_item(
+            lines_submenu,
+            1,
+            IDM_SHOW_SUN,
+            "সূর্যোদয়-সূর্যাস্ত",
+            widget_lines & WIDGET_LINE_SUN != 0,
+            false,
+            false,
+        );
+        add_owner_drawn_item(
+            lines_submenu,
+            2,
+            IDM_SHOW_PRAYER,
+            "পরবর্তী নামাজ",
+            widget_lines & WIDGET_LINE_PRAYER != 0,
+            false,
+            false,
+        );
+        add_owner_drawn_item(
+            lines_submenu,
+            3,
+            IDM_SHOW_PROHOR,
+            "প্রহর",
+            widget_lines & WIDGET_LINE_PROHOR != 0,
+            false,
+            false,
+        );
+        add_owner_drawn_submenu(menu, 8, lines_submenu, "উইজেটে দেখাও");
+
+        // পুঞ্জিকার বিন্যাস - Submenu of punjika layout options
+        let punjika_submenu = CreatePopupMenu().unwrap();
+        add_owner_drawn_item(
+            punjika_submenu,
+            0,
+            IDM_PUNJIKA_SIX_ROWS,
+            "সবসময় ছয় সপ্তাহ",
+            six_rows,
+            false,
+            false,
+        );
+        add_owner_drawn_item(punjika_submenu, 1, 0, "", false, false, true);
+        for (i, name) in [
+            "শুধু বাংলা তারিখ",
+            "সাথে ইংরেজি তারিখ",
+            "সাথে হিজরি তারিখ",
+            "সাথে তিথি",
+        ]
+        .iter()
+        .enumerate()
+        {
+            add_owner_drawn_item(
+                punjika_submenu,
+                2 + i as u32,
+                IDM_PUNJIKA_LABEL_BASE + i as u32,
+                name,
+                cell_label == i as u32,
+                false,
+                false,
+            );
+        }
+        add_owner_drawn_item(punjika_submenu, 6, 0, "", false, false, true);
+
+        // সপ্তাহ শুরু and সাপ্তাহিক ছুটি - one of the weekdays, any of them
+        let week_start_submenu = CreatePopupMenu().unwrap();
+        let weekend_submenu = CreatePopupMenu().unwrap();
+        for (i, name) in BANGLA_WEEKDAYS.iter().enumerate() {
+            add_owner_drawn_item(
+                week_start_submenu,
+                i as u32,
+                IDM_WEEK_START_BASE + i as u32,
+                name,
+                week_start == i as u32,
+                false,
+                false,
+            );
+            add_owner_drawn_item(
+                weekend_submenu,
+                i as u32,
+                IDM_WEEKEND_BASE + i as u32,
+                name,
+                weekend_days & (1 << i) != 0,
+                false,
+                false,
+            );
+        }
+        add_owner_drawn_submenu(punjika_submenu, 7, week_start_submenu, "সপ্তাহ শুরু");
+        add_owner_drawn_submenu(punjika_submenu, 8, weekend_submenu, "সাপ্তাহিক ছুটি");
+        add_owner_drawn_submenu(menu, 9, punjika_submenu, "পুঞ্জিকার বিন্যাস");
+
+        // ফিড সার্ভার - Submenu
+        let feed_submenu = CreatePopupMenu().unwrap();
+        add_owner_drawn_item(
+            feed_submenu,
+            0,
+            IDM_FEED_SERVER_ON,
+            "চালু",
+            feed_server,
+            false,
+            false,
+        );
+        add_owner_drawn_item(
+            feed_submenu,
+            1,
+            IDM_FEED_SERVER_OFF,
+            "বন্ধ",
+            !feed_server,
+            false,
+            false,
+        );
+        add_owner_drawn_item(feed_submenu, 2, 0, "", false, false, true);
+        add_owner_drawn_item(
+            feed_submenu,
+            3,
+            IDM_FEED_SERVER_OPEN,
+            &format!("খুলুন (localhost:{})", feed_port),
+            false,
+            !feed_server,
+            false,
+        );
+        add_owner_drawn_submenu(menu, 10, feed_submenu, "ফিড সার্ভার");
+
+        // ওয়ালপেপার - Submenu: picture, corner, off
+        let wallpaper_submenu = CreatePopupMenu().unwrap();
+        add_owner_drawn_item(
+            wallpaper_submenu,
+            0,
+            IDM_WALLPAPER_CHOOSE,
+            "ছবি বেছে নিন…",
+            false,
+            false,
+            false,
+        );
+        add_owner_drawn_item(wallpaper_submenu, 1, 0, "", false, false, true);
+        for (i, name) in ["উপরে বাঁয়ে", "উপরে ডানে", "নিচে বাঁয়ে", "নিচে ডানে"]
+            .iter()
+            .enumerate()
+        {
+            add_owner_drawn_item(
+                wallpaper_submenu,
+                2 + i as u32,
+                IDM_WALLPAPER_CORNER_BASE + i as u32,
+                name,
+                wallpaper_corner == i as u32,
+                false,
+                false,
+            );
+        }
+        add_owner_drawn_item(wallpaper_submenu, 6, 0, "", false, false, true);
+        add_owner_drawn_item(
+            wallpaper_submenu,
+            7,
+            IDM_WALLPAPER_OFF,
+            "বন্ধ",
+            !wallpaper,
+            false,
+            false,
+        );
+        add_owner_drawn_submenu(menu, 11, wallpaper_submenu, "ওয়ালপেপার");
 
         // Separator
-        add_owner_drawn_item(menu, 4, 0, "", false, false, true);
+        add_owner_drawn_item(menu, 12, 0, "", false, false, true);
 
         // ফন্ট লাইসেন্স
-        add_owner_drawn_item(menu, 5, IDM_FONT_LICENSE, "ফন্ট লাইসেন্স", false, false, false);
+        add_owner_drawn_item(
+            menu,
+            13,
+            IDM_FONT_LICENSE,
+            "ফন্ট লাইসেন্স",
+            false,
+            false,
+            false,
+        );
 
         // ওয়েবসাইট
-        add_owner_drawn_item(menu, 6, IDM_WEBSITE, "ওয়েবসাইট", false, false, false);
+        add_owner_drawn_item(menu, 14, IDM_WEBSITE, "ওয়েবসাইট", false, false, false);
 
         // Separator
-        add_owner_drawn_item(menu, 7, 0, "", false, false, true);
+        add_owner_drawn_item(menu, 15, 0, "", false, false, true);
 
         // বন্ধ করুন
-        add_owner_drawn_item(menu, 8, IDM_EXIT, "বন্ধ করুন", false, false, false);
+        add_owner_drawn_item(menu, 16, IDM_EXIT, "বন্ধ করুন", false, false, false);
 
         let mut pt = POINT::default();
         let _ = GetCursorPos(&mut pt);
@@ -166,6 +494,57 @@ pub fn show_context_menu(hwnd: HWND) {
     }
 }
 
+/// Menu for a punjika date cell: add an event, or edit one of the day's events.
+/// Returns the chosen command, or 0 if the menu was dismissed.
+pub fn show_event_menu(hwnd: HWND, titles: &[String]) -> u32 {
+    {
+        let mut strings = MENU_STRINGS.lock().unwrap();
+        strings.clear();
+        strings.reserve(2 + titles.len());
+    }
+
+    unsafe {
+        let menu = CreatePopupMenu().unwrap();
+        add_owner_drawn_item(
+            menu,
+            0,
+            IDM_EVENT_ADD,
+            "নতুন ইভেন্ট যোগ করুন",
+            false,
+            false,
+            false,
+        );
+        if !titles.is_empty() {
+            add_owner_drawn_item(menu, 1, 0, "", false, false, true);
+        }
+        for (i, title) in titles.iter().enumerate() {
+            add_owner_drawn_item(
+                menu,
+                2 + i as u32,
+                IDM_EVENT_EDIT_BASE + i as u32,
+                &format!("✎ {}", title),
+                false,
+                false,
+                false,
+            );
+        }
+
+        let mut pt = POINT::default();
+        let _ = GetCursorPos(&mut pt);
+        let command = TrackPopupMenu(
+            menu,
+            TPM_RIGHTBUTTON | TPM_RETURNCMD,
+            pt.x,
+            pt.y,
+            Some(0),
+            hwnd,
+            None,
+        );
+        let _ = DestroyMenu(menu);
+        command.0 as u32
+    }
+}
+
 pub fn open_url(url: &str) {
     let url_wide: Vec<u16> = url.encode_utf16().chain(std::iter::once(0)).collect();
     let operation = w!("open");
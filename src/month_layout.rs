@@ -0,0 +1,820 @@
+// month_layout.rs - Where everything in a punjika month goes
+//
+// The month grid as a model: the header, navigation and weekday rows, and a
+// cell per day with its Gregorian date and what is marked on it (today,
+// holidays, full/new moon, eclipses). The punjika draws and hit-tests from
+// it, and the image, PDF and HTML exporters lay their months out with it, so
+// they cannot drift apart. The punjika's year view, twelve small months with
+// their holidays, is laid out here too. The columns start on the first day
+// of the Week passed in, and its weekend days are marked. Nothing here
+// touches the window system: the week, the day's marks and today are passed
+// in, so a layout depends only on its inputs.
+
+#[cfg(any(windows, test))]
+use crate::calendar::bangla_year_days;
+use crate::calendar::{
+    BanglaDate, civil_from_days, days_from_civil, get_bangla_month_days, gregorian_from_bangla,
+    to_bangla_number,
+};
+use crate::canvas::Rect;
+use crate::constants::*;
+use crate::eclipses::{Eclipse, eclipse_days_between};
+#[cfg(windows)]
+use crate::hijri::gregorian_to_hijri;
+use crate::holidays::{Holiday, HolidayKind, holidays_between};
+use crate::locations::Location;
+#[cfg(windows)]
+use crate::panchang::tithi_for_day;
+use crate::panchang::{MoonPhase, moon_phases_between};
+use crate::{WEEK_START, WEEKEND_DAYS};
+use std::sync::atomic::Ordering;
+
+/// Gap between the navigation bar, the weekday row and the grid
+const CAL_ROW_GAP: i32 = 5;
+/// Space between a cell's edge and its drawn background
+const CAL_CELL_INSET: f32 = 2.0;
+
+// The year view: small months four across and three down
+#[cfg(any(windows, test))]
+const YEAR_COLUMNS: i32 = 4;
+#[cfg(any(windows, test))]
+const MINI_CELL_WIDTH: i32 = 22;
+#[cfg(any(windows, test))]
+const MINI_CELL_HEIGHT: i32 = 18;
+#[cfg(any(windows, test))]
+const MINI_TITLE_HEIGHT: i32 = 24;
+#[cfg(any(windows, test))]
+const MINI_WEEKDAY_HEIGHT: i32 = 18;
+#[cfg(any(windows, test))]
+const MINI_GAP: i32 = 16;
+
+/// The first day of the week and the weekend days, as chosen in the tray
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Week {
+    pub start: u32,   // Sunday = 0
+    pub weekend: u32, // bit per weekday
+}
+
+impl Default for Week {
+    fn default() -> Week {
+        Week {
+            start: 0,
+            weekend: DEFAULT_WEEKEND_DAYS,
+        }
+    }
+}
+
+impl Week {
+    /// Grid column of a day, counted from the first day of the week
+    fn column(&self, days: i64) -> i32 {
+        (days + 4 - self.start as i64).rem_euclid(7) as i32
+    }
+
+    /// The weekday (Sunday = 0) of a grid column, for the weekday names
+    pub fn weekday(&self, column: i32) -> usize {
+        (column as usize + self.start as usize) % 7
+    }
+
+    /// Whether a weekday (Sunday = 0) is one of the weekend days
+    pub fn is_weekend(&self, weekday: usize) -> bool {
+        self.weekend & (1 << weekday) != 0
+    }
+}
+
+/// The week chosen in the tray
+pub fn current_week() -> Week {
+    Week {
+        start: WEEK_START.load(Ordering::Relaxed),
+        weekend: WEEKEND_DAYS.load(Ordering::Relaxed),
+    }
+}
+
+/// What a day cell shows under the Bangla day
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CellLabel {
+    None,
+    Gregorian,
+    Hijri,
+    Tithi,
+}
+
+#[cfg(windows)]
+impl CellLabel {
+    pub const ALL: [CellLabel; 4] = [
+        CellLabel::None,
+        CellLabel::Gregorian,
+        CellLabel::Hijri,
+        CellLabel::Tithi,
+    ];
+
+    /// A stored index into ALL; no label if it is out of range
+    pub fn from_index(index: u32) -> CellLabel {
+        CellLabel::ALL
+            .get(index as usize)
+            .copied()
+            .unwrap_or(CellLabel::None)
+    }
+
+    /// Every label of this kind a cell can show, for measuring the widest
+    pub fn samples(self) -> Vec<String> {
+        match self {
+            CellLabel::None => Vec::new(),
+            CellLabel::Gregorian => GREGORIAN_MONTHS_SHORT
+                .iter()
+                .map(|name| format!("১ {}", name))
+                .chain(std::iter::once(to_bangla_number(31)))
+                .collect(),
+            CellLabel::Hijri => vec![to_bangla_number(30)],
+            CellLabel::Tithi => TITHI_NAMES.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+}
+
+/// A day of the month and its place in the grid
+pub struct DayCell {
+    pub day: i32,  // Bangla day, 1-based
+    pub days: i64, // days since 1970-01-01
+    pub row: i32,
+    pub column: i32,
+    pub rect: Rect, // the whole cell, for hit-testing
+    pub gregorian: (i32, i32, i32),
+    pub is_today: bool,
+    pub is_weekend: bool,
+    pub holiday: Option<HolidayKind>, // the most important of the day's holidays
+    pub moon_phase: Option<MoonPhase>,
+    pub eclipse: Option<Eclipse>,
+}
+
+impl DayCell {
+    /// The cell's background, inset from its neighbours
+    pub fn inner_rect(&self) -> Rect {
+        Rect::new(
+            self.rect.left + CAL_CELL_INSET,
+            self.rect.top + CAL_CELL_INSET,
+            self.rect.right - CAL_CELL_INSET,
+            self.rect.bottom - CAL_CELL_INSET,
+        )
+    }
+
+    /// Text color for the day number: public holidays win over optional ones
+    /// and observances
+    pub fn date_color(&self) -> u32 {
+        holiday_color(self.holiday)
+    }
+
+    /// The Gregorian day, with its month on the 1st and on ১ of the Bangla
+    /// month
+    pub fn gregorian_label(&self) -> String {
+        let (_, g_month, g_day) = self.gregorian;
+        if g_day == 1 || self.day == 1 {
+            format!(
+                "{} {}",
+                to_bangla_number(g_day),
+                GREGORIAN_MONTHS[(g_month - 1) as usize]
+            )
+        } else {
+            to_bangla_number(g_day)
+        }
+    }
+
+    /// The cell's second line: the Gregorian day (with the month on the
+    /// 1st), the Hijri day or the tithi at sunrise at `location`
+    #[cfg(windows)]
+    pub fn label(&self, kind: CellLabel, location: &Location) -> Option<String> {
+        let (g_year, g_month, g_day) = self.gregorian;
+        match kind {
+            CellLabel::None => None,
+            CellLabel::Gregorian if g_day == 1 => Some(format!(
+                "১ {}",
+                GREGORIAN_MONTHS_SHORT[(g_month - 1) as usize]
+            )),
+            CellLabel::Gregorian => Some(to_bangla_number(g_day)),
+            CellLabel::Hijri => Some(to_bangla_number(
+                gregorian_to_hijri(g_year, g_month, g_day).2,
+            )),
+            CellLabel::Tithi => Some(tithi_for_day(self.days, location).get_name().to_string()),
+        }
+    }
+}
+
+/// Text color for a holiday kind, as the punjika shows it
+pub fn holiday_color(kind: Option<HolidayKind>) -> u32 {
+    match kind {
+        Some(HolidayKind::Public) => CAL_HOLIDAY_TEXT,
+        Some(HolidayKind::Optional) => CAL_OPTIONAL_TEXT,
+        Some(HolidayKind::Observance) => CAL_OBSERVANCE_TEXT,
+        None => CAL_DATE_TEXT,
+    }
+}
+
+/// First and last day (days since 1970-01-01) of a Bangla month (0-11)
+fn month_days(year: i32, month: i32) -> (i64, i64) {
+    let (g_year, g_month, g_day) = gregorian_from_bangla(year, month, 1);
+    let first = days_from_civil(g_year, g_month, g_day);
+    (first, first + get_bangla_month_days(month, year) as i64 - 1)
+}
+
+/// What is marked on a month's days, looked up apart from the layout
+#[derive(Default)]
+pub struct MonthMarks {
+    pub holidays: Vec<Holiday>,
+    pub moon_phases: Vec<(i64, MoonPhase)>,
+    pub eclipses: Vec<(i64, Eclipse)>,
+}
+
+impl MonthMarks {
+    /// The holidays, full and new moons and eclipses (as seen from
+    /// `location`) of a Bangla month (0-11)
+    pub fn for_month(year: i32, month: i32, location: &Location) -> MonthMarks {
+        let (first_days, last_days) = month_days(year, month);
+        MonthMarks {
+            holidays: holidays_between(first_days, last_days),
+            moon_phases: moon_phases_between(first_days, last_days, BD_UTC_OFFSET_MINUTES),
+            eclipses: eclipse_days_between(first_days, last_days, location),
+        }
+    }
+}
+
+/// A Bangla month laid out as the punjika shows it, `width` pixels wide
+pub struct MonthLayout {
+    pub year: i32,
+    pub month: i32, // 0-11
+    pub first_days: i64,
+    pub last_days: i64,
+    pub rows: i32,
+    pub width: f32,
+    pub header_rect: Rect,
+    pub nav_rect: Rect,
+    pub separator_y: f32,
+    pub grid_top: f32,
+    pub cell_width: f32,
+    pub cell_height: f32,
+    pub footer_height: f32,
+    pub week: Week,
+    pub cells: Vec<DayCell>,
+    pub holidays: Vec<Holiday>,
+    #[cfg(windows)]
+    pub eclipses: Vec<(i64, Eclipse)>,
+}
+
+impl MonthLayout {
+    /// Lay out a month (0-11) with its columns in `week` order and `marks`
+    /// on its days; `today` is marked if it falls in it
+    pub fn new(
+        year: i32,
+        month: i32,
+        width: f32,
+        today: &BanglaDate,
+        week: Week,
+        marks: MonthMarks,
+    ) -> MonthLayout {
+        let days_in_month = get_bangla_month_days(month, year);
+        let (first_days, last_days) = month_days(year, month);
+        let first_column = week.column(first_days);
+        let rows = (first_column + days_in_month + 6) / 7;
+
+        let header_rect = Rect::new(0.0, 0.0, width, CAL_HEADER_HEIGHT as f32);
+        let nav_rect = Rect::new(
+            0.0,
+            CAL_HEADER_HEIGHT as f32,
+            width,
+            (CAL_HEADER_HEIGHT + CAL_NAV_HEIGHT) as f32,
+        );
+        let separator_y =
+            (CAL_HEADER_HEIGHT + CAL_NAV_HEIGHT + CAL_ROW_GAP + CAL_WEEKDAY_HEIGHT) as f32;
+        let grid_top = separator_y + CAL_ROW_GAP as f32;
+        // Whole pixels, as GDI divides
+        let cell_width = ((width as i32 - CAL_PADDING * 2) / 7) as f32;
+        let cell_height = CAL_CELL_SIZE as f32;
+
+        let MonthMarks {
+            holidays,
+            moon_phases,
+            eclipses,
+        } = marks;
+        let is_current_month = month == today.month && year == today.year;
+
+        let cells = (1..=days_in_month)
+            .map(|day| {
+                let index = first_column + day - 1;
+                let (row, column) = (index / 7, index % 7);
+                let left = CAL_PADDING as f32 + column as f32 * cell_width;
+                let top = grid_top + row as f32 * cell_height;
+                let days = first_days + day as i64 - 1;
+                DayCell {
+                    day,
+                    days,
+                    row,
+                    column,
+                    rect: Rect::new(left, top, left + cell_width, top + cell_height),
+                    gregorian: civil_from_days(days),
+                    is_today: is_current_month && day == today.day,
+                    is_weekend: week.is_weekend(week.weekday(column)),
+                    holiday: holidays
+                        .iter()
+                        .filter(|h| h.days == days)
+                        .map(|h| h.kind)
+                        .min(),
+                    moon_phase: moon_phases
+                        .iter()
+                        .find(|(d, _)| *d == days)
+                        .map(|&(_, p)| p),
+                    eclipse: eclipses.iter().find(|(d, _)| *d == days).map(|&(_, e)| e),
+                }
+            })
+            .collect();
+
+        MonthLayout {
+            year,
+            month,
+            first_days,
+            last_days,
+            rows,
+            width,
+            header_rect,
+            nav_rect,
+            separator_y,
+            grid_top,
+            cell_width,
+            cell_height,
+            footer_height: CAL_FOOTER_HEIGHT as f32,
+            week,
+            cells,
+            holidays,
+            #[cfg(windows)]
+            eclipses,
+        }
+    }
+
+    /// The same month with a header `height` pixels tall instead of
+    /// CAL_HEADER_HEIGHT, for an extra line; everything below moves down
+    #[cfg(any(windows, test))]
+    pub fn with_header_height(mut self, height: f32) -> MonthLayout {
+        let shift = height - self.header_rect.bottom;
+        self.header_rect.bottom = height;
+        self.nav_rect.top += shift;
+        self.nav_rect.bottom += shift;
+        self.separator_y += shift;
+        self.grid_top += shift;
+        for cell in &mut self.cells {
+            cell.rect.top += shift;
+            cell.rect.bottom += shift;
+        }
+        self
+    }
+
+    /// The same month with rows `height` pixels tall instead of CAL_CELL_SIZE
+    #[cfg(any(windows, test))]
+    pub fn with_cell_height(mut self, height: f32) -> MonthLayout {
+        for cell in &mut self.cells {
+            cell.rect.top = self.grid_top + cell.row as f32 * height;
+            cell.rect.bottom = cell.rect.top + height;
+        }
+        self.cell_height = height;
+        self
+    }
+
+    /// The same month with room for at least `rows` weeks, so that every
+    /// month is the same height
+    #[cfg(any(windows, test))]
+    pub fn with_rows(mut self, rows: i32) -> MonthLayout {
+        self.rows = self.rows.max(rows);
+        self
+    }
+
+    /// The same month with a footer `height` pixels tall instead of
+    /// CAL_FOOTER_HEIGHT, for more than one line
+    #[cfg(any(windows, test))]
+    pub fn with_footer_height(mut self, height: f32) -> MonthLayout {
+        self.footer_height = height;
+        self
+    }
+
+    /// Height the month needs, down to the bottom of the footer
+    pub fn height(&self) -> f32 {
+        self.grid_top + self.rows as f32 * self.cell_height + self.footer_height
+    }
+
+    /// Header cell for the weekday of a column
+    pub fn weekday_rect(&self, column: i32) -> Rect {
+        let left = CAL_PADDING as f32 + column as f32 * self.cell_width;
+        Rect::new(
+            left,
+            self.separator_y - CAL_WEEKDAY_HEIGHT as f32,
+            left + self.cell_width,
+            self.separator_y,
+        )
+    }
+
+    /// The footer, at the bottom of a window (or image) `height` tall
+    pub fn footer_rect(&self, height: f32) -> Rect {
+        Rect::new(
+            CAL_PADDING as f32,
+            height - self.footer_height,
+            self.width - CAL_PADDING as f32,
+            height - 4.0,
+        )
+    }
+
+    /// The cell of a Bangla day (1-based)
+    #[cfg(any(windows, test))]
+    pub fn cell(&self, day: i32) -> Option<&DayCell> {
+        self.cells.get(usize::try_from(day - 1).ok()?)
+    }
+
+    /// The day cell under a point, if any
+    #[cfg(any(windows, test))]
+    pub fn day_at(&self, x: f32, y: f32) -> Option<&DayCell> {
+        self.cells.iter().find(|cell| {
+            x >= cell.rect.left && x < cell.rect.right && y >= cell.rect.top && y < cell.rect.bottom
+        })
+    }
+
+    /// Holidays on a day
+    pub fn holidays_on(&self, days: i64) -> impl Iterator<Item = &Holiday> {
+        self.holidays.iter().filter(move |h| h.days == days)
+    }
+}
+
+/// A day in one of the year view's small months
+#[cfg(any(windows, test))]
+pub struct MiniCell {
+    pub day: i32,
+    pub rect: Rect,
+    pub is_today: bool,
+    pub is_weekend: bool,
+    pub holiday: Option<HolidayKind>,
+}
+
+#[cfg(windows)]
+impl MiniCell {
+    /// Text color for the day number, as in the month view
+    pub fn date_color(&self) -> u32 {
+        holiday_color(self.holiday)
+    }
+}
+
+/// A month (0-11) in the year view: its name over a grid of its days
+#[cfg(any(windows, test))]
+pub struct MiniMonth {
+    pub month: i32,
+    pub rect: Rect, // the whole block, for hit-testing
+    pub title_rect: Rect,
+    pub cells: Vec<MiniCell>,
+}
+
+#[cfg(any(windows, test))]
+impl MiniMonth {
+    /// Weekday initial cell of a column
+    pub fn weekday_rect(&self, column: i32) -> Rect {
+        let left = self.rect.left + (column * MINI_CELL_WIDTH) as f32;
+        Rect::new(
+            left,
+            self.title_rect.bottom,
+            left + MINI_CELL_WIDTH as f32,
+            self.title_rect.bottom + MINI_WEEKDAY_HEIGHT as f32,
+        )
+    }
+}
+
+/// A Bangla year as the punjika's year view shows it: the header and
+/// navigation bar of the month view over twelve small months
+#[cfg(any(windows, test))]
+pub struct YearLayout {
+    pub year: i32,
+    pub first_days: i64,
+    pub last_days: i64,
+    pub width: f32,
+    pub height: f32,
+    pub header_rect: Rect,
+    pub nav_rect: Rect,
+    pub week: Week,
+    pub months: Vec<MiniMonth>,
+}
+
+#[cfg(any(windows, test))]
+impl YearLayout {
+    /// Lay out a year with its months' columns in `week` order and its
+    /// `holidays` marked; `today` is marked if it falls in it
+    pub fn new(year: i32, today: &BanglaDate, week: Week, holidays: &[Holiday]) -> YearLayout {
+        let (first_days, last_days) = bangla_year_days(year);
+
+        let block_width = 7 * MINI_CELL_WIDTH;
+        let block_height = MINI_TITLE_HEIGHT + MINI_WEEKDAY_HEIGHT + 6 * MINI_CELL_HEIGHT;
+        let width = CAL_PADDING * 2 + YEAR_COLUMNS * block_width + (YEAR_COLUMNS - 1) * MINI_GAP;
+        let rows = 12 / YEAR_COLUMNS;
+        let grid_top = CAL_HEADER_HEIGHT + CAL_NAV_HEIGHT + CAL_ROW_GAP;
+        let height = grid_top + rows * block_height + (rows - 1) * MINI_GAP + CAL_PADDING;
+
+        let months = (0..12)
+            .map(|month| {
+                let left = CAL_PADDING + (month % YEAR_COLUMNS) * (block_width + MINI_GAP);
+                let top = grid_top + (month / YEAR_COLUMNS) * (block_height + MINI_GAP);
+                let (month_first, _) = month_days(year, month);
+                let first_column = week.column(month_first);
+                let cells_top = top + MINI_TITLE_HEIGHT + MINI_WEEKDAY_HEIGHT;
+                let is_current_month = month == today.month && year == today.year;
+
+                let cells = (1..=get_bangla_month_days(month, year))
+                    .map(|day| {
+                        let index = first_column + day - 1;
+                        let (row, column) = (index / 7, index % 7);
+                        let cell_left = (left + column * MINI_CELL_WIDTH) as f32;
+                        let cell_top = (cells_top + row * MINI_CELL_HEIGHT) as f32;
+                        let days = month_first + day as i64 - 1;
+                        MiniCell {
+                            day,
+                            rect: Rect::new(
+                                cell_left,
+                                cell_top,
+                                cell_left + MINI_CELL_WIDTH as f32,
+                                cell_top + MINI_CELL_HEIGHT as f32,
+                            ),
+                            is_today: is_current_month && day == today.day,
+                            is_weekend: week.is_weekend(week.weekday(column)),
+                            holiday: holidays
+                                .iter()
+                                .filter(|h| h.days == days)
+                                .map(|h| h.kind)
+                                .min(),
+                        }
+                    })
+                    .collect();
+
+                MiniMonth {
+                    month,
+                    rect: Rect::new(
+                        left as f32,
+                        top as f32,
+                        (left + block_width) as f32,
+                        (top + block_height) as f32,
+                    ),
+                    title_rect: Rect::new(
+                        left as f32,
+                        top as f32,
+                        (left + block_width) as f32,
+                        (top + MINI_TITLE_HEIGHT) as f32,
+                    ),
+                    cells,
+                }
+            })
+            .collect();
+
+        YearLayout {
+            year,
+            first_days,
+            last_days,
+            width: width as f32,
+            height: height as f32,
+            header_rect: Rect::new(0.0, 0.0, width as f32, CAL_HEADER_HEIGHT as f32),
+            nav_rect: Rect::new(
+                0.0,
+                CAL_HEADER_HEIGHT as f32,
+                width as f32,
+                (CAL_HEADER_HEIGHT + CAL_NAV_HEIGHT) as f32,
+            ),
+            week,
+            months,
+        }
+    }
+
+    /// The small month under a point, if any
+    pub fn month_at(&self, x: f32, y: f32) -> Option<&MiniMonth> {
+        self.months.iter().find(|mini| {
+            x >= mini.rect.left && x < mini.rect.right && y >= mini.rect.top && y < mini.rect.bottom
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Boishakh 1432 starts on Monday 2025-04-14 and has 31 days
+    const YEAR: i32 = 1432;
+    const WIDTH: f32 = CAL_WIDTH as f32;
+
+    fn today() -> BanglaDate {
+        BanglaDate {
+            day: 3,
+            month: 0,
+            year: YEAR,
+            weekday: 3,
+        }
+    }
+
+    fn boishakh(week: Week, marks: MonthMarks) -> MonthLayout {
+        MonthLayout::new(YEAR, 0, WIDTH, &today(), week, marks)
+    }
+
+    fn holiday(days: i64, kind: HolidayKind) -> Holiday {
+        Holiday {
+            id: String::new(),
+            name: String::new(),
+            kind,
+            days,
+        }
+    }
+
+    #[test]
+    fn month_starts_in_its_weekday_column() {
+        let layout = boishakh(Week::default(), MonthMarks::default());
+        assert_eq!(layout.first_days, days_from_civil(2025, 4, 14));
+        assert_eq!(layout.last_days, layout.first_days + 30);
+        assert_eq!(layout.cells.len(), 31);
+        let first = layout.cell(1).unwrap();
+        assert_eq!((first.row, first.column), (0, 1));
+        let seventh = layout.cell(7).unwrap();
+        assert_eq!((seventh.row, seventh.column), (1, 0));
+        assert_eq!(layout.rows, 5);
+    }
+
+    #[test]
+    fn week_start_moves_the_columns() {
+        let week = Week {
+            start: 6, // Saturday
+            weekend: DEFAULT_WEEKEND_DAYS,
+        };
+        let layout = boishakh(week, MonthMarks::default());
+        assert_eq!(layout.cell(1).unwrap().column, 2);
+        assert_eq!(week.weekday(0), 6);
+        assert_eq!(week.weekday(2), 1);
+        // Friday 18 April is the last column, Saturday 19 April the first
+        assert_eq!(layout.cell(5).unwrap().column, 6);
+        assert_eq!(layout.cell(6).unwrap().column, 0);
+    }
+
+    #[test]
+    fn weekend_days_are_marked() {
+        let layout = boishakh(Week::default(), MonthMarks::default());
+        // Friday and Saturday, 18 and 19 April
+        assert!(layout.cell(5).unwrap().is_weekend);
+        assert!(layout.cell(6).unwrap().is_weekend);
+        assert!(!layout.cell(7).unwrap().is_weekend);
+
+        let sunday_off = Week {
+            start: 0,
+            weekend: 1,
+        };
+        let layout = boishakh(sunday_off, MonthMarks::default());
+        assert!(!layout.cell(5).unwrap().is_weekend);
+        assert!(layout.cell(7).unwrap().is_weekend);
+    }
+
+    #[test]
+    fn cells_tile_the_grid() {
+        let layout = boishakh(Week::default(), MonthMarks::default());
+        let grid_top = (CAL_HEADER_HEIGHT + CAL_NAV_HEIGHT + CAL_WEEKDAY_HEIGHT) as f32 + 10.0;
+        let cell_width = ((CAL_WIDTH - CAL_PADDING * 2) / 7) as f32;
+        assert_eq!(layout.grid_top, grid_top);
+        assert_eq!(layout.cell_width, cell_width);
+
+        let first = layout.cell(1).unwrap().rect;
+        assert_eq!(first.left, CAL_PADDING as f32 + cell_width);
+        assert_eq!(first.top, grid_top);
+        assert_eq!(first.right - first.left, cell_width);
+        assert_eq!(first.bottom - first.top, CAL_CELL_SIZE as f32);
+        assert_eq!(layout.cell(2).unwrap().rect.left, first.right);
+        assert_eq!(layout.cell(8).unwrap().rect.top, first.bottom);
+
+        let weekday = layout.weekday_rect(1);
+        assert_eq!((weekday.left, weekday.right), (first.left, first.right));
+        assert_eq!(weekday.bottom, layout.separator_y);
+
+        assert_eq!(
+            layout.height(),
+            grid_top + 5.0 * CAL_CELL_SIZE as f32 + CAL_FOOTER_HEIGHT as f32
+        );
+        assert!(layout.cell(0).is_none());
+        assert!(layout.cell(32).is_none());
+    }
+
+    #[test]
+    fn resizing_moves_the_cells() {
+        let layout = boishakh(Week::default(), MonthMarks::default())
+            .with_header_height(80.0)
+            .with_cell_height(50.0)
+            .with_footer_height(70.0)
+            .with_rows(6);
+        let grid_top = (CAL_HEADER_HEIGHT + CAL_NAV_HEIGHT + CAL_WEEKDAY_HEIGHT) as f32 + 30.0;
+        assert_eq!(layout.grid_top, grid_top);
+        assert_eq!(layout.nav_rect.top, 80.0);
+        let eighth = layout.cell(8).unwrap().rect;
+        assert_eq!(
+            (eighth.top, eighth.bottom),
+            (grid_top + 50.0, grid_top + 100.0)
+        );
+        assert_eq!(layout.rows, 6);
+        assert_eq!(layout.height(), grid_top + 6.0 * 50.0 + 70.0);
+        assert_eq!(layout.footer_rect(400.0).top, 330.0);
+    }
+
+    #[test]
+    fn day_at_finds_the_cell_under_a_point() {
+        let layout = boishakh(Week::default(), MonthMarks::default());
+        let first = layout.cell(1).unwrap().rect;
+        let (x, y) = (first.left + 1.0, first.top + 1.0);
+        assert_eq!(layout.day_at(x, y).map(|cell| cell.day), Some(1));
+        // Cells include their top left edge but not their bottom right one
+        assert_eq!(
+            layout.day_at(first.left, first.top).map(|cell| cell.day),
+            Some(1)
+        );
+        assert_eq!(
+            layout.day_at(first.right, first.top).map(|cell| cell.day),
+            Some(2)
+        );
+        assert_eq!(
+            layout.day_at(first.left, first.bottom).map(|cell| cell.day),
+            Some(8)
+        );
+        let last = layout.cell(31).unwrap().rect;
+        assert_eq!(
+            layout
+                .day_at(last.right - 1.0, last.bottom - 1.0)
+                .map(|cell| cell.day),
+            Some(31)
+        );
+        // The empty first column, the navigation bar and below the grid
+        assert!(layout.day_at(CAL_PADDING as f32 + 1.0, y).is_none());
+        assert!(layout.day_at(x, layout.nav_rect.top + 1.0).is_none());
+        assert!(layout.day_at(x, last.bottom + 1.0).is_none());
+        assert!(layout.day_at(-1.0, y).is_none());
+    }
+
+    #[test]
+    fn today_and_marks_are_placed_on_their_days() {
+        let first = days_from_civil(2025, 4, 14);
+        let marks = MonthMarks {
+            holidays: vec![
+                holiday(first, HolidayKind::Observance),
+                holiday(first, HolidayKind::Public),
+                holiday(first + 2, HolidayKind::Optional),
+            ],
+            moon_phases: vec![(first + 12, MoonPhase::Full)],
+            eclipses: Vec::new(),
+        };
+        let layout = boishakh(Week::default(), marks);
+        let marked: Vec<i32> = layout
+            .cells
+            .iter()
+            .filter(|cell| cell.is_today)
+            .map(|cell| cell.day)
+            .collect();
+        assert_eq!(marked, [3]);
+        assert_eq!(layout.cell(1).unwrap().holiday, Some(HolidayKind::Public));
+        assert_eq!(layout.cell(1).unwrap().date_color(), CAL_HOLIDAY_TEXT);
+        assert_eq!(layout.cell(3).unwrap().holiday, Some(HolidayKind::Optional));
+        assert_eq!(layout.cell(2).unwrap().holiday, None);
+        assert_eq!(layout.holidays_on(first).count(), 2);
+        assert_eq!(layout.cell(13).unwrap().moon_phase, Some(MoonPhase::Full));
+        assert_eq!(layout.cell(14).unwrap().moon_phase, None);
+
+        let jyoishtho = MonthLayout::new(
+            YEAR,
+            1,
+            WIDTH,
+            &today(),
+            Week::default(),
+            MonthMarks::default(),
+        );
+        assert!(jyoishtho.cells.iter().all(|cell| !cell.is_today));
+    }
+
+    #[test]
+    fn year_view_finds_the_month_under_a_point() {
+        let holidays = [holiday(days_from_civil(2025, 4, 14), HolidayKind::Public)];
+        let layout = YearLayout::new(YEAR, &today(), Week::default(), &holidays);
+        assert_eq!(layout.year, YEAR);
+        assert_eq!(layout.week, Week::default());
+        assert_eq!(layout.months.len(), 12);
+        assert_eq!(layout.first_days, days_from_civil(2025, 4, 14));
+        assert_eq!(layout.last_days, days_from_civil(2026, 4, 13));
+        assert_eq!(layout.header_rect.bottom, layout.nav_rect.top);
+        assert!(
+            layout
+                .months
+                .iter()
+                .all(|m| m.rect.top >= layout.nav_rect.bottom)
+        );
+        for mini in &layout.months {
+            let rect = mini.rect;
+            let (x, y) = (
+                (rect.left + rect.right) / 2.0,
+                (rect.top + rect.bottom) / 2.0,
+            );
+            assert_eq!(layout.month_at(x, y).map(|m| m.month), Some(mini.month));
+            assert!(rect.right <= layout.width && rect.bottom <= layout.height);
+        }
+        assert!(layout.month_at(1.0, 1.0).is_none());
+
+        let boishakh = &layout.months[0];
+        assert_eq!(boishakh.cells.len(), 31);
+        assert_eq!(boishakh.cells[30].day, 31);
+        assert_eq!(boishakh.cells[0].holiday, Some(HolidayKind::Public));
+        assert!(boishakh.cells[2].is_today);
+        assert!(boishakh.cells[4].is_weekend);
+        assert_eq!(boishakh.weekday_rect(1).left, boishakh.cells[0].rect.left);
+    }
+}
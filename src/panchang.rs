@@ -0,0 +1,281 @@
+// panchang.rs - Panchang elements: তিথি, নক্ষত্র, যোগ and করণ
+
+use crate::astronomy::*;
+#[cfg(windows)]
+use crate::calendar::format_bangla_time;
+#[cfg(windows)]
+use crate::constants::*;
+#[cfg(windows)]
+use crate::locations::Location;
+
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Paksha {
+    Shukla,  // শুক্লপক্ষ - waxing
+    Krishna, // কৃষ্ণপক্ষ - waning
+}
+
+/// A tithi: each 12 degrees of Moon-Sun elongation
+#[cfg(any(windows, test))]
+#[derive(Debug, Clone, Copy)]
+pub struct Tithi {
+    pub index: i32, // 0-29 (14 = পূর্ণিমা, 29 = অমাবস্যা)
+    pub start: f64, // Julian Day (UT) when it starts
+    pub end: f64,   // Julian Day (UT) when it ends
+}
+
+#[cfg(windows)]
+impl Tithi {
+    pub fn paksha(&self) -> Paksha {
+        if self.index < 15 {
+            Paksha::Shukla
+        } else {
+            Paksha::Krishna
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self.index {
+            14 => TITHI_NAMES[14],
+            29 => TITHI_NAMES[15],
+            0..=28 => TITHI_NAMES[(self.index % 15) as usize],
+            _ => "?",
+        }
+    }
+
+    pub fn get_paksha_name(&self) -> &'static str {
+        match self.paksha() {
+            Paksha::Shukla => PAKSHA_NAMES[0],
+            Paksha::Krishna => PAKSHA_NAMES[1],
+        }
+    }
+}
+
+// Mean daily motions in degrees, used as root-finding slopes
+#[cfg(any(windows, test))]
+const ELONGATION_RATE: f64 = 360.0 / SYNODIC_MONTH;
+#[cfg(windows)]
+const MOON_SIDEREAL_RATE: f64 = 13.176;
+#[cfg(windows)]
+const SUN_SIDEREAL_RATE: f64 = 0.9856;
+
+/// Which equal division of an angle is running at a moment, and when it
+/// starts and ends
+#[cfg(any(windows, test))]
+fn division_at(
+    angle: impl Fn(f64) -> f64 + Copy,
+    size: f64,
+    rate: f64,
+    jd: f64,
+) -> (i32, f64, f64) {
+    let value = angle(jd);
+    let index = (value / size).floor();
+    let first = index * size;
+    let start = find_angle(angle, first, rate, jd - (value - first) / rate);
+    let target = first + size;
+    let end = find_angle(angle, target, rate, jd + (target - value) / rate);
+    (index as i32, start, end)
+}
+
+/// The tithi running at a moment
+#[cfg(any(windows, test))]
+pub fn tithi_at(jd: f64) -> Tithi {
+    let (index, start, end) = division_at(lunar_elongation, 12.0, ELONGATION_RATE, jd);
+    Tithi {
+        index: index.clamp(0, 29),
+        start,
+        end,
+    }
+}
+
+/// A named panchang element (নক্ষত্র, যোগ, করণ) and when it ends
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy)]
+pub struct Anga {
+    pub name: &'static str,
+    pub end: f64, // Julian Day (UT)
+}
+
+/// The nakshatra: each 13°20' of the Moon's sidereal longitude
+#[cfg(windows)]
+pub fn nakshatra_at(jd: f64) -> Anga {
+    let (index, _, end) = division_at(
+        sidereal_moon_longitude,
+        360.0 / 27.0,
+        MOON_SIDEREAL_RATE,
+        jd,
+    );
+    Anga {
+        name: NAKSHATRA_NAMES[index.clamp(0, 26) as usize],
+        end,
+    }
+}
+
+/// The yoga: each 13°20' of the Sun's and Moon's sidereal longitudes added
+#[cfg(windows)]
+pub fn yoga_at(jd: f64) -> Anga {
+    let sum = |jd: f64| normalize_degrees(sidereal_sun_longitude(jd) + sidereal_moon_longitude(jd));
+    let rate = MOON_SIDEREAL_RATE + SUN_SIDEREAL_RATE;
+    let (index, _, end) = division_at(sum, 360.0 / 27.0, rate, jd);
+    Anga {
+        name: YOGA_NAMES[index.clamp(0, 26) as usize],
+        end,
+    }
+}
+
+/// The karana: half a tithi. The first half of শুক্লা প্রতিপদ is কিংস্তুঘ্ন,
+/// then বব…বিষ্টি repeat eight times, and the month ends with শকুনি, চতুষ্পদ, নাগ.
+#[cfg(windows)]
+pub fn karana_at(jd: f64) -> Anga {
+    let (index, _, end) = division_at(lunar_elongation, 6.0, ELONGATION_RATE, jd);
+    let name = match index {
+        0 => KARANA_NAMES[10],
+        1..=56 => KARANA_NAMES[((index - 1) % 7) as usize],
+        57..=59 => KARANA_NAMES[(index - 50) as usize],
+        _ => "?",
+    };
+    Anga { name, end }
+}
+
+/// Sunrise of a day, or 6 AM local time where the sun does not rise
+#[cfg(windows)]
+fn sunrise_or_morning(days: i64, location: &Location) -> f64 {
+    location
+        .sun_times(days)
+        .0
+        .unwrap_or(location.day_start(days) + 0.25)
+}
+
+/// The tithi of a civil day: the one running at sunrise
+#[cfg(windows)]
+pub fn tithi_for_day(days: i64, location: &Location) -> Tithi {
+    tithi_at(sunrise_or_morning(days, location))
+}
+
+/// The panchang of a civil day, taken at sunrise as punjikas do
+#[cfg(windows)]
+pub struct DayPanchang {
+    pub tithi: Tithi,
+    pub nakshatra: Anga,
+    pub yoga: Anga,
+    pub karana: Anga,
+}
+
+#[cfg(windows)]
+pub fn panchang_for_day(days: i64, location: &Location) -> DayPanchang {
+    let sunrise = sunrise_or_morning(days, location);
+    DayPanchang {
+        tithi: tithi_at(sunrise),
+        nakshatra: nakshatra_at(sunrise),
+        yoga: yoga_at(sunrise),
+        karana: karana_at(sunrise),
+    }
+}
+
+/// Start time of an element relative to a day: আগের দিন রাত ৯:০৫ থেকে
+#[cfg(windows)]
+pub fn format_start_time(start: f64, days: i64, utc_offset_minutes: i32) -> String {
+    let (start_day, start_minute) = local_day_minute(start, utc_offset_minutes);
+    let day_prefix = if start_day < days {
+        "আগের দিন "
+    } else {
+        ""
+    };
+    format!("{}{} থেকে", day_prefix, format_bangla_time(start_minute))
+}
+
+/// End time of an element relative to a day: রাত ৮:১২ পর্যন্ত, পরদিন ভোর ৪:৩০ পর্যন্ত
+#[cfg(windows)]
+pub fn format_end_time(end: f64, days: i64, utc_offset_minutes: i32) -> String {
+    let (end_day, end_minute) = local_day_minute(end, utc_offset_minutes);
+    let day_prefix = if end_day > days {
+        "পরদিন "
+    } else {
+        ""
+    };
+    format!("{}{} পর্যন্ত", day_prefix, format_bangla_time(end_minute))
+}
+
+/// Local day number and minute of the day for a moment
+pub fn local_day_minute(jd: f64, utc_offset_minutes: i32) -> (i64, i32) {
+    let local = days_from_jd(jd) + utc_offset_minutes as f64 / 1440.0;
+    let day = local.floor() as i64;
+    let minute = ((local - day as f64) * 1440.0).round() as i32;
+    if minute >= 1440 {
+        (day + 1, minute - 1440)
+    } else {
+        (day, minute)
+    }
+}
+
+/// Widget line: শুক্লপক্ষ দ্বিতীয়া • রাত ৮:১২ পর্যন্ত
+#[cfg(windows)]
+pub fn format_tithi_line(jd: f64, utc_offset_minutes: i32) -> String {
+    let tithi = tithi_at(jd);
+    let (today, _) = local_day_minute(jd, utc_offset_minutes);
+    let (end_day, end_minute) = local_day_minute(tithi.end, utc_offset_minutes);
+    let day_prefix = match end_day - today {
+        0 => "",
+        1 => "কাল ",
+        _ => "পরশু ",
+    };
+    format!(
+        "{} {} • {}{} পর্যন্ত",
+        tithi.get_paksha_name(),
+        tithi.get_name(),
+        day_prefix,
+        format_bangla_time(end_minute)
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoonPhase {
+    New,  // অমাবস্যা
+    Full, // পূর্ণিমা
+}
+
+/// Local days (between two day numbers, inclusive) on which a new or full moon occurs
+pub fn moon_phases_between(
+    first_day: i64,
+    last_day: i64,
+    utc_offset_minutes: i32,
+) -> Vec<(i64, MoonPhase)> {
+    let mut phases = Vec::new();
+    let first = jd_from_days(first_day) - utc_offset_minutes as f64 / 1440.0;
+    let mut new_moon = new_moon_before(first);
+
+    loop {
+        let full_moon = find_elongation(180.0, new_moon + SYNODIC_MONTH / 2.0);
+        for (jd, phase) in [(new_moon, MoonPhase::New), (full_moon, MoonPhase::Full)] {
+            let (day, _) = local_day_minute(jd, utc_offset_minutes);
+            if (first_day..=last_day).contains(&day) {
+                phases.push((day, phase));
+            }
+        }
+        if local_day_minute(full_moon, utc_offset_minutes).0 > last_day {
+            break;
+        }
+        new_moon = find_elongation(0.0, new_moon + SYNODIC_MONTH);
+    }
+    phases
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tithi_starts_when_the_last_one_ends() {
+        let jd = jd_from_days(crate::calendar::days_from_civil(2025, 4, 14));
+        let tithi = tithi_at(jd);
+        assert!(tithi.start < jd && jd < tithi.end);
+        // A tithi lasts between about 19 and 27 hours
+        let hours = (tithi.end - tithi.start) * 24.0;
+        assert!((19.0..27.0).contains(&hours), "{} hours", hours);
+        let next = tithi_at(tithi.end + 0.01);
+        assert_eq!(next.index, (tithi.index + 1) % 30);
+        assert!((next.start - tithi.end).abs() < 1e-5);
+        let previous = tithi_at(tithi.start - 0.01);
+        assert!((previous.end - tithi.start).abs() < 1e-5);
+    }
+}
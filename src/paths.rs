@@ -0,0 +1,25 @@
+// paths.rs - Locations for data files (beside the executable and in the user profile)
+
+use crate::constants::APP_NAME;
+use std::path::PathBuf;
+
+/// Directory containing the running executable
+pub fn exe_dir() -> Option<PathBuf> {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.to_path_buf()))
+}
+
+/// Per-user data directory: %APPDATA%\BanglaCalendar, or without APPDATA
+/// (outside Windows) $XDG_DATA_HOME/BanglaCalendar or
+/// ~/.local/share/BanglaCalendar
+pub fn user_data_dir() -> Option<PathBuf> {
+    let non_empty = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty());
+    if let Some(appdata) = non_empty("APPDATA") {
+        return Some(PathBuf::from(appdata).join(APP_NAME));
+    }
+    non_empty("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| non_empty("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .map(|dir| dir.join(APP_NAME))
+}
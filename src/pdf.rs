@@ -0,0 +1,393 @@
+// pdf.rs - Printable month and year calendars as PDF
+//
+// One A4 landscape page per Bangla month: the month with its year and ritu,
+// the Gregorian months it spans, and a grid of days with the Gregorian date
+// in small type and holidays in their punjika colors. Text is shaped with the
+// embedded Ekush font, which is subset into the file so the PDF prints the
+// same everywhere.
+
+use crate::calendar::{get_current_bangla_date, gregorian_span, to_bangla_number};
+use crate::canvas::{Align, color_rgb};
+use crate::constants::*;
+use crate::holidays::Holiday;
+use crate::locations::current_location;
+use crate::month_layout::{MonthLayout, MonthMarks, current_week, holiday_color};
+use crate::shaping::{EKUSH_FONT_DATA, ekush_face, shape_text};
+use pdf_writer::types::{CidFontType, FontFlags, SystemInfo};
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+use std::collections::BTreeMap;
+
+// A4 landscape, in points
+const PAGE_WIDTH: f32 = 842.0;
+const PAGE_HEIGHT: f32 = 595.0;
+const PAGE_MARGIN: f32 = 36.0;
+const HEADER_HEIGHT: f32 = 64.0;
+const WEEKDAY_HEIGHT: f32 = 26.0;
+const CELL_PADDING: f32 = 6.0;
+const GRID_LINE: u32 = 0x00E0E0E0;
+
+const FONT_NAME: Name = Name(b"F1");
+const FONT_BASE_NAME: Name = Name(b"BNGCAL+Ekush-Regular");
+
+/// Size (points), color and alignment of a line of text
+#[derive(Clone, Copy)]
+struct TextStyle {
+    size: f32,
+    color: u32,
+    align: Align,
+}
+
+const MONTH_STYLE: TextStyle = TextStyle {
+    size: 26.0,
+    color: CAL_HEADER_TEXT,
+    align: Align::Center,
+};
+const SUB_STYLE: TextStyle = TextStyle {
+    size: 13.0,
+    color: CAL_HEADER_TEXT,
+    align: Align::Center,
+};
+const WEEKDAY_STYLE: TextStyle = TextStyle {
+    size: 12.0,
+    color: CAL_WEEKDAY_TEXT,
+    align: Align::Center,
+};
+const DAY_STYLE: TextStyle = TextStyle {
+    size: 24.0,
+    color: CAL_DATE_TEXT,
+    align: Align::Left,
+};
+const GREGORIAN_STYLE: TextStyle = TextStyle {
+    size: 9.0,
+    color: CAL_FOOTER_TEXT,
+    align: Align::Right,
+};
+const HOLIDAY_STYLE: TextStyle = TextStyle {
+    size: 8.0,
+    color: CAL_HOLIDAY_TEXT,
+    align: Align::Left,
+};
+
+/// A COLORREF as PDF RGB
+fn rgb(color: u32) -> (f32, f32, f32) {
+    let (r, g, b) = color_rgb(color);
+    (r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0)
+}
+
+/// A page being drawn, and the glyphs used so far in the document (with the
+/// text each one stands for, for copy and search)
+struct PageWriter<'a> {
+    content: Content,
+    glyphs: &'a mut BTreeMap<u16, String>,
+}
+
+impl PageWriter<'_> {
+    fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: u32) {
+        let (r, g, b) = rgb(color);
+        self.content.set_fill_rgb(r, g, b);
+        self.content.rect(x, y, width, height);
+        self.content.fill_nonzero();
+    }
+
+    fn stroke_rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: u32) {
+        let (r, g, b) = rgb(color);
+        self.content.set_stroke_rgb(r, g, b);
+        self.content.set_line_width(0.75);
+        self.content.rect(x, y, width, height);
+        self.content.stroke();
+    }
+
+    /// Draw a line of text on a baseline; shrunk if it is wider than
+    /// `max_width`
+    fn text(&mut self, text: &str, x: f32, y: f32, style: TextStyle, max_width: f32) {
+        let shaped = shape_text(text);
+        if shaped.glyphs.is_empty() {
+            return;
+        }
+        let size = style.size.min(max_width / shaped.width);
+        let width = shaped.width * size;
+        let left = match style.align {
+            Align::Left => x,
+            Align::Center => x - width / 2.0,
+            Align::Right => x - width,
+        };
+
+        let (r, g, b) = rgb(style.color);
+        self.content.set_fill_rgb(r, g, b);
+        self.content.begin_text();
+        self.content.set_font(FONT_NAME, size);
+        for glyph in &shaped.glyphs {
+            self.content.set_text_matrix([
+                size,
+                0.0,
+                0.0,
+                size,
+                left + glyph.x * size,
+                y + glyph.y * size,
+            ]);
+            self.content.show(Str(&glyph.id.to_be_bytes()));
+            let text = self.glyphs.entry(glyph.id).or_default();
+            if text.is_empty() {
+                text.push_str(&glyph.text);
+            }
+        }
+        self.content.end_text();
+    }
+}
+
+/// Draw one Bangla month (0-11) on a page
+fn draw_month(page: &mut PageWriter, year: i32, month: i32) {
+    let left = PAGE_MARGIN;
+    let width = PAGE_WIDTH - PAGE_MARGIN * 2.0;
+    let center = PAGE_WIDTH / 2.0;
+    // The grid's rows and columns; the page has its own geometry
+    let layout = MonthLayout::new(
+        year,
+        month,
+        width,
+        &get_current_bangla_date(),
+        current_week(),
+        MonthMarks::for_month(year, month, current_location()),
+    );
+
+    // Header: month, then year · ritu · Gregorian months
+    let header_top = PAGE_HEIGHT - PAGE_MARGIN;
+    page.fill_rect(
+        left,
+        header_top - HEADER_HEIGHT,
+        width,
+        HEADER_HEIGHT,
+        CAL_HEADER_BG,
+    );
+    page.text(
+        BANGLA_MONTHS[month as usize],
+        center,
+        header_top - 32.0,
+        MONTH_STYLE,
+        width,
+    );
+    let sub_text = format!(
+        "{} বঙ্গাব্দ · {}কাল · {}",
+        to_bangla_number(year),
+        BANGLA_SEASONS[month as usize],
+        gregorian_span(layout.first_days, layout.last_days)
+    );
+    page.text(&sub_text, center, header_top - 54.0, SUB_STYLE, width);
+
+    // Weekday names
+    let cell_width = width / 7.0;
+    let weekday_top = header_top - HEADER_HEIGHT;
+    for column in 0..7 {
+        let weekday = layout.week.weekday(column);
+        page.text(
+            BANGLA_WEEKDAYS_SHORT[weekday],
+            left + cell_width * (column as f32 + 0.5),
+            weekday_top - 18.0,
+            TextStyle {
+                color: if layout.week.is_weekend(weekday) {
+                    CAL_WEEKEND_TEXT
+                } else {
+                    CAL_WEEKDAY_TEXT
+                },
+                ..WEEKDAY_STYLE
+            },
+            cell_width,
+        );
+    }
+
+    // Day grid
+    let grid_top = weekday_top - WEEKDAY_HEIGHT;
+    let cell_height = (grid_top - PAGE_MARGIN) / layout.rows as f32;
+    let text_width = cell_width - CELL_PADDING * 2.0;
+    for cell in &layout.cells {
+        let cell_left = left + cell_width * cell.column as f32;
+        let cell_top = grid_top - cell_height * cell.row as f32;
+        if cell.is_weekend {
+            page.fill_rect(
+                cell_left,
+                cell_top - cell_height,
+                cell_width,
+                cell_height,
+                CAL_WEEKEND_BG,
+            );
+        }
+        page.stroke_rect(
+            cell_left,
+            cell_top - cell_height,
+            cell_width,
+            cell_height,
+            GRID_LINE,
+        );
+
+        page.text(
+            &to_bangla_number(cell.day),
+            cell_left + CELL_PADDING,
+            cell_top - 28.0,
+            TextStyle {
+                color: cell.date_color(),
+                ..DAY_STYLE
+            },
+            text_width,
+        );
+
+        // Gregorian date, with its month where it changes
+        page.text(
+            &cell.gregorian_label(),
+            cell_left + cell_width - CELL_PADDING,
+            cell_top - 16.0,
+            GREGORIAN_STYLE,
+            text_width / 2.0,
+        );
+
+        // Holiday names along the bottom, the first one on top
+        let day_holidays: Vec<&Holiday> = layout.holidays_on(cell.days).collect();
+        let lines = day_holidays
+            .len()
+            .min(((cell_height - 36.0) / 10.0).max(0.0) as usize);
+        for (line, holiday) in day_holidays.iter().take(lines).enumerate() {
+            page.text(
+                &holiday.name,
+                cell_left + CELL_PADDING,
+                cell_top - cell_height + CELL_PADDING + 10.0 * (lines - 1 - line) as f32,
+                TextStyle {
+                    color: holiday_color(Some(holiday.kind)),
+                    ..HOLIDAY_STYLE
+                },
+                text_width,
+            );
+        }
+    }
+}
+
+/// Embed the glyphs used as a subset of Ekush (a CID-keyed TrueType font)
+fn write_font(pdf: &mut Pdf, type0_id: Ref, next_id: &mut Ref, glyphs: &BTreeMap<u16, String>) {
+    let mut alloc = || next_id.bump();
+    let (cid_id, descriptor_id, cmap_id, file_id) = (alloc(), alloc(), alloc(), alloc());
+
+    let face = ekush_face();
+    let scale = 1000.0 / face.units_per_em() as f32;
+    let system_info = SystemInfo {
+        registry: Str(b"Adobe"),
+        ordering: Str(b"Identity"),
+        supplement: 0,
+    };
+
+    pdf.type0_font(type0_id)
+        .base_font(FONT_BASE_NAME)
+        .encoding_predefined(Name(b"Identity-H"))
+        .descendant_font(cid_id)
+        .to_unicode(cmap_id);
+
+    let mut cid = pdf.cid_font(cid_id);
+    cid.subtype(CidFontType::Type2)
+        .base_font(FONT_BASE_NAME)
+        .system_info(system_info)
+        .font_descriptor(descriptor_id)
+        .default_width(0.0)
+        .cid_to_gid_map_predefined(Name(b"Identity"));
+    let mut widths = cid.widths();
+    for &id in glyphs.keys() {
+        let advance = face
+            .glyph_hor_advance(rustybuzz::ttf_parser::GlyphId(id))
+            .unwrap_or(0);
+        widths.consecutive(id, [advance as f32 * scale]);
+    }
+    widths.finish();
+    cid.finish();
+
+    let bbox = face.global_bounding_box();
+    pdf.font_descriptor(descriptor_id)
+        .name(FONT_BASE_NAME)
+        .flags(FontFlags::SYMBOLIC)
+        .bbox(Rect::new(
+            bbox.x_min as f32 * scale,
+            bbox.y_min as f32 * scale,
+            bbox.x_max as f32 * scale,
+            bbox.y_max as f32 * scale,
+        ))
+        .italic_angle(0.0)
+        .ascent(face.ascender() as f32 * scale)
+        .descent(face.descender() as f32 * scale)
+        .cap_height(face.capital_height().unwrap_or(face.ascender()) as f32 * scale)
+        .stem_v(80.0)
+        .font_file2(file_id);
+
+    let mut cmap = pdf_writer::types::UnicodeCmap::new(Name(b"Ekush-UTF16"), system_info);
+    for (&id, text) in glyphs {
+        if !text.is_empty() {
+            cmap.pair_with_multiple(id, text.chars());
+        }
+    }
+    pdf.cmap(cmap_id, &cmap.finish());
+
+    // Glyph IDs are kept, so the subset still matches the shaped text
+    let ids: Vec<u16> = glyphs.keys().copied().collect();
+    let font_data = subsetter::subset(EKUSH_FONT_DATA, 0, subsetter::Profile::pdf(&ids))
+        .unwrap_or_else(|_| EKUSH_FONT_DATA.to_vec());
+    let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&font_data, 6);
+    pdf.stream(file_id, &compressed)
+        .filter(Filter::FlateDecode)
+        .pair(Name(b"Length1"), font_data.len() as i32);
+}
+
+/// A PDF with one page for each (Bangla year, month 0-11)
+fn calendar_pdf(title: &str, months: &[(i32, i32)]) -> Vec<u8> {
+    let mut pdf = Pdf::new();
+    let mut next_id = Ref::new(1);
+    let catalog_id = next_id.bump();
+    let page_tree_id = next_id.bump();
+    let info_id = next_id.bump();
+    let font_id = next_id.bump();
+
+    let mut glyphs = BTreeMap::new();
+    let mut page_ids = Vec::with_capacity(months.len());
+    for &(year, month) in months {
+        let page_id = next_id.bump();
+        let content_id = next_id.bump();
+        page_ids.push(page_id);
+
+        let mut page = PageWriter {
+            content: Content::new(),
+            glyphs: &mut glyphs,
+        };
+        draw_month(&mut page, year, month);
+        let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&page.content.finish(), 6);
+        pdf.stream(content_id, &compressed)
+            .filter(Filter::FlateDecode);
+
+        let mut page = pdf.page(page_id);
+        page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT))
+            .parent(page_tree_id)
+            .contents(content_id);
+        page.resources().fonts().pair(FONT_NAME, font_id);
+        page.finish();
+    }
+
+    // The font objects come last, once every glyph is known
+    write_font(&mut pdf, font_id, &mut next_id, &glyphs);
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.pages(page_tree_id)
+        .count(page_ids.len() as i32)
+        .kids(page_ids);
+    pdf.document_info(info_id)
+        .title(TextStr(title))
+        .creator(TextStr("Bangla Calendar"));
+    pdf.finish()
+}
+
+/// একটি মাসের ক্যালেন্ডার (month 0-11)
+pub fn month_pdf(year: i32, month: i32) -> Vec<u8> {
+    let title = format!(
+        "{} {}",
+        BANGLA_MONTHS[month as usize],
+        to_bangla_number(year)
+    );
+    calendar_pdf(&title, &[(year, month)])
+}
+
+/// পুরো বছরের ক্যালেন্ডার: বৈশাখ থেকে চৈত্র, প্রতি পাতায় এক মাস
+pub fn year_pdf(year: i32) -> Vec<u8> {
+    let title = format!("{} বঙ্গাব্দ", to_bangla_number(year));
+    let months: Vec<(i32, i32)> = (0..12).map(|month| (year, month)).collect();
+    calendar_pdf(&title, &months)
+}
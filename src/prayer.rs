@@ -0,0 +1,151 @@
+// prayer.rs - Islamic prayer times (নামাজের সময়) and Ramadan sehri/iftar
+
+use crate::astronomy::*;
+use crate::calendar::{civil_from_days, format_clock_time, to_bangla_number};
+use crate::hijri::{RAMADAN, gregorian_to_hijri};
+use crate::locations::{Location, current_location};
+use crate::panchang::local_day_minute;
+use crate::{ASR_RULE, PRAYER_METHOD};
+use std::sync::atomic::Ordering;
+
+// The five prayers, in order
+pub const PRAYER_NAMES: &[&str] = &["ফজর", "যোহর", "আসর", "মাগরিব", "এশা"];
+pub const FAJR: usize = 0;
+pub const MAGHRIB: usize = 3;
+
+/// A calculation method: twilight angles for Fajr and Isha, and the
+/// precautionary minutes (ইহতিয়াত) added to each prayer
+pub struct PrayerMethod {
+    pub name: &'static str,
+    fajr_angle: f64,
+    isha_angle: f64,
+    offsets: [i32; 5],
+}
+
+// The first entry is the default
+pub const PRAYER_METHODS: &[PrayerMethod] = &[
+    PrayerMethod {
+        name: "ইসলামিক ফাউন্ডেশন বাংলাদেশ",
+        fajr_angle: 18.0,
+        isha_angle: 18.0,
+        offsets: [0, 2, 1, 1, 0],
+    },
+    PrayerMethod {
+        name: "করাচি (University of Islamic Sciences)",
+        fajr_angle: 18.0,
+        isha_angle: 18.0,
+        offsets: [0, 0, 0, 0, 0],
+    },
+];
+
+/// Asr begins when a shadow is its object's length (standard) or twice that (Hanafi)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsrRule {
+    Hanafi,
+    Standard,
+}
+
+impl AsrRule {
+    pub fn from_setting(value: u32) -> Self {
+        if value == 1 {
+            AsrRule::Standard
+        } else {
+            AsrRule::Hanafi
+        }
+    }
+
+    fn shadow_factor(self) -> f64 {
+        match self {
+            AsrRule::Hanafi => 2.0,
+            AsrRule::Standard => 1.0,
+        }
+    }
+}
+
+/// Start of each prayer on a local day (Julian Days, UT); None where the
+/// Sun never reaches the needed depression
+pub fn prayer_times(
+    days: i64,
+    location: &Location,
+    method: &PrayerMethod,
+    asr_rule: AsrRule,
+) -> [Option<f64>; 5] {
+    let day_start = location.day_start(days);
+    let (lat, lon) = (location.latitude, location.longitude);
+
+    let noon = solar_noon(day_start, lon);
+    // Asr: the Sun's altitude when the shadow grows to factor + noon shadow
+    let zenith_distance = (lat - sun_declination(noon)).abs();
+    let asr_altitude = (1.0 / (asr_rule.shadow_factor() + zenith_distance.to_radians().tan()))
+        .atan()
+        .to_degrees();
+
+    let times = [
+        sun_altitude_crossing(day_start, lat, lon, -method.fajr_angle, true),
+        Some(noon),
+        sun_altitude_crossing(day_start, lat, lon, asr_altitude, false),
+        sunset(day_start, lat, lon),
+        sun_altitude_crossing(day_start, lat, lon, -method.isha_angle, false),
+    ];
+
+    let mut result = [None; 5];
+    for (i, time) in times.iter().enumerate() {
+        result[i] = time.map(|jd| jd + method.offsets[i] as f64 / 1440.0);
+    }
+    result
+}
+
+/// Prayer times for the chosen location, method and Asr rule
+pub fn current_prayer_times(days: i64) -> [Option<f64>; 5] {
+    let method = PRAYER_METHODS
+        .get(PRAYER_METHOD.load(Ordering::Relaxed) as usize)
+        .unwrap_or(&PRAYER_METHODS[0]);
+    let asr_rule = AsrRule::from_setting(ASR_RULE.load(Ordering::Relaxed));
+    prayer_times(days, current_location(), method, asr_rule)
+}
+
+/// Whether a day falls in Ramadan (tabular Hijri calendar)
+pub fn is_ramadan(days: i64) -> bool {
+    let (year, month, day) = civil_from_days(days);
+    gregorian_to_hijri(year, month, day).1 == RAMADAN
+}
+
+/// Widget line: আসর ৩:৪৫ • ১ ঘণ্টা ১২ মিনিট বাকি
+/// (in Ramadan Fajr and Maghrib are shown as সেহরি শেষ and ইফতার), or None
+/// where no prayer time can be calculated
+pub fn format_next_prayer_line(now: f64) -> Option<String> {
+    let offset = current_location().utc_offset_minutes;
+    let (today, _) = local_day_minute(now, offset);
+
+    // The next start today, or tomorrow's Fajr
+    let next = current_prayer_times(today)
+        .iter()
+        .enumerate()
+        .find_map(|(i, t)| t.filter(|&jd| jd > now).map(|jd| (i, jd)))
+        .or_else(|| current_prayer_times(today + 1)[FAJR].map(|jd| (FAJR, jd)));
+
+    let (prayer, jd) = next?;
+    let (day, minute) = local_day_minute(jd, offset);
+    let name = match prayer {
+        FAJR if is_ramadan(day) => "সেহরি শেষ",
+        MAGHRIB if is_ramadan(day) => "ইফতার",
+        _ => PRAYER_NAMES[prayer],
+    };
+
+    let minutes_left = ((jd - now) * 1440.0).ceil() as i32;
+    let remaining = if minutes_left >= 60 {
+        format!(
+            "{} ঘণ্টা {} মিনিট",
+            to_bangla_number(minutes_left / 60),
+            to_bangla_number(minutes_left % 60)
+        )
+    } else {
+        format!("{} মিনিট", to_bangla_number(minutes_left))
+    };
+    Some(format!(
+        "{} {} • {} বাকি",
+        name,
+        format_clock_time(minute),
+        remaining
+    ))
+}
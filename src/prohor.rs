@@ -0,0 +1,108 @@
+// prohor.rs - Traditional time of day: প্রহর, দণ্ড and পল
+//
+// Day (sunrise to sunset) and night (sunset to the next sunrise) each have
+// four প্রহর, so they stretch and shrink with the seasons. দণ্ড and পল are
+// counted from sunrise: 60 দণ্ড make a day and night, 60 পল make a দণ্ড.
+
+use crate::calendar::to_bangla_number;
+use crate::constants::PROHOR_ORDINALS;
+use crate::locations::Location;
+use crate::panchang::local_day_minute;
+
+/// A moment in traditional units
+#[derive(Debug, Clone, Copy)]
+pub struct TraditionalTime {
+    pub is_day: bool,
+    pub prohor: i32,        // 0-3 within the day or the night
+    pub since_sunrise: f64, // days since the last sunrise
+}
+
+impl TraditionalTime {
+    /// দিনের দ্বিতীয় প্রহর, রাতের প্রথম প্রহর
+    pub fn get_prohor_name(&self) -> String {
+        format!(
+            "{} {} প্রহর",
+            if self.is_day {
+                "দিনের"
+            } else {
+                "রাতের"
+            },
+            PROHOR_ORDINALS[self.prohor.clamp(0, 3) as usize]
+        )
+    }
+}
+
+/// Sunrise and sunset of a local day, at 06:00 and 18:00 where the Sun
+/// doesn't rise or set
+fn sun_times_or_default(days: i64, location: &Location) -> (f64, f64) {
+    let day_start = location.day_start(days);
+    let (sunrise, sunset) = location.sun_times(days);
+    (
+        sunrise.unwrap_or(day_start + 0.25),
+        sunset.unwrap_or(day_start + 0.75),
+    )
+}
+
+/// The traditional time at a moment (Julian Day UT)
+pub fn traditional_time_at(jd: f64, location: &Location) -> TraditionalTime {
+    let (today, _) = local_day_minute(jd, location.utc_offset_minutes);
+    // Before sunrise it is still the night of the previous day
+    let (mut day, (mut sunrise, mut sunset)) = (today, sun_times_or_default(today, location));
+    if jd < sunrise {
+        day -= 1;
+        (sunrise, sunset) = sun_times_or_default(day, location);
+    }
+
+    let (start, end, is_day) = if jd < sunset {
+        (sunrise, sunset, true)
+    } else {
+        (sunset, sun_times_or_default(day + 1, location).0, false)
+    };
+    let prohor = ((jd - start) / (end - start) * 4.0).floor() as i32;
+    TraditionalTime {
+        is_day,
+        prohor: prohor.clamp(0, 3),
+        since_sunrise: jd - sunrise,
+    }
+}
+
+/// A length of time in দণ্ড and পল (1 দণ্ড = 24 minutes, 1 পল = 24 seconds)
+pub fn format_dondo_pol(duration: f64) -> String {
+    let pol = (duration * 3600.0).floor().max(0.0) as i32;
+    format!(
+        "{} দণ্ড {} পল",
+        to_bangla_number(pol / 60),
+        to_bangla_number(pol % 60)
+    )
+}
+
+/// দিনমান and রাত্রিমান of a local day: sunrise to sunset, sunset to the next sunrise
+pub fn day_and_night_length(days: i64, location: &Location) -> (f64, f64) {
+    let (sunrise, sunset) = sun_times_or_default(days, location);
+    let (next_sunrise, _) = sun_times_or_default(days + 1, location);
+    (sunset - sunrise, next_sunrise - sunset)
+}
+
+/// Widget line: দিনের দ্বিতীয় প্রহর • ৮ দণ্ড ২৪ পল
+pub fn format_prohor_line(jd: f64, location: &Location) -> String {
+    let time = traditional_time_at(jd, location);
+    format!(
+        "{} • {}",
+        time.get_prohor_name(),
+        format_dondo_pol(time.since_sunrise)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dondo_and_pol() {
+        // 1 দণ্ড = 24 minutes, 1 পল = 24 seconds
+        assert_eq!(format_dondo_pol(0.0), "০ দণ্ড ০ পল");
+        assert_eq!(format_dondo_pol(0.5), "৩০ দণ্ড ০ পল");
+        let eight_dondo_ten_pol = (8.0 * 24.0 * 60.0 + 10.0 * 24.0 + 1.0) / 86400.0;
+        assert_eq!(format_dondo_pol(eight_dondo_ten_pol), "৮ দণ্ড ১০ পল");
+    }
+}
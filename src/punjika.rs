@@ -1,44 +1,127 @@
 // punjika.rs - Calendar popup window (পুঞ্জিকা)
 
 use crate::calendar::*;
+use crate::canvas::Rect;
 use crate::constants::*;
+use crate::date_prompt::show_date_prompt;
+use crate::day_view::show_day_view;
+use crate::eclipses::Visibility;
+use crate::event_editor::show_event_editor;
+use crate::events::{UserEvent, events_between};
 use crate::fonts::{
     get_cal_date_font, get_cal_header_font, get_cal_nav_font, get_cal_sub_font,
     get_cal_weekday_font,
 };
-use crate::get_flag_icon;
+use crate::hijri::{RAMADAN, gregorian_to_hijri};
+use crate::holidays::holidays_between;
+use crate::locations::current_location;
+use crate::menu::{handle_draw_item, handle_measure_item, show_event_menu};
+use crate::month_layout::{
+    CellLabel, DayCell, MonthLayout, MonthMarks, Week, YearLayout, current_week,
+};
+use crate::panchang::MoonPhase;
+use crate::ramadan::show_ramadan_timetable;
+use crate::{PUNJIKA_CELL_LABEL, PUNJIKA_SIX_ROWS, get_flag_icon};
+use std::sync::Mutex;
 use std::sync::atomic::{AtomicI32, AtomicPtr, Ordering};
 use windows::{
-    Win32::Foundation::*, Win32::Graphics::Dwm::*, Win32::Graphics::Gdi::*,
-    Win32::System::LibraryLoader::GetModuleHandleW, Win32::UI::WindowsAndMessaging::*, core::*,
+    Win32::Foundation::*,
+    Win32::Graphics::Dwm::*,
+    Win32::Graphics::Gdi::*,
+    Win32::System::LibraryLoader::GetModuleHandleW,
+    Win32::UI::HiDpi::*,
+    Win32::UI::Input::KeyboardAndMouse::{GetKeyState, VK_CONTROL},
+    Win32::UI::WindowsAndMessaging::*,
+    core::*,
 };
 
-// Calendar state - track which month/year we're viewing
-static VIEW_MONTH: AtomicI32 = AtomicI32::new(0); // 0-11
-static VIEW_YEAR: AtomicI32 = AtomicI32::new(1431);
+/// What the punjika shows: a month with a selected day, or the year view of
+/// its year
+#[derive(Clone, Copy)]
+struct ViewState {
+    year: i32,
+    month: i32,      // 0-11
+    day: i32,        // the selected day, 1-based
+    year_view: bool, // the twelve months instead of one
+}
+
+impl ViewState {
+    /// Move by `delta` months across years, keeping the selected day if the
+    /// month has it (else its last day)
+    fn add_months(&mut self, delta: i32) {
+        let index = self.year * 12 + self.month + delta;
+        self.year = index.div_euclid(12);
+        self.month = index.rem_euclid(12);
+        self.day = self.day.min(get_bangla_month_days(self.month, self.year));
+    }
+
+    /// Previous/next: a month, or a year in the year view
+    fn step(&mut self, delta: i32) {
+        self.add_months(if self.year_view { delta * 12 } else { delta });
+    }
+
+    /// The selected day, as days since 1970-01-01
+    fn selected_days(&self) -> i64 {
+        let (year, month, day) = gregorian_from_bangla(self.year, self.month, self.day);
+        days_from_civil(year, month, day)
+    }
+
+    /// Select a day (days since 1970-01-01) and show its month
+    fn show_day(&mut self, days: i64) {
+        let date = bangla_date_from_days(days);
+        self.year = date.year;
+        self.month = date.month;
+        self.day = date.day;
+    }
+
+    /// Move the selection by `delta` days, into the next or previous month
+    /// at the edges
+    fn move_selection(&mut self, delta: i64) {
+        self.show_day(self.selected_days() + delta);
+    }
+
+    /// Select today, in its month (or year)
+    fn show_today(&mut self) {
+        let today = get_current_bangla_date();
+        self.year = today.year;
+        self.month = today.month;
+        self.day = today.day;
+    }
+}
+
+// Calendar state - which month/year we're viewing and the selected day
+static VIEW: Mutex<ViewState> = Mutex::new(ViewState {
+    year: 1431,
+    month: 0,
+    day: 1,
+    year_view: false,
+});
+// Mouse wheel movement not yet turned into months (see WM_MOUSEWHEEL)
+static WHEEL_DELTA_SUM: AtomicI32 = AtomicI32::new(0);
 static HOVER_DAY: AtomicI32 = AtomicI32::new(-1);
 static CALENDAR_HWND_PTR: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(std::ptr::null_mut());
-
-// Calendar window dimensions
-const CAL_WIDTH: i32 = 320;
-const CAL_HEIGHT: i32 = 380;
-const CAL_HEADER_HEIGHT: i32 = 60;
-const CAL_NAV_HEIGHT: i32 = 40;
-const CAL_WEEKDAY_HEIGHT: i32 = 30;
-const CAL_CELL_SIZE: i32 = 40;
-const CAL_PADDING: i32 = 12;
-
-// Colors
-const CAL_BG: u32 = 0x00FFFFFF;
-const CAL_HEADER_BG: u32 = 0x00006B3C; // Bangladesh green
-const CAL_HEADER_TEXT: u32 = 0x00FFFFFF;
-const CAL_NAV_BG: u32 = 0x00F5F5F5;
-const CAL_NAV_TEXT: u32 = 0x00333333;
-const CAL_WEEKDAY_TEXT: u32 = 0x00666666;
-const CAL_DATE_TEXT: u32 = 0x00333333;
-const CAL_TODAY_BG: u32 = 0x00006B3C;
-const CAL_TODAY_TEXT: u32 = 0x00FFFFFF;
-const CAL_HOVER_BG: u32 = 0x00E8F5E9;
+// Day cell size, the height of its label line and of a line of the detail
+// panel, measured from the fonts when the window opens and when the label
+// setting changes
+static CELL_WIDTH: AtomicI32 = AtomicI32::new((CAL_WIDTH - CAL_PADDING * 2) / 7);
+static CELL_HEIGHT: AtomicI32 = AtomicI32::new(CAL_CELL_SIZE);
+static LABEL_HEIGHT: AtomicI32 = AtomicI32::new(0);
+static DETAIL_LINE_HEIGHT: AtomicI32 = AtomicI32::new(18);
+// The viewed month's layout for hit-testing mouse moves, with the year, month
+// and client size it was laid out for; forgotten when the view or the
+// settings change
+type LayoutKey = (i32, i32, i32, i32);
+static HIT_LAYOUT: Mutex<Option<(LayoutKey, MonthLayout)>> = Mutex::new(None);
+
+// The header's third line, with the Gregorian months
+const CAL_SPAN_LINE_HEIGHT: i32 = 18;
+
+// The detail panel under the grid: the selected day's dates, then its
+// holidays and events
+const CAL_DETAIL_LINES: i32 = 3;
+
+const CAL_EVENT_DOT_SIZE: i32 = 5;
+const CAL_EVENT_MAX_DOTS: usize = 3;
 
 #[inline]
 fn get_calendar_hwnd() -> HWND {
@@ -50,6 +133,51 @@ fn set_calendar_hwnd(hwnd: HWND) {
     CALENDAR_HWND_PTR.store(hwnd.0, Ordering::Relaxed);
 }
 
+/// Redraw the punjika, if it is open, after its data changed
+pub fn refresh_calendar() {
+    forget_hit_layout();
+    let cal_hwnd = get_calendar_hwnd();
+    if !cal_hwnd.is_invalid() {
+        unsafe {
+            let _ = InvalidateRect(Some(cal_hwnd), None, true);
+        }
+    }
+}
+
+/// The punjika's current view
+fn view() -> ViewState {
+    *VIEW.lock().unwrap()
+}
+
+/// Change the view, then fit and redraw the window
+fn update_view(hwnd: HWND, change: impl FnOnce(&mut ViewState)) {
+    change(&mut VIEW.lock().unwrap());
+    HOVER_DAY.store(-1, Ordering::Relaxed);
+    view_changed(hwnd);
+}
+
+/// Show the month of a day (days since 1970-01-01) in the punjika, if it is
+/// open, as the go-to-date prompt asks
+pub fn show_calendar_date(days: i64) {
+    let cal_hwnd = get_calendar_hwnd();
+    if !cal_hwnd.is_invalid() {
+        update_view(cal_hwnd, |view| {
+            view.show_day(days);
+            view.year_view = false;
+        });
+    }
+}
+
+/// Fit the punjika, if it is open, to the viewed month after a layout
+/// setting changed
+pub fn resize_calendar() {
+    let cal_hwnd = get_calendar_hwnd();
+    if !cal_hwnd.is_invalid() {
+        measure_cells(cal_hwnd);
+        view_changed(cal_hwnd);
+    }
+}
+
 /// Show the calendar popup
 pub fn show_calendar(parent: HWND) {
     unsafe {
@@ -61,9 +189,11 @@ pub fn show_calendar(parent: HWND) {
         }
 
         // Initialize to current Bangla date
-        let current = get_current_bangla_date();
-        VIEW_MONTH.store(current.month, Ordering::Relaxed);
-        VIEW_YEAR.store(current.year, Ordering::Relaxed);
+        {
+            let mut view = VIEW.lock().unwrap();
+            view.show_today();
+            view.year_view = false;
+        }
 
         let instance = GetModuleHandleW(None).unwrap_or_default();
         let class_name = w!("BongoPunjikaClass");
@@ -71,7 +201,7 @@ pub fn show_calendar(parent: HWND) {
         // Register window class
         let wc = WNDCLASSEXW {
             cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
-            style: CS_HREDRAW | CS_VREDRAW,
+            style: CS_HREDRAW | CS_VREDRAW | CS_DBLCLKS,
             lpfnWndProc: Some(calendar_wndproc),
             hInstance: instance.into(),
             hCursor: LoadCursorW(None, IDC_ARROW).unwrap_or_default(),
@@ -83,21 +213,16 @@ pub fn show_calendar(parent: HWND) {
         };
         RegisterClassExW(&wc);
 
-        // Center on screen
-        let screen_width = GetSystemMetrics(SM_CXSCREEN);
-        let screen_height = GetSystemMetrics(SM_CYSCREEN);
-        let x = (screen_width - CAL_WIDTH) / 2;
-        let y = (screen_height - CAL_HEIGHT) / 2;
-
+        // Created hidden; sized to the month and centered below
         let hwnd = CreateWindowExW(
             WS_EX_TOPMOST | WS_EX_DLGMODALFRAME,
             class_name,
             w!("পুঞ্জিকা"),
-            WS_POPUP | WS_VISIBLE | WS_CAPTION | WS_SYSMENU,
-            x,
-            y,
+            WS_POPUP | WS_CAPTION | WS_SYSMENU,
+            0,
+            0,
+            CAL_WIDTH,
             CAL_WIDTH,
-            CAL_HEIGHT,
             Some(parent),
             None,
             Some(instance.into()),
@@ -106,6 +231,23 @@ pub fn show_calendar(parent: HWND) {
         .unwrap_or_default();
 
         set_calendar_hwnd(hwnd);
+        measure_cells(hwnd);
+        fit_window(hwnd);
+
+        // Center on screen
+        let mut window_rect = RECT::default();
+        let _ = GetWindowRect(hwnd, &mut window_rect);
+        let screen_width = GetSystemMetrics(SM_CXSCREEN);
+        let screen_height = GetSystemMetrics(SM_CYSCREEN);
+        let _ = SetWindowPos(
+            hwnd,
+            None,
+            (screen_width - (window_rect.right - window_rect.left)) / 2,
+            (screen_height - (window_rect.bottom - window_rect.top)) / 2,
+            0,
+            0,
+            SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE,
+        );
 
         // Set rounded corners on Windows 11
         let preference = DWM_WINDOW_CORNER_PREFERENCE(2); // DWMWCP_ROUND
@@ -121,76 +263,346 @@ pub fn show_calendar(parent: HWND) {
     }
 }
 
-/// Get the number of days in a Bangla month
-fn get_bangla_month_days(month: i32, year: i32) -> i32 {
-    // Bangladesh calendar: first 5 months have 31 days, rest have 30
-    // Falgun (month 10) has 31 days in leap years
-    if month < 5 {
-        31
-    } else if month == 10 {
-        // Falgun - check leap year
-        let gregorian_year = year + 594;
-        if (gregorian_year % 4 == 0 && gregorian_year % 100 != 0) || (gregorian_year % 400 == 0) {
-            31
+/// A layout rectangle as a GDI RECT
+fn gdi_rect(rect: Rect) -> RECT {
+    RECT {
+        left: rect.left as i32,
+        top: rect.top as i32,
+        right: rect.right as i32,
+        bottom: rect.bottom as i32,
+    }
+}
+
+/// The viewed month laid out for the window's client area
+fn view_layout(client: &RECT) -> MonthLayout {
+    let view = view();
+    let layout = MonthLayout::new(
+        view.year,
+        view.month,
+        client.right as f32,
+        &get_current_bangla_date(),
+        current_week(),
+        MonthMarks::for_month(view.year, view.month, current_location()),
+    )
+    .with_header_height((CAL_HEADER_HEIGHT + CAL_SPAN_LINE_HEIGHT) as f32)
+    .with_cell_height(CELL_HEIGHT.load(Ordering::Relaxed) as f32)
+    .with_footer_height(
+        (DETAIL_LINE_HEIGHT.load(Ordering::Relaxed) * CAL_DETAIL_LINES + 10) as f32,
+    );
+    if PUNJIKA_SIX_ROWS.load(Ordering::Relaxed) {
+        layout.with_rows(6)
+    } else {
+        layout
+    }
+}
+
+/// The viewed year laid out for the year view
+fn view_year_layout() -> YearLayout {
+    let year = view().year;
+    let (first_days, last_days) = bangla_year_days(year);
+    YearLayout::new(
+        year,
+        &get_current_bangla_date(),
+        current_week(),
+        &holidays_between(first_days, last_days),
+    )
+}
+
+/// The header's first line, the month name (or the year in the year view);
+/// clicking it switches between the views
+fn title_rect(client: &RECT) -> RECT {
+    RECT {
+        left: CAL_PADDING,
+        top: 8,
+        right: client.right - CAL_PADDING,
+        bottom: 32,
+    }
+}
+
+/// What the day cells show under the Bangla day
+fn cell_label() -> CellLabel {
+    CellLabel::from_index(PUNJIKA_CELL_LABEL.load(Ordering::Relaxed))
+}
+
+/// Size the day cells to their text: the date font with the moon and event
+/// dots above and below it, and the label line under the date, as wide as
+/// the widest label of its kind (so the window does not change width); and
+/// the detail panel's lines to the small font
+fn measure_cells(hwnd: HWND) {
+    unsafe {
+        let hdc = GetDC(Some(hwnd));
+        let old_font = SelectObject(hdc, get_cal_date_font().into());
+        let mut metrics = TEXTMETRICW::default();
+        let _ = GetTextMetricsW(hdc, &mut metrics);
+        let date_height = metrics.tmHeight;
+
+        SelectObject(hdc, get_cal_sub_font().into());
+        let _ = GetTextMetricsW(hdc, &mut metrics);
+        DETAIL_LINE_HEIGHT.store(metrics.tmHeight, Ordering::Relaxed);
+        let samples = cell_label().samples();
+        let label_height = if samples.is_empty() {
+            0
         } else {
-            30
-        }
+            metrics.tmHeight
+        };
+        let label_width = samples
+            .iter()
+            .map(|text| {
+                let mut text: Vec<u16> = text.encode_utf16().collect();
+                let mut bounds = RECT::default();
+                DrawTextW(hdc, &mut text, &mut bounds, DT_CALCRECT | DT_SINGLELINE);
+                bounds.right
+            })
+            .max()
+            .unwrap_or(0);
+        SelectObject(hdc, old_font);
+        ReleaseDC(Some(hwnd), hdc);
+
+        // The label keeps clear of the cell's inset background by 2 px a side
+        CELL_WIDTH.store(
+            (label_width + 8).max((CAL_WIDTH - CAL_PADDING * 2) / 7),
+            Ordering::Relaxed,
+        );
+        CELL_HEIGHT.store(
+            (date_height + label_height + 2 * (CAL_MOON_SIZE + 3)).max(CAL_CELL_SIZE),
+            Ordering::Relaxed,
+        );
+        LABEL_HEIGHT.store(label_height, Ordering::Relaxed);
+    }
+}
+
+/// Size the window to the viewed month: seven cells wide, as many rows as it
+/// needs (or six), with the frame and caption as they are at the window's
+/// DPI; or to the year view. The top-left corner stays where it is.
+fn fit_window(hwnd: HWND) {
+    unsafe {
+        let (width, height) = if view().year_view {
+            let layout = view_year_layout();
+            (layout.width as i32, layout.height as i32)
+        } else {
+            let width = (CELL_WIDTH.load(Ordering::Relaxed) * 7 + CAL_PADDING * 2).max(CAL_WIDTH);
+            let client = RECT {
+                right: width,
+                ..Default::default()
+            };
+            (width, view_layout(&client).height().ceil() as i32)
+        };
+        let mut frame = RECT {
+            right: width,
+            bottom: height,
+            ..Default::default()
+        };
+        let _ = AdjustWindowRectExForDpi(
+            &mut frame,
+            WINDOW_STYLE(GetWindowLongW(hwnd, GWL_STYLE) as u32),
+            false,
+            WINDOW_EX_STYLE(GetWindowLongW(hwnd, GWL_EXSTYLE) as u32),
+            GetDpiForWindow(hwnd),
+        );
+        let _ = SetWindowPos(
+            hwnd,
+            None,
+            0,
+            0,
+            frame.right - frame.left,
+            frame.bottom - frame.top,
+            SWP_NOMOVE | SWP_NOZORDER | SWP_NOACTIVATE,
+        );
+    }
+}
+
+/// Resize and redraw after the viewed month changed
+fn view_changed(hwnd: HWND) {
+    forget_hit_layout();
+    fit_window(hwnd);
+    unsafe {
+        let _ = InvalidateRect(Some(hwnd), None, true);
+    }
+}
+
+/// Weekend days' names in red
+fn weekday_name_color(week: Week, weekday: usize) -> u32 {
+    if week.is_weekend(weekday) {
+        CAL_WEEKEND_TEXT
     } else {
-        30
+        CAL_WEEKDAY_TEXT
+    }
+}
+
+/// Fill a rectangle with rounded corners, as the day cells' backgrounds
+fn fill_round_rect(hdc: HDC, rect: &RECT, radius: i32, color: u32) {
+    unsafe {
+        let brush = CreateSolidBrush(COLORREF(color));
+        let rgn = CreateRoundRectRgn(rect.left, rect.top, rect.right, rect.bottom, radius, radius);
+        let _ = FillRgn(hdc, rgn, brush);
+        let _ = DeleteObject(rgn.into());
+        let _ = DeleteObject(brush.into());
+    }
+}
+
+/// Navigation bar with the previous and next buttons (months, or years in
+/// the year view)
+fn draw_nav_bar(hdc: HDC, nav_rect: &RECT) {
+    unsafe {
+        let nav_brush = CreateSolidBrush(COLORREF(CAL_NAV_BG));
+        FillRect(hdc, nav_rect, nav_brush);
+        let _ = DeleteObject(nav_brush.into());
+
+        let old_font = SelectObject(hdc, get_cal_nav_font().into());
+        SetTextColor(hdc, COLORREF(CAL_NAV_TEXT));
+
+        // Previous button (◀ পূর্ববর্তী)
+        let mut prev_text: Vec<u16> = "◀ পূর্ববর্তী".encode_utf16().collect();
+        let mut prev_rect = RECT {
+            left: CAL_PADDING,
+            top: nav_rect.top + 8,
+            right: nav_rect.right / 2 - 10,
+            bottom: nav_rect.bottom - 8,
+        };
+        DrawTextW(
+            hdc,
+            &mut prev_text,
+            &mut prev_rect,
+            DT_LEFT | DT_VCENTER | DT_SINGLELINE,
+        );
+
+        // Next button (পরবর্তী ▶)
+        let mut next_text: Vec<u16> = "পরবর্তী ▶".encode_utf16().collect();
+        let mut next_rect = RECT {
+            left: nav_rect.right / 2 + 10,
+            top: nav_rect.top + 8,
+            right: nav_rect.right - CAL_PADDING,
+            bottom: nav_rect.bottom - 8,
+        };
+        DrawTextW(
+            hdc,
+            &mut next_text,
+            &mut next_rect,
+            DT_RIGHT | DT_VCENTER | DT_SINGLELINE,
+        );
+        SelectObject(hdc, old_font);
     }
 }
 
-/// Get the weekday of the first day of a Bangla month
-fn get_first_day_weekday(month: i32, year: i32) -> i32 {
-    // Convert Bangla date to Gregorian and get weekday
-    // 1st Boishakh 1432 was April 14, 2025 (Monday = 1)
-
-    let ref_year = 1432;
-    let ref_month = 0; // Boishakh
-    let ref_weekday = 1; // Monday
-
-    // Calculate total days from reference
-    let mut total_days = 0;
-
-    if year > ref_year || (year == ref_year && month > ref_month) {
-        // Forward from reference
-        let mut y = ref_year;
-        let mut m = ref_month;
-
-        while y < year || (y == year && m < month) {
-            total_days += get_bangla_month_days(m, y);
-            m += 1;
-            if m > 11 {
-                m = 0;
-                y += 1;
+/// Draw the year view: twelve small months with today and the holidays
+/// marked as in the month view
+fn draw_year(hdc: HDC, rect: &RECT) {
+    unsafe {
+        let layout = view_year_layout();
+
+        let bg_brush = CreateSolidBrush(COLORREF(CAL_BG));
+        FillRect(hdc, rect, bg_brush);
+        let _ = DeleteObject(bg_brush.into());
+
+        let header_brush = CreateSolidBrush(COLORREF(CAL_HEADER_BG));
+        FillRect(hdc, &gdi_rect(layout.header_rect), header_brush);
+        let _ = DeleteObject(header_brush.into());
+
+        SetBkMode(hdc, TRANSPARENT);
+
+        // Header: the year, and the Gregorian years it spans
+        let old_font = SelectObject(hdc, get_cal_header_font().into());
+        SetTextColor(hdc, COLORREF(CAL_HEADER_TEXT));
+        let mut year_text: Vec<u16> = format!("{} বঙ্গাব্দ", to_bangla_number(layout.year))
+            .encode_utf16()
+            .collect();
+        DrawTextW(
+            hdc,
+            &mut year_text,
+            &mut title_rect(rect),
+            DT_CENTER | DT_SINGLELINE,
+        );
+        SelectObject(hdc, get_cal_sub_font().into());
+        let mut span_text: Vec<u16> = gregorian_span(layout.first_days, layout.last_days)
+            .encode_utf16()
+            .collect();
+        let mut span_rect = RECT {
+            left: CAL_PADDING,
+            top: 34,
+            right: rect.right - CAL_PADDING,
+            bottom: CAL_HEADER_HEIGHT - 4,
+        };
+        DrawTextW(
+            hdc,
+            &mut span_text,
+            &mut span_rect,
+            DT_CENTER | DT_SINGLELINE,
+        );
+
+        draw_nav_bar(hdc, &gdi_rect(layout.nav_rect));
+
+        for mini in &layout.months {
+            // Month name
+            SelectObject(hdc, get_cal_weekday_font().into());
+            SetTextColor(hdc, COLORREF(CAL_HEADER_BG));
+            let mut name_text: Vec<u16> =
+                BANGLA_MONTHS[mini.month as usize].encode_utf16().collect();
+            DrawTextW(
+                hdc,
+                &mut name_text,
+                &mut gdi_rect(mini.title_rect),
+                DT_CENTER | DT_VCENTER | DT_SINGLELINE,
+            );
+
+            // Weekday initials, from the first day of the week
+            SelectObject(hdc, get_cal_sub_font().into());
+            for column in 0..7 {
+                let weekday = layout.week.weekday(column);
+                SetTextColor(hdc, COLORREF(weekday_name_color(layout.week, weekday)));
+                let mut letter_text: Vec<u16> =
+                    BANGLA_WEEKDAYS_LETTER[weekday].encode_utf16().collect();
+                DrawTextW(
+                    hdc,
+                    &mut letter_text,
+                    &mut gdi_rect(mini.weekday_rect(column)),
+                    DT_CENTER | DT_VCENTER | DT_SINGLELINE,
+                );
             }
-        }
-    } else if year < ref_year || (year == ref_year && month < ref_month) {
-        // Backward from reference
-        let mut y = ref_year;
-        let mut m = ref_month;
-
-        while y > year || (y == year && m > month) {
-            m -= 1;
-            if m < 0 {
-                m = 11;
-                y -= 1;
+
+            // Days, in their holiday colors
+            for cell in &mini.cells {
+                let cell_rect = gdi_rect(cell.rect);
+                if cell.is_today {
+                    let today_brush = CreateSolidBrush(COLORREF(CAL_TODAY_BG));
+                    let rgn = CreateRoundRectRgn(
+                        cell_rect.left,
+                        cell_rect.top,
+                        cell_rect.right,
+                        cell_rect.bottom,
+                        6,
+                        6,
+                    );
+                    let _ = FillRgn(hdc, rgn, today_brush);
+                    let _ = DeleteObject(rgn.into());
+                    let _ = DeleteObject(today_brush.into());
+                    SetTextColor(hdc, COLORREF(CAL_TODAY_TEXT));
+                } else {
+                    if cell.is_weekend {
+                        fill_round_rect(hdc, &cell_rect, 6, CAL_WEEKEND_BG);
+                    }
+                    SetTextColor(hdc, COLORREF(cell.date_color()));
+                }
+                let mut day_text: Vec<u16> = to_bangla_number(cell.day).encode_utf16().collect();
+                DrawTextW(
+                    hdc,
+                    &mut day_text,
+                    &mut gdi_rect(cell.rect),
+                    DT_CENTER | DT_VCENTER | DT_SINGLELINE,
+                );
             }
-            total_days -= get_bangla_month_days(m, y);
         }
-    }
 
-    (ref_weekday + total_days % 7 + 7) % 7
+        SelectObject(hdc, old_font);
+    }
 }
 
 /// Draw the calendar using cached fonts
 fn draw_calendar(hdc: HDC, rect: &RECT) {
     unsafe {
-        let month = VIEW_MONTH.load(Ordering::Relaxed);
-        let year = VIEW_YEAR.load(Ordering::Relaxed);
+        let layout = view_layout(rect);
+        let (month, year) = (layout.month, layout.year);
         let hover_day = HOVER_DAY.load(Ordering::Relaxed);
-        let current = get_current_bangla_date();
-        let is_current_month = month == current.month && year == current.year;
+        let selected_day = view().day;
 
         // Background
         let bg_brush = CreateSolidBrush(COLORREF(CAL_BG));
@@ -198,19 +610,13 @@ fn draw_calendar(hdc: HDC, rect: &RECT) {
         let _ = DeleteObject(bg_brush.into());
 
         // Header background (green)
-        let header_rect = RECT {
-            left: 0,
-            top: 0,
-            right: rect.right,
-            bottom: CAL_HEADER_HEIGHT,
-        };
+        let header_rect = gdi_rect(layout.header_rect);
         let header_brush = CreateSolidBrush(COLORREF(CAL_HEADER_BG));
         FillRect(hdc, &header_rect, header_brush);
         let _ = DeleteObject(header_brush.into());
 
         // Use cached fonts (no allocation per paint!)
         let header_font = get_cal_header_font();
-        let nav_font = get_cal_nav_font();
         let weekday_font = get_cal_weekday_font();
         let date_font = get_cal_date_font();
         let sub_font = get_cal_sub_font();
@@ -228,12 +634,7 @@ fn draw_calendar(hdc: HDC, rect: &RECT) {
             "?"
         };
         let mut month_text: Vec<u16> = month_name.encode_utf16().collect();
-        let mut month_rect = RECT {
-            left: CAL_PADDING,
-            top: 8,
-            right: rect.right - CAL_PADDING,
-            bottom: 32,
-        };
+        let mut month_rect = title_rect(rect);
         DrawTextW(
             hdc,
             &mut month_text,
@@ -259,68 +660,48 @@ fn draw_calendar(hdc: HDC, rect: &RECT) {
         };
         DrawTextW(hdc, &mut sub_vec, &mut sub_rect, DT_CENTER | DT_SINGLELINE);
 
-        // Navigation bar background
-        let nav_rect = RECT {
-            left: 0,
-            top: CAL_HEADER_HEIGHT,
-            right: rect.right,
-            bottom: CAL_HEADER_HEIGHT + CAL_NAV_HEIGHT,
-        };
-        let nav_brush = CreateSolidBrush(COLORREF(CAL_NAV_BG));
-        FillRect(hdc, &nav_rect, nav_brush);
-        let _ = DeleteObject(nav_brush.into());
-
-        // Navigation arrows
-        SelectObject(hdc, nav_font.into());
-        SetTextColor(hdc, COLORREF(CAL_NAV_TEXT));
-
-        // Previous button (◀ পূর্ববর্তী)
-        let mut prev_text: Vec<u16> = "◀ পূর্ববর্তী".encode_utf16().collect();
-        let mut prev_rect = RECT {
+        // The Gregorian months the Bangla month spans
+        let mut span_vec: Vec<u16> = gregorian_span(layout.first_days, layout.last_days)
+            .encode_utf16()
+            .collect();
+        let mut span_rect = RECT {
             left: CAL_PADDING,
-            top: CAL_HEADER_HEIGHT + 8,
-            right: CAL_WIDTH / 2 - 10,
-            bottom: CAL_HEADER_HEIGHT + CAL_NAV_HEIGHT - 8,
-        };
-        DrawTextW(
-            hdc,
-            &mut prev_text,
-            &mut prev_rect,
-            DT_LEFT | DT_VCENTER | DT_SINGLELINE,
-        );
-
-        // Next button (পরবর্তী ▶)
-        let mut next_text: Vec<u16> = "পরবর্তী ▶".encode_utf16().collect();
-        let mut next_rect = RECT {
-            left: CAL_WIDTH / 2 + 10,
-            top: CAL_HEADER_HEIGHT + 8,
+            top: CAL_HEADER_HEIGHT - 6,
             right: rect.right - CAL_PADDING,
-            bottom: CAL_HEADER_HEIGHT + CAL_NAV_HEIGHT - 8,
+            bottom: header_rect.bottom - 4,
         };
         DrawTextW(
             hdc,
-            &mut next_text,
-            &mut next_rect,
-            DT_RIGHT | DT_VCENTER | DT_SINGLELINE,
+            &mut span_vec,
+            &mut span_rect,
+            DT_CENTER | DT_SINGLELINE,
         );
 
-        // Weekday headers
-        let weekday_y = CAL_HEADER_HEIGHT + CAL_NAV_HEIGHT + 5;
+        let nav_rect = gdi_rect(layout.nav_rect);
+        draw_nav_bar(hdc, &nav_rect);
+
+        // Ramadan timetable link (only in months with Ramadan days)
+        if ramadan_year_in_month(month, year).is_some() {
+            SelectObject(hdc, sub_font.into());
+            SetTextColor(hdc, COLORREF(CAL_HEADER_BG));
+            let mut ramadan_text: Vec<u16> = "☪ রমজান".encode_utf16().collect();
+            let mut ramadan_rect = ramadan_link_rect(&layout);
+            DrawTextW(
+                hdc,
+                &mut ramadan_text,
+                &mut ramadan_rect,
+                DT_CENTER | DT_VCENTER | DT_SINGLELINE,
+            );
+        }
+
+        // Weekday headers, from the first day of the week
         SelectObject(hdc, weekday_font.into());
-        SetTextColor(hdc, COLORREF(CAL_WEEKDAY_TEXT));
-
-        // Short weekday names
-        let weekday_short = ["রবি", "সোম", "মঙ্গল", "বুধ", "বৃহঃ", "শুক্র", "শনি"];
-        let cell_width = (rect.right - CAL_PADDING * 2) / 7;
-
-        for (i, day) in weekday_short.iter().enumerate() {
-            let mut day_text: Vec<u16> = day.encode_utf16().collect();
-            let mut day_rect = RECT {
-                left: CAL_PADDING + (i as i32 * cell_width),
-                top: weekday_y,
-                right: CAL_PADDING + ((i + 1) as i32 * cell_width),
-                bottom: weekday_y + CAL_WEEKDAY_HEIGHT,
-            };
+
+        for column in 0..7 {
+            let weekday = layout.week.weekday(column);
+            SetTextColor(hdc, COLORREF(weekday_name_color(layout.week, weekday)));
+            let mut day_text: Vec<u16> = BANGLA_WEEKDAYS_SHORT[weekday].encode_utf16().collect();
+            let mut day_rect = gdi_rect(layout.weekday_rect(column));
             DrawTextW(
                 hdc,
                 &mut day_text,
@@ -330,7 +711,7 @@ fn draw_calendar(hdc: HDC, rect: &RECT) {
         }
 
         // Draw separator line
-        let sep_y = weekday_y + CAL_WEEKDAY_HEIGHT;
+        let sep_y = layout.separator_y as i32;
         let sep_pen = CreatePen(PS_SOLID, 1, COLORREF(0x00E0E0E0));
         let old_pen = SelectObject(hdc, sep_pen.into());
         let _ = MoveToEx(hdc, CAL_PADDING, sep_y, None);
@@ -338,85 +719,228 @@ fn draw_calendar(hdc: HDC, rect: &RECT) {
         SelectObject(hdc, old_pen);
         let _ = DeleteObject(sep_pen.into());
 
-        // Date grid
-        let grid_y = sep_y + 5;
-        let days_in_month = get_bangla_month_days(month, year);
-        let first_weekday = get_first_day_weekday(month, year);
-
-        SelectObject(hdc, date_font.into());
+        // Date grid; events are personal, so they are looked up here
+        let month_events = events_between(layout.first_days, layout.last_days);
+
+        let label = cell_label();
+        let label_height = LABEL_HEIGHT.load(Ordering::Relaxed);
+
+        for cell in &layout.cells {
+            let cell_rect = gdi_rect(cell.inner_rect());
+            let is_hover = cell.day == hover_day;
+
+            // Draw cell background
+            if cell.is_today {
+                let today_brush = CreateSolidBrush(COLORREF(CAL_TODAY_BG));
+                let rgn = CreateRoundRectRgn(
+                    cell_rect.left,
+                    cell_rect.top,
+                    cell_rect.right,
+                    cell_rect.bottom,
+                    8,
+                    8,
+                );
+                let _ = FillRgn(hdc, rgn, today_brush);
+                let _ = DeleteObject(rgn.into());
+                let _ = DeleteObject(today_brush.into());
+                SetTextColor(hdc, COLORREF(CAL_TODAY_TEXT));
+            } else if is_hover {
+                let hover_brush = CreateSolidBrush(COLORREF(CAL_HOVER_BG));
+                let rgn = CreateRoundRectRgn(
+                    cell_rect.left,
+                    cell_rect.top,
+                    cell_rect.right,
+                    cell_rect.bottom,
+                    8,
+                    8,
+                );
+                let _ = FillRgn(hdc, rgn, hover_brush);
+                let _ = DeleteObject(rgn.into());
+                let _ = DeleteObject(hover_brush.into());
+                SetTextColor(hdc, COLORREF(cell.date_color()));
+            } else {
+                if cell.is_weekend {
+                    fill_round_rect(hdc, &cell_rect, 8, CAL_WEEKEND_BG);
+                }
+                SetTextColor(hdc, COLORREF(cell.date_color()));
+            }
 
-        let mut day = 1;
-        let mut row = 0;
+            // Selected day: a ring around the cell
+            if cell.day == selected_day {
+                let ring_pen = CreatePen(PS_SOLID, 2, COLORREF(CAL_SELECTED_BORDER));
+                let old_pen = SelectObject(hdc, ring_pen.into());
+                let old_brush = SelectObject(hdc, GetStockObject(NULL_BRUSH));
+                let _ = RoundRect(
+                    hdc,
+                    cell_rect.left + 1,
+                    cell_rect.top + 1,
+                    cell_rect.right,
+                    cell_rect.bottom,
+                    8,
+                    8,
+                );
+                SelectObject(hdc, old_brush);
+                SelectObject(hdc, old_pen);
+                let _ = DeleteObject(ring_pen.into());
+            }
 
-        while day <= days_in_month {
-            for col in 0..7 {
-                if row == 0 && col < first_weekday {
-                    continue;
-                }
-                if day > days_in_month {
-                    break;
-                }
+            // Draw day number, above the label line (if any) and the
+            // event dots
+            let mut label_rect = cell_rect;
+            label_rect.bottom = cell_rect.bottom - CAL_EVENT_DOT_SIZE - 2;
+            label_rect.top = label_rect.bottom - label_height;
+            let day_str = to_bangla_number(cell.day);
+            let mut day_vec: Vec<u16> = day_str.encode_utf16().collect();
+            let mut text_rect = cell_rect;
+            if label_height > 0 {
+                text_rect.bottom = label_rect.top;
+            }
+            SelectObject(hdc, date_font.into());
+            DrawTextW(
+                hdc,
+                &mut day_vec,
+                &mut text_rect,
+                DT_CENTER | DT_VCENTER | DT_SINGLELINE,
+            );
 
-                let cell_x = CAL_PADDING + col * cell_width;
-                let cell_y = grid_y + row * CAL_CELL_SIZE;
+            // Gregorian day, Hijri day or tithi
+            if let Some(label_text) = cell.label(label, current_location()) {
+                let mut label_vec: Vec<u16> = label_text.encode_utf16().collect();
+                SelectObject(hdc, sub_font.into());
+                SetTextColor(
+                    hdc,
+                    COLORREF(if cell.is_today {
+                        CAL_TODAY_TEXT
+                    } else {
+                        CAL_WEEKDAY_TEXT
+                    }),
+                );
+                DrawTextW(
+                    hdc,
+                    &mut label_vec,
+                    &mut label_rect,
+                    DT_CENTER | DT_VCENTER | DT_SINGLELINE,
+                );
+            }
 
-                let cell_rect = RECT {
-                    left: cell_x + 2,
-                    top: cell_y + 2,
-                    right: cell_x + cell_width - 2,
-                    bottom: cell_y + CAL_CELL_SIZE - 2,
+            // Full/new moon dot in the top-right corner
+            if let Some(phase) = cell.moon_phase {
+                let fill = match phase {
+                    MoonPhase::Full => CAL_FULL_MOON,
+                    MoonPhase::New => CAL_NEW_MOON,
                 };
+                let moon_brush = CreateSolidBrush(COLORREF(fill));
+                let moon_pen = CreatePen(PS_SOLID, 1, COLORREF(CAL_MOON_OUTLINE));
+                let old_brush = SelectObject(hdc, moon_brush.into());
+                let old_pen = SelectObject(hdc, moon_pen.into());
+                let _ = Ellipse(
+                    hdc,
+                    cell_rect.right - CAL_MOON_SIZE - 3,
+                    cell_rect.top + 3,
+                    cell_rect.right - 3,
+                    cell_rect.top + 3 + CAL_MOON_SIZE,
+                );
+                SelectObject(hdc, old_brush);
+                SelectObject(hdc, old_pen);
+                let _ = DeleteObject(moon_brush.into());
+                let _ = DeleteObject(moon_pen.into());
+            }
 
-                let is_today = is_current_month && day == current.day;
-                let is_hover = day == hover_day;
+            // One dot per personal event (up to three) along the bottom edge
+            let day_events: Vec<&UserEvent> = month_events
+                .iter()
+                .filter(|(d, _)| *d == cell.days)
+                .map(|(_, e)| e)
+                .take(CAL_EVENT_MAX_DOTS)
+                .collect();
+            let dots_width = day_events.len() as i32 * (CAL_EVENT_DOT_SIZE + 2) - 2;
+            let mut dot_x = (cell_rect.left + cell_rect.right - dots_width) / 2;
+            for event in day_events {
+                let color = COLORREF(event.color.colorref());
+                let dot_brush = CreateSolidBrush(color);
+                let dot_pen = CreatePen(PS_SOLID, 1, color);
+                let old_brush = SelectObject(hdc, dot_brush.into());
+                let old_pen = SelectObject(hdc, dot_pen.into());
+                let _ = Ellipse(
+                    hdc,
+                    dot_x,
+                    cell_rect.bottom - CAL_EVENT_DOT_SIZE - 2,
+                    dot_x + CAL_EVENT_DOT_SIZE,
+                    cell_rect.bottom - 2,
+                );
+                SelectObject(hdc, old_brush);
+                SelectObject(hdc, old_pen);
+                let _ = DeleteObject(dot_brush.into());
+                let _ = DeleteObject(dot_pen.into());
+                dot_x += CAL_EVENT_DOT_SIZE + 2;
+            }
 
-                // Draw cell background
-                if is_today {
-                    let today_brush = CreateSolidBrush(COLORREF(CAL_TODAY_BG));
-                    let rgn = CreateRoundRectRgn(
-                        cell_rect.left,
-                        cell_rect.top,
-                        cell_rect.right,
-                        cell_rect.bottom,
-                        8,
-                        8,
-                    );
-                    let _ = FillRgn(hdc, rgn, today_brush);
-                    let _ = DeleteObject(rgn.into());
-                    let _ = DeleteObject(today_brush.into());
-                    SetTextColor(hdc, COLORREF(CAL_TODAY_TEXT));
-                } else if is_hover {
-                    let hover_brush = CreateSolidBrush(COLORREF(CAL_HOVER_BG));
-                    let rgn = CreateRoundRectRgn(
-                        cell_rect.left,
-                        cell_rect.top,
-                        cell_rect.right,
-                        cell_rect.bottom,
-                        8,
-                        8,
-                    );
-                    let _ = FillRgn(hdc, rgn, hover_brush);
-                    let _ = DeleteObject(rgn.into());
-                    let _ = DeleteObject(hover_brush.into());
-                    SetTextColor(hdc, COLORREF(CAL_DATE_TEXT));
+            // Eclipse dot in the top-left corner, hollow when not visible here
+            if let Some(eclipse) = cell.eclipse {
+                let color = if eclipse.kind.is_solar() {
+                    CAL_SOLAR_ECLIPSE
                 } else {
-                    SetTextColor(hdc, COLORREF(CAL_DATE_TEXT));
-                }
+                    CAL_LUNAR_ECLIPSE
+                };
+                let eclipse_brush = if eclipse.visibility == Visibility::NotVisible {
+                    GetStockObject(NULL_BRUSH)
+                } else {
+                    CreateSolidBrush(COLORREF(color)).into()
+                };
+                let eclipse_pen = CreatePen(PS_SOLID, 1, COLORREF(color));
+                let old_brush = SelectObject(hdc, eclipse_brush);
+                let old_pen = SelectObject(hdc, eclipse_pen.into());
+                let _ = Ellipse(
+                    hdc,
+                    cell_rect.left + 3,
+                    cell_rect.top + 3,
+                    cell_rect.left + 3 + CAL_MOON_SIZE,
+                    cell_rect.top + 3 + CAL_MOON_SIZE,
+                );
+                SelectObject(hdc, old_brush);
+                SelectObject(hdc, old_pen);
+                let _ = DeleteObject(eclipse_brush);
+                let _ = DeleteObject(eclipse_pen.into());
+            }
+        }
 
-                // Draw day number
-                let day_str = to_bangla_number(day);
-                let mut day_vec: Vec<u16> = day_str.encode_utf16().collect();
-                let mut text_rect = cell_rect;
+        // Detail panel for the selected day, under a separator line
+        let mut panel_rect = gdi_rect(layout.footer_rect(rect.bottom as f32));
+        let sep_pen = CreatePen(PS_SOLID, 1, COLORREF(0x00E0E0E0));
+        let old_pen = SelectObject(hdc, sep_pen.into());
+        let _ = MoveToEx(hdc, panel_rect.left, panel_rect.top, None);
+        let _ = LineTo(hdc, panel_rect.right, panel_rect.top);
+        SelectObject(hdc, old_pen);
+        let _ = DeleteObject(sep_pen.into());
+
+        if let Some(cell) = layout.cell(selected_day) {
+            let line_height = DETAIL_LINE_HEIGHT.load(Ordering::Relaxed);
+            SelectObject(hdc, sub_font.into());
+            panel_rect.top += 6;
+            for (i, line) in detail_lines(&layout, cell, &month_events)
+                .iter()
+                .enumerate()
+            {
+                // The holidays line in the day's holiday color
+                let color = if i == 2 && cell.holiday.is_some() {
+                    cell.date_color()
+                } else {
+                    CAL_FOOTER_TEXT
+                };
+                SetTextColor(hdc, COLORREF(color));
+                let mut line_vec: Vec<u16> = line.encode_utf16().collect();
+                let mut line_rect = RECT {
+                    bottom: panel_rect.top + line_height,
+                    ..panel_rect
+                };
                 DrawTextW(
                     hdc,
-                    &mut day_vec,
-                    &mut text_rect,
-                    DT_CENTER | DT_VCENTER | DT_SINGLELINE,
+                    &mut line_vec,
+                    &mut line_rect,
+                    DT_CENTER | DT_VCENTER | DT_SINGLELINE | DT_END_ELLIPSIS,
                 );
-
-                day += 1;
+                panel_rect.top += line_height;
             }
-            row += 1;
         }
 
         // Restore original font - do NOT delete cached fonts
@@ -424,67 +948,137 @@ fn draw_calendar(hdc: HDC, rect: &RECT) {
     }
 }
 
-/// Get the day number at a mouse position
-fn get_day_at_point(x: i32, y: i32, rect: &RECT) -> i32 {
-    let month = VIEW_MONTH.load(Ordering::Relaxed);
-    let year = VIEW_YEAR.load(Ordering::Relaxed);
+/// The detail panel's lines for a day: the Bangla date, weekday and ritu;
+/// the Gregorian and Hijri dates; the day's holidays, eclipses and events
+fn detail_lines(
+    layout: &MonthLayout,
+    cell: &DayCell,
+    month_events: &[(i64, UserEvent)],
+) -> [String; 3] {
+    let date = bangla_date_from_days(cell.days);
+    let (g_year, g_month, g_day) = cell.gregorian;
+    let (h_year, h_month, h_day) = gregorian_to_hijri(g_year, g_month, g_day);
+    let names: Vec<&str> = layout
+        .holidays_on(cell.days)
+        .map(|h| h.name.as_str())
+        .chain(
+            layout
+                .eclipses
+                .iter()
+                .filter(|(d, _)| *d == cell.days)
+                .map(|(_, e)| e.kind.get_name()),
+        )
+        .chain(
+            month_events
+                .iter()
+                .filter(|(d, _)| *d == cell.days)
+                .map(|(_, e)| e.title.as_str()),
+        )
+        .collect();
+    [
+        format!(
+            "{} {} {}, {}",
+            date.get_ordinal(),
+            date.get_month_name(),
+            date.format_line2(),
+            date.format_line3()
+        ),
+        format!(
+            "{} {} {} • {} {} {} হিজরি",
+            to_bangla_number(g_day),
+            GREGORIAN_MONTHS[(g_month - 1) as usize],
+            to_bangla_number(g_year),
+            to_bangla_number(h_day),
+            HIJRI_MONTHS[h_month as usize],
+            to_bangla_number(h_year)
+        ),
+        names.join(", "),
+    ]
+}
 
-    let weekday_y = CAL_HEADER_HEIGHT + CAL_NAV_HEIGHT + 5;
-    let sep_y = weekday_y + CAL_WEEKDAY_HEIGHT;
-    let grid_y = sep_y + 5;
+/// Lay the month out again on the next hit-test
+fn forget_hit_layout() {
+    *HIT_LAYOUT.lock().unwrap() = None;
+}
 
-    if y < grid_y {
+/// Get the day number at a mouse position (none in the year view), from the
+/// layout kept for hit-testing while the month and window size stay the same
+fn get_day_at_point(x: i32, y: i32, rect: &RECT) -> i32 {
+    let view = view();
+    if view.year_view {
         return -1;
     }
-
-    let cell_width = (rect.right - CAL_PADDING * 2) / 7;
-    let col = (x - CAL_PADDING) / cell_width;
-    let row = (y - grid_y) / CAL_CELL_SIZE;
-
-    if !(0..7).contains(&col) || row < 0 {
-        return -1;
+    let key = (view.year, view.month, rect.right, rect.bottom);
+    let mut hit_layout = HIT_LAYOUT.lock().unwrap();
+    if hit_layout.as_ref().is_none_or(|(cached, _)| *cached != key) {
+        *hit_layout = Some((key, view_layout(rect)));
     }
+    hit_layout.as_ref().map_or(-1, |(_, layout)| {
+        layout
+            .day_at(x as f32, y as f32)
+            .map_or(-1, |cell| cell.day)
+    })
+}
 
-    let first_weekday = get_first_day_weekday(month, year);
-    let cell_index = row * 7 + col;
-    let day = cell_index - first_weekday + 1;
+/// Hijri year whose Ramadan overlaps a Bangla month, if any
+fn ramadan_year_in_month(month: i32, year: i32) -> Option<i32> {
+    let (first_y, first_m, first_d) = gregorian_from_bangla(year, month, 1);
+    let first_days = days_from_civil(first_y, first_m, first_d);
+    (0..get_bangla_month_days(month, year) as i64).find_map(|offset| {
+        let (y, m, d) = civil_from_days(first_days + offset);
+        let (hijri_year, hijri_month, _) = gregorian_to_hijri(y, m, d);
+        (hijri_month == RAMADAN).then_some(hijri_year)
+    })
+}
 
-    let days_in_month = get_bangla_month_days(month, year);
-    if day >= 1 && day <= days_in_month {
-        day
-    } else {
-        -1
+/// Clickable area of the Ramadan link, between the navigation buttons
+fn ramadan_link_rect(layout: &MonthLayout) -> RECT {
+    let nav_rect = gdi_rect(layout.nav_rect);
+    let center = nav_rect.right / 2;
+    RECT {
+        left: center - 40,
+        top: nav_rect.top + 8,
+        right: center + 40,
+        bottom: nav_rect.bottom - 8,
     }
 }
 
-/// Navigate to previous month
-fn go_prev_month() {
-    let mut month = VIEW_MONTH.load(Ordering::Relaxed);
-    let mut year = VIEW_YEAR.load(Ordering::Relaxed);
-
-    month -= 1;
-    if month < 0 {
-        month = 11;
-        year -= 1;
+/// Open the sehri/iftar timetable if the viewed month has Ramadan days
+fn open_ramadan_timetable(hwnd: HWND) {
+    let view = view();
+    if let Some(hijri_year) = ramadan_year_in_month(view.month, view.year) {
+        show_ramadan_timetable(hwnd, hijri_year);
     }
-
-    VIEW_MONTH.store(month, Ordering::Relaxed);
-    VIEW_YEAR.store(year, Ordering::Relaxed);
 }
 
-/// Navigate to next month
-fn go_next_month() {
-    let mut month = VIEW_MONTH.load(Ordering::Relaxed);
-    let mut year = VIEW_YEAR.load(Ordering::Relaxed);
-
-    month += 1;
-    if month > 11 {
-        month = 0;
-        year += 1;
+/// Right-click on a date cell: add a personal event, or edit one of the day's
+fn open_event_menu(hwnd: HWND, day: i32) {
+    let ViewState { year, month, .. } = view();
+    let (g_year, g_month, g_day) = gregorian_from_bangla(year, month, day);
+    let days = days_from_civil(g_year, g_month, g_day);
+    let day_events: Vec<UserEvent> = events_between(days, days)
+        .into_iter()
+        .map(|(_, event)| event)
+        .collect();
+    let titles: Vec<String> = day_events.iter().map(|e| e.title.clone()).collect();
+
+    match show_event_menu(hwnd, &titles) {
+        IDM_EVENT_ADD => {
+            let event = UserEvent {
+                year,
+                month: month + 1,
+                day,
+                ..Default::default()
+            };
+            show_event_editor(hwnd, &event);
+        }
+        command if command >= IDM_EVENT_EDIT_BASE => {
+            if let Some(event) = day_events.get((command - IDM_EVENT_EDIT_BASE) as usize) {
+                show_event_editor(hwnd, event);
+            }
+        }
+        _ => {}
     }
-
-    VIEW_MONTH.store(month, Ordering::Relaxed);
-    VIEW_YEAR.store(year, Ordering::Relaxed);
 }
 
 /// Calendar window procedure
@@ -508,7 +1102,11 @@ extern "system" fn calendar_wndproc(
                 let mem_bitmap = CreateCompatibleBitmap(hdc, rect.right, rect.bottom);
                 let old_bitmap = SelectObject(mem_dc, mem_bitmap.into());
 
-                draw_calendar(mem_dc, &rect);
+                if view().year_view {
+                    draw_year(mem_dc, &rect);
+                } else {
+                    draw_calendar(mem_dc, &rect);
+                }
 
                 let _ = BitBlt(
                     hdc,
@@ -530,29 +1128,88 @@ extern "system" fn calendar_wndproc(
                 LRESULT(0)
             }
 
-            WM_LBUTTONDOWN => {
+            // A double click is a second click, on a day it opens the day
+            WM_LBUTTONDOWN | WM_LBUTTONDBLCLK => {
                 let x = (lparam.0 & 0xFFFF) as i16 as i32;
                 let y = ((lparam.0 >> 16) & 0xFFFF) as i16 as i32;
 
+                let mut rect = RECT::default();
+                let _ = GetClientRect(hwnd, &mut rect);
+                let title = title_rect(&rect);
+                let on_title =
+                    x >= title.left && x < title.right && y >= title.top && y < title.bottom;
+
+                if view().year_view {
+                    // Year view: the title goes back to the month, a small
+                    // month opens it, the navigation bar changes the year
+                    let layout = view_year_layout();
+                    let nav_rect = gdi_rect(layout.nav_rect);
+                    if on_title {
+                        update_view(hwnd, |view| view.year_view = false);
+                    } else if y >= nav_rect.top && y < nav_rect.bottom {
+                        let delta = if x < nav_rect.right / 2 { -1 } else { 1 };
+                        update_view(hwnd, |view| view.step(delta));
+                    } else if let Some(mini) = layout.month_at(x as f32, y as f32) {
+                        update_view(hwnd, |view| {
+                            view.add_months(mini.month - view.month);
+                            view.year_view = false;
+                        });
+                    }
+                    return LRESULT(0);
+                }
+                if on_title {
+                    update_view(hwnd, |view| view.year_view = true);
+                    return LRESULT(0);
+                }
+                let layout = view_layout(&rect);
+
                 // Check navigation clicks
-                let nav_top = CAL_HEADER_HEIGHT;
-                let nav_bottom = CAL_HEADER_HEIGHT + CAL_NAV_HEIGHT;
-
-                if y >= nav_top && y < nav_bottom {
-                    if x < CAL_WIDTH / 2 {
-                        // Previous
-                        go_prev_month();
-                        let _ = InvalidateRect(Some(hwnd), None, true);
+                let nav_rect = gdi_rect(layout.nav_rect);
+                let on_nav = y >= nav_rect.top && y < nav_rect.bottom;
+
+                let ramadan_rect = ramadan_link_rect(&layout);
+                let on_ramadan_link = x >= ramadan_rect.left
+                    && x < ramadan_rect.right
+                    && ramadan_year_in_month(layout.month, layout.year).is_some();
+
+                if on_nav && on_ramadan_link {
+                    open_ramadan_timetable(hwnd);
+                } else if on_nav {
+                    // Previous or next
+                    let delta = if x < nav_rect.right / 2 { -1 } else { 1 };
+                    update_view(hwnd, |view| view.step(delta));
+                } else if let Some(cell) = layout.day_at(x as f32, y as f32) {
+                    // Date cell: select it; twice, open the day's details
+                    if message == WM_LBUTTONDBLCLK {
+                        show_day_view(hwnd, cell.days);
                     } else {
-                        // Next
-                        go_next_month();
-                        let _ = InvalidateRect(Some(hwnd), None, true);
+                        let day = cell.day;
+                        update_view(hwnd, |view| view.day = day);
                     }
                 }
 
                 LRESULT(0)
             }
 
+            WM_RBUTTONUP => {
+                let x = (lparam.0 & 0xFFFF) as i16 as i32;
+                let y = ((lparam.0 >> 16) & 0xFFFF) as i16 as i32;
+
+                let mut rect = RECT::default();
+                let _ = GetClientRect(hwnd, &mut rect);
+                let day = get_day_at_point(x, y, &rect);
+                if day > 0 {
+                    update_view(hwnd, |view| view.day = day);
+                    open_event_menu(hwnd, day);
+                }
+                LRESULT(0)
+            }
+
+            // Owner-drawn event menu
+            WM_MEASUREITEM => handle_measure_item(hwnd, lparam),
+
+            WM_DRAWITEM => handle_draw_item(lparam),
+
             WM_MOUSEMOVE => {
                 let x = (lparam.0 & 0xFFFF) as i16 as i32;
                 let y = ((lparam.0 >> 16) & 0xFFFF) as i16 as i32;
@@ -569,21 +1226,84 @@ extern "system" fn calendar_wndproc(
                 LRESULT(0)
             }
 
+            // One month (or year) per notch, however finely the wheel reports
+            WM_MOUSEWHEEL => {
+                let delta = ((wparam.0 >> 16) & 0xFFFF) as i16 as i32;
+                let sum = WHEEL_DELTA_SUM.fetch_add(delta, Ordering::Relaxed) + delta;
+                let notches = sum / WHEEL_DELTA as i32;
+                if notches != 0 {
+                    WHEEL_DELTA_SUM.fetch_sub(notches * WHEEL_DELTA as i32, Ordering::Relaxed);
+                    // Rolling away from you goes back
+                    update_view(hwnd, |view| view.step(-notches));
+                }
+                LRESULT(0)
+            }
+
             WM_KEYDOWN => {
+                let ctrl = GetKeyState(VK_CONTROL.0 as i32) < 0;
                 match wparam.0 as i32 {
                     0x1B => {
-                        // ESC
-                        let _ = DestroyWindow(hwnd);
+                        // ESC - back to the month from the year view, else close
+                        if view().year_view {
+                            update_view(hwnd, |view| view.year_view = false);
+                        } else {
+                            let _ = DestroyWindow(hwnd);
+                        }
+                    }
+                    0x21 => {
+                        // Page Up - previous month
+                        update_view(hwnd, |view| view.step(-1));
+                    }
+                    0x22 => {
+                        // Page Down - next month
+                        update_view(hwnd, |view| view.step(1));
+                    }
+                    key @ (0x25 | 0x27) => {
+                        // Left/Right arrow - previous/next day, with Ctrl (or
+                        // in the year view) month
+                        let delta = if key == 0x25 { -1 } else { 1 };
+                        update_view(hwnd, |view| {
+                            if ctrl || view.year_view {
+                                view.step(delta);
+                            } else {
+                                view.move_selection(delta as i64);
+                            }
+                        });
+                    }
+                    key @ (0x26 | 0x28) => {
+                        // Up/Down arrow - previous/next week, with Ctrl (or in
+                        // the year view) year
+                        let delta = if key == 0x26 { -1 } else { 1 };
+                        update_view(hwnd, |view| {
+                            if ctrl || view.year_view {
+                                view.add_months(delta * 12);
+                            } else {
+                                view.move_selection(delta as i64 * 7);
+                            }
+                        });
+                    }
+                    0x0D => {
+                        // Enter - the selected day's details
+                        let view = view();
+                        if !view.year_view {
+                            show_day_view(hwnd, view.selected_days());
+                        }
+                    }
+                    0x24 | 0x54 => {
+                        // Home, T - today
+                        update_view(hwnd, ViewState::show_today);
+                    }
+                    0x47 => {
+                        // G - go to a date
+                        show_date_prompt(hwnd);
                     }
-                    0x25 => {
-                        // Left arrow
-                        go_prev_month();
-                        let _ = InvalidateRect(Some(hwnd), None, true);
+                    0x59 => {
+                        // Y - year view
+                        update_view(hwnd, |view| view.year_view = !view.year_view);
                     }
-                    0x27 => {
-                        // Right arrow
-                        go_next_month();
-                        let _ = InvalidateRect(Some(hwnd), None, true);
+                    0x52 => {
+                        // R - Ramadan timetable
+                        open_ramadan_timetable(hwnd);
                     }
                     _ => {}
                 }
@@ -593,6 +1313,7 @@ extern "system" fn calendar_wndproc(
             WM_DESTROY => {
                 set_calendar_hwnd(HWND(std::ptr::null_mut()));
                 HOVER_DAY.store(-1, Ordering::Relaxed);
+                WHEEL_DELTA_SUM.store(0, Ordering::Relaxed);
                 LRESULT(0)
             }
 
@@ -0,0 +1,360 @@
+// ramadan.rs - Ramadan sehri/iftar timetable popup, opened from the punjika
+
+use crate::astronomy::jd_now;
+use crate::calendar::*;
+use crate::constants::*;
+use crate::fonts::{
+    get_cal_date_font, get_cal_header_font, get_cal_sub_font, get_cal_weekday_font,
+};
+use crate::get_flag_icon;
+use crate::hijri::ramadan_days;
+use crate::locations::current_location;
+use crate::panchang::local_day_minute;
+use crate::prayer::{FAJR, MAGHRIB, current_prayer_times};
+use std::sync::atomic::{AtomicI32, AtomicPtr, Ordering};
+use windows::{
+    Win32::Foundation::*, Win32::Graphics::Dwm::*, Win32::Graphics::Gdi::*,
+    Win32::System::LibraryLoader::GetModuleHandleW, Win32::UI::WindowsAndMessaging::*, core::*,
+};
+
+// The Hijri year being shown
+static VIEW_HIJRI_YEAR: AtomicI32 = AtomicI32::new(0);
+static RAMADAN_HWND_PTR: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(std::ptr::null_mut());
+
+// Window dimensions: two tables side by side (রোজা ১-১৫, ১৬-৩০)
+const RAMADAN_WIDTH: i32 = 580;
+const RAMADAN_HEIGHT: i32 = 500;
+const RAMADAN_HEADER_HEIGHT: i32 = 60;
+const RAMADAN_ROW_HEIGHT: i32 = 22;
+const RAMADAN_PADDING: i32 = 16;
+const RAMADAN_ROWS_PER_TABLE: i32 = 15;
+const RAMADAN_FOOTER_HEIGHT: i32 = 26;
+
+// Columns: রোজা, তারিখ, সেহরি, ইফতার (widths)
+const RAMADAN_COLUMNS: &[(&str, i32)] = &[("রোজা", 40), ("তারিখ", 110), ("সেহরি", 55), ("ইফতার", 55)];
+
+// Colors
+const RAMADAN_BG: u32 = 0x00FFFFFF;
+const RAMADAN_HEADER_BG: u32 = 0x00006B3C; // Bangladesh green
+const RAMADAN_HEADER_TEXT: u32 = 0x00FFFFFF;
+const RAMADAN_COLUMN_TEXT: u32 = 0x00666666;
+const RAMADAN_ROW_TEXT: u32 = 0x00333333;
+const RAMADAN_STRIPE_BG: u32 = 0x00F7F7F7;
+const RAMADAN_TODAY_BG: u32 = 0x00E8F5E9;
+const RAMADAN_FOOTER_TEXT: u32 = 0x00888888;
+
+#[inline]
+fn get_ramadan_hwnd() -> HWND {
+    HWND(RAMADAN_HWND_PTR.load(Ordering::Relaxed))
+}
+
+#[inline]
+fn set_ramadan_hwnd(hwnd: HWND) {
+    RAMADAN_HWND_PTR.store(hwnd.0, Ordering::Relaxed);
+}
+
+/// Show the sehri/iftar timetable for Ramadan of a Hijri year
+pub fn show_ramadan_timetable(parent: HWND, hijri_year: i32) {
+    unsafe {
+        VIEW_HIJRI_YEAR.store(hijri_year, Ordering::Relaxed);
+
+        // If already open, just show the new year
+        let ramadan_hwnd = get_ramadan_hwnd();
+        if !ramadan_hwnd.is_invalid() && IsWindow(Some(ramadan_hwnd)).as_bool() {
+            let _ = InvalidateRect(Some(ramadan_hwnd), None, true);
+            let _ = SetForegroundWindow(ramadan_hwnd);
+            return;
+        }
+
+        let instance = GetModuleHandleW(None).unwrap_or_default();
+        let class_name = w!("BongoRamadanClass");
+
+        let wc = WNDCLASSEXW {
+            cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+            style: CS_HREDRAW | CS_VREDRAW,
+            lpfnWndProc: Some(ramadan_wndproc),
+            hInstance: instance.into(),
+            hCursor: LoadCursorW(None, IDC_ARROW).unwrap_or_default(),
+            hbrBackground: HBRUSH(std::ptr::null_mut()),
+            lpszClassName: class_name,
+            hIcon: get_flag_icon(),
+            hIconSm: get_flag_icon(),
+            ..Default::default()
+        };
+        RegisterClassExW(&wc);
+
+        // Center on screen
+        let screen_width = GetSystemMetrics(SM_CXSCREEN);
+        let screen_height = GetSystemMetrics(SM_CYSCREEN);
+        let x = (screen_width - RAMADAN_WIDTH) / 2;
+        let y = (screen_height - RAMADAN_HEIGHT) / 2;
+
+        let hwnd = CreateWindowExW(
+            WS_EX_TOPMOST | WS_EX_DLGMODALFRAME,
+            class_name,
+            w!("সেহরি ও ইফতারের সময়সূচি"),
+            WS_POPUP | WS_VISIBLE | WS_CAPTION | WS_SYSMENU,
+            x,
+            y,
+            RAMADAN_WIDTH,
+            RAMADAN_HEIGHT,
+            Some(parent),
+            None,
+            Some(instance.into()),
+            None,
+        )
+        .unwrap_or_default();
+
+        set_ramadan_hwnd(hwnd);
+
+        // Set rounded corners on Windows 11
+        let preference = DWM_WINDOW_CORNER_PREFERENCE(2); // DWMWCP_ROUND
+        let _ = DwmSetWindowAttribute(
+            hwnd,
+            DWMWA_WINDOW_CORNER_PREFERENCE,
+            &preference as *const _ as *const std::ffi::c_void,
+            std::mem::size_of::<DWM_WINDOW_CORNER_PREFERENCE>() as u32,
+        );
+
+        let _ = ShowWindow(hwnd, SW_SHOW);
+        let _ = UpdateWindow(hwnd);
+    }
+}
+
+/// Draw a single line of text in a rectangle
+fn draw_text(hdc: HDC, text: &str, rect: RECT, format: DRAW_TEXT_FORMAT) {
+    unsafe {
+        let mut text_vec: Vec<u16> = text.encode_utf16().collect();
+        let mut text_rect = rect;
+        DrawTextW(
+            hdc,
+            &mut text_vec,
+            &mut text_rect,
+            format | DT_SINGLELINE | DT_VCENTER | DT_END_ELLIPSIS,
+        );
+    }
+}
+
+/// Fill a rectangle with a solid color
+fn fill_rect(hdc: HDC, rect: &RECT, color: u32) {
+    unsafe {
+        let brush = CreateSolidBrush(COLORREF(color));
+        FillRect(hdc, rect, brush);
+        let _ = DeleteObject(brush.into());
+    }
+}
+
+/// Draw the timetable using cached fonts
+fn draw_timetable(hdc: HDC, rect: &RECT) {
+    unsafe {
+        let hijri_year = VIEW_HIJRI_YEAR.load(Ordering::Relaxed);
+        let (first_day, last_day) = ramadan_days(hijri_year);
+        let location = current_location();
+        let offset = location.utc_offset_minutes;
+        let (today, _) = local_day_minute(jd_now(), offset);
+
+        fill_rect(hdc, rect, RAMADAN_BG);
+
+        // Header: title, then place
+        let header_rect = RECT {
+            left: 0,
+            top: 0,
+            right: rect.right,
+            bottom: RAMADAN_HEADER_HEIGHT,
+        };
+        fill_rect(hdc, &header_rect, RAMADAN_HEADER_BG);
+
+        SetBkMode(hdc, TRANSPARENT);
+        SetTextColor(hdc, COLORREF(RAMADAN_HEADER_TEXT));
+        let old_font = SelectObject(hdc, get_cal_header_font().into());
+        draw_text(
+            hdc,
+            &format!("রমজান {} হিজরি", to_bangla_number(hijri_year)),
+            RECT {
+                left: RAMADAN_PADDING,
+                top: 6,
+                right: rect.right - RAMADAN_PADDING,
+                bottom: 32,
+            },
+            DT_CENTER,
+        );
+        SelectObject(hdc, get_cal_sub_font().into());
+        draw_text(
+            hdc,
+            &format!("সেহরি ও ইফতারের সময় • {}", location.name),
+            RECT {
+                left: RAMADAN_PADDING,
+                top: 34,
+                right: rect.right - RAMADAN_PADDING,
+                bottom: RAMADAN_HEADER_HEIGHT - 4,
+            },
+            DT_CENTER,
+        );
+
+        // Two tables side by side
+        let table_width: i32 = RAMADAN_COLUMNS.iter().map(|(_, w)| w).sum();
+        let gap = rect.right - RAMADAN_PADDING * 2 - table_width * 2;
+        let table_top = RAMADAN_HEADER_HEIGHT + 8;
+
+        for table in 0..2 {
+            let table_left = RAMADAN_PADDING + table * (table_width + gap);
+
+            // Column headers
+            SelectObject(hdc, get_cal_weekday_font().into());
+            SetTextColor(hdc, COLORREF(RAMADAN_COLUMN_TEXT));
+            let mut left = table_left;
+            for (title, width) in RAMADAN_COLUMNS {
+                draw_text(
+                    hdc,
+                    title,
+                    RECT {
+                        left,
+                        top: table_top,
+                        right: left + width,
+                        bottom: table_top + RAMADAN_ROW_HEIGHT,
+                    },
+                    DT_CENTER,
+                );
+                left += width;
+            }
+
+            // One row per fast
+            SelectObject(hdc, get_cal_date_font().into());
+            SetTextColor(hdc, COLORREF(RAMADAN_ROW_TEXT));
+            for row in 0..RAMADAN_ROWS_PER_TABLE {
+                let fast = table * RAMADAN_ROWS_PER_TABLE + row + 1;
+                let day = first_day + fast as i64 - 1;
+                if day > last_day {
+                    break;
+                }
+
+                let top = table_top + (row + 1) * RAMADAN_ROW_HEIGHT;
+                let row_rect = RECT {
+                    left: table_left,
+                    top,
+                    right: table_left + table_width,
+                    bottom: top + RAMADAN_ROW_HEIGHT,
+                };
+                if day == today {
+                    fill_rect(hdc, &row_rect, RAMADAN_TODAY_BG);
+                } else if row % 2 == 1 {
+                    fill_rect(hdc, &row_rect, RAMADAN_STRIPE_BG);
+                }
+
+                // Sehri ends at Fajr, iftar is at Maghrib
+                let times = current_prayer_times(day);
+                let clock = |jd: Option<f64>| {
+                    jd.map(|jd| format_clock_time(local_day_minute(jd, offset).1))
+                        .unwrap_or_else(|| "—".to_string())
+                };
+                let (_, month, month_day) = civil_from_days(day);
+                let weekday = (day + 4).rem_euclid(7) as usize; // 1970-01-01 was a Thursday
+                let cells = [
+                    to_bangla_number(fast),
+                    format!(
+                        "{} {}, {}",
+                        to_bangla_number(month_day),
+                        GREGORIAN_MONTHS[(month - 1) as usize],
+                        BANGLA_WEEKDAYS_SHORT[weekday]
+                    ),
+                    clock(times[FAJR]),
+                    clock(times[MAGHRIB]),
+                ];
+
+                let mut left = table_left;
+                for (text, (_, width)) in cells.iter().zip(RAMADAN_COLUMNS) {
+                    draw_text(
+                        hdc,
+                        text,
+                        RECT {
+                            left,
+                            top,
+                            right: left + width,
+                            bottom: top + RAMADAN_ROW_HEIGHT,
+                        },
+                        DT_CENTER,
+                    );
+                    left += width;
+                }
+            }
+        }
+
+        // Footer: the start depends on the moon sighting
+        SelectObject(hdc, get_cal_sub_font().into());
+        SetTextColor(hdc, COLORREF(RAMADAN_FOOTER_TEXT));
+        draw_text(
+            hdc,
+            "চাঁদ দেখার উপর নির্ভর করে তারিখ এক দিন এগিয়ে-পিছিয়ে যেতে পারে",
+            RECT {
+                left: RAMADAN_PADDING,
+                top: rect.bottom - RAMADAN_FOOTER_HEIGHT,
+                right: rect.right - RAMADAN_PADDING,
+                bottom: rect.bottom - 4,
+            },
+            DT_CENTER,
+        );
+
+        // Restore original font - do NOT delete cached fonts
+        SelectObject(hdc, old_font);
+    }
+}
+
+extern "system" fn ramadan_wndproc(
+    hwnd: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    unsafe {
+        match message {
+            WM_PAINT => {
+                let mut ps = PAINTSTRUCT::default();
+                let hdc = BeginPaint(hwnd, &mut ps);
+
+                let mut rect = RECT::default();
+                let _ = GetClientRect(hwnd, &mut rect);
+
+                // Double buffer
+                let mem_dc = CreateCompatibleDC(Some(hdc));
+                let mem_bitmap = CreateCompatibleBitmap(hdc, rect.right, rect.bottom);
+                let old_bitmap = SelectObject(mem_dc, mem_bitmap.into());
+
+                draw_timetable(mem_dc, &rect);
+
+                let _ = BitBlt(
+                    hdc,
+                    0,
+                    0,
+                    rect.right,
+                    rect.bottom,
+                    Some(mem_dc),
+                    0,
+                    0,
+                    SRCCOPY,
+                );
+
+                SelectObject(mem_dc, old_bitmap);
+                let _ = DeleteObject(mem_bitmap.into());
+                let _ = DeleteDC(mem_dc);
+
+                let _ = EndPaint(hwnd, &ps);
+                LRESULT(0)
+            }
+
+            WM_KEYDOWN => {
+                if wparam.0 as i32 == 0x1B {
+                    // ESC
+                    let _ = DestroyWindow(hwnd);
+                }
+                LRESULT(0)
+            }
+
+            WM_DESTROY => {
+                set_ramadan_hwnd(HWND(std::ptr::null_mut()));
+                LRESULT(0)
+            }
+
+            _ => DefWindowProcW(hwnd, message, wparam, lparam),
+        }
+    }
+}
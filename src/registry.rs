@@ -2,6 +2,7 @@
 
 use crate::AUTOSTART_ENABLED;
 use crate::constants::*;
+use crate::locations::{LOCATIONS, find_location};
 use std::sync::atomic::Ordering;
 use windows::{Win32::System::Registry::*, core::*};
 
@@ -258,3 +259,226 @@ pub unsafe fn mark_has_run() {
         }
     }
 }
+
+/// Read a DWORD value from the app's settings key
+unsafe fn read_app_dword(name: &str) -> Option<u32> {
+    let key_path = w!("Software\\BanglaCalendar");
+    let mut hkey = HKEY::default();
+
+    if unsafe { RegOpenKeyExW(HKEY_CURRENT_USER, key_path, Some(0), KEY_READ, &mut hkey) }.is_ok() {
+        let mut data: u32 = 0;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        let value_name = HSTRING::from(name);
+
+        let result = unsafe {
+            RegQueryValueExW(
+                hkey,
+                &value_name,
+                None,
+                None,
+                Some(&mut data as *mut u32 as *mut u8),
+                Some(&mut size),
+            )
+        };
+        unsafe {
+            let _ = RegCloseKey(hkey);
+        }
+        result.is_ok().then_some(data)
+    } else {
+        None
+    }
+}
+
+/// Write a DWORD value to the app's settings key
+unsafe fn write_app_dword(name: &str, data: u32) {
+    let key_path = w!("Software\\BanglaCalendar");
+    let mut hkey = HKEY::default();
+
+    if unsafe { RegCreateKeyW(HKEY_CURRENT_USER, key_path, &mut hkey) }.is_ok() {
+        let value_name = HSTRING::from(name);
+        unsafe {
+            let _ = RegSetValueExW(
+                hkey,
+                &value_name,
+                Some(0),
+                REG_DWORD,
+                Some(std::slice::from_raw_parts(
+                    &data as *const u32 as *const u8,
+                    std::mem::size_of::<u32>(),
+                )),
+            );
+            let _ = RegCloseKey(hkey);
+        }
+    }
+}
+
+/// Optional widget lines (WIDGET_LINE_* flags)
+pub unsafe fn load_widget_lines() -> u32 {
+    unsafe { read_app_dword(WIDGET_LINES_KEY) }.unwrap_or(0)
+}
+
+pub unsafe fn save_widget_lines(lines: u32) {
+    unsafe { write_app_dword(WIDGET_LINES_KEY, lines) }
+}
+
+/// Read a string value from the app's settings key
+unsafe fn read_app_string(name: &str) -> Option<String> {
+    let key_path = w!("Software\\BanglaCalendar");
+    let mut hkey = HKEY::default();
+
+    if unsafe { RegOpenKeyExW(HKEY_CURRENT_USER, key_path, Some(0), KEY_READ, &mut hkey) }.is_ok() {
+        let mut data = [0u16; 256];
+        let mut size = std::mem::size_of_val(&data) as u32;
+        let value_name = HSTRING::from(name);
+
+        let result = unsafe {
+            RegQueryValueExW(
+                hkey,
+                &value_name,
+                None,
+                None,
+                Some(data.as_mut_ptr() as *mut u8),
+                Some(&mut size),
+            )
+        };
+        unsafe {
+            let _ = RegCloseKey(hkey);
+        }
+        if result.is_ok() {
+            let len = (size as usize / 2).min(data.len());
+            let text = String::from_utf16_lossy(&data[..len]);
+            return Some(text.trim_end_matches('\0').to_string());
+        }
+    }
+    None
+}
+
+/// Write a string value to the app's settings key
+unsafe fn write_app_string(name: &str, value: &str) {
+    let key_path = w!("Software\\BanglaCalendar");
+    let mut hkey = HKEY::default();
+
+    if unsafe { RegCreateKeyW(HKEY_CURRENT_USER, key_path, &mut hkey) }.is_ok() {
+        let value_name = HSTRING::from(name);
+        let value_wide: Vec<u16> = value.encode_utf16().chain(std::iter::once(0)).collect();
+        unsafe {
+            let _ = RegSetValueExW(
+                hkey,
+                &value_name,
+                Some(0),
+                REG_SZ,
+                Some(std::slice::from_raw_parts(
+                    value_wide.as_ptr() as *const u8,
+                    value_wide.len() * 2,
+                )),
+            );
+            let _ = RegCloseKey(hkey);
+        }
+    }
+}
+
+/// Chosen location (index into LOCATIONS, stored by id)
+pub unsafe fn load_location() -> u32 {
+    unsafe { read_app_string(LOCATION_KEY) }
+        .and_then(|id| find_location(&id))
+        .unwrap_or(0) as u32
+}
+
+pub unsafe fn save_location(index: u32) {
+    if let Some(location) = LOCATIONS.get(index as usize) {
+        unsafe { write_app_string(LOCATION_KEY, location.id) }
+    }
+}
+
+/// Prayer calculation method (index into PRAYER_METHODS)
+pub unsafe fn load_prayer_method() -> u32 {
+    unsafe { read_app_dword(PRAYER_METHOD_KEY) }.unwrap_or(0)
+}
+
+pub unsafe fn save_prayer_method(method: u32) {
+    unsafe { write_app_dword(PRAYER_METHOD_KEY, method) }
+}
+
+/// Asr rule: 0 = Hanafi, 1 = standard
+pub unsafe fn load_asr_rule() -> u32 {
+    unsafe { read_app_dword(ASR_RULE_KEY) }.unwrap_or(0)
+}
+
+pub unsafe fn save_asr_rule(rule: u32) {
+    unsafe { write_app_dword(ASR_RULE_KEY, rule) }
+}
+
+/// Local feed server: on or off (off by default)
+pub unsafe fn load_feed_server() -> bool {
+    unsafe { read_app_dword(FEED_SERVER_KEY) }.unwrap_or(0) != 0
+}
+
+pub unsafe fn save_feed_server(enabled: bool) {
+    unsafe { write_app_dword(FEED_SERVER_KEY, enabled as u32) }
+}
+
+/// Local feed server port; set the FeedServerPort value to change it
+pub unsafe fn load_feed_server_port() -> u16 {
+    unsafe { read_app_dword(FEED_SERVER_PORT_KEY) }
+        .and_then(|port| u16::try_from(port).ok())
+        .filter(|&port| port != 0)
+        .unwrap_or(DEFAULT_FEED_SERVER_PORT)
+}
+
+/// Picture for the calendar wallpaper; None when the wallpaper is off
+pub unsafe fn load_wallpaper_image() -> Option<String> {
+    unsafe { read_app_string(WALLPAPER_IMAGE_KEY) }.filter(|path| !path.is_empty())
+}
+
+pub unsafe fn save_wallpaper_image(path: &str) {
+    unsafe { write_app_string(WALLPAPER_IMAGE_KEY, path) }
+}
+
+/// Wallpaper calendar corner (index into Corner::ALL, bottom right by default)
+pub unsafe fn load_wallpaper_corner() -> u32 {
+    unsafe { read_app_dword(WALLPAPER_CORNER_KEY) }.unwrap_or(3)
+}
+
+pub unsafe fn save_wallpaper_corner(corner: u32) {
+    unsafe { write_app_dword(WALLPAPER_CORNER_KEY, corner) }
+}
+
+/// Punjika always six rows tall, so it does not change size between months
+pub unsafe fn load_punjika_six_rows() -> bool {
+    unsafe { read_app_dword(PUNJIKA_SIX_ROWS_KEY) }.unwrap_or(0) != 0
+}
+
+pub unsafe fn save_punjika_six_rows(enabled: bool) {
+    unsafe { write_app_dword(PUNJIKA_SIX_ROWS_KEY, enabled as u32) }
+}
+
+/// What the punjika's day cells show under the Bangla day (index into
+/// CellLabel::ALL, nothing by default)
+pub unsafe fn load_punjika_cell_label() -> u32 {
+    unsafe { read_app_dword(PUNJIKA_CELL_LABEL_KEY) }.unwrap_or(0)
+}
+
+pub unsafe fn save_punjika_cell_label(label: u32) {
+    unsafe { write_app_dword(PUNJIKA_CELL_LABEL_KEY, label) }
+}
+
+/// The punjika's first day of the week (Sunday = 0, the default)
+pub unsafe fn load_week_start() -> u32 {
+    unsafe { read_app_dword(WEEK_START_KEY) }
+        .filter(|&weekday| weekday < 7)
+        .unwrap_or(0)
+}
+
+pub unsafe fn save_week_start(weekday: u32) {
+    unsafe { write_app_dword(WEEK_START_KEY, weekday) }
+}
+
+/// The weekend days, one bit per weekday (Sunday = bit 0); Friday and
+/// Saturday by default, as in Bangladesh
+pub unsafe fn load_weekend_days() -> u32 {
+    unsafe { read_app_dword(WEEKEND_DAYS_KEY) }.unwrap_or(DEFAULT_WEEKEND_DAYS) & 0x7F
+}
+
+pub unsafe fn save_weekend_days(days: u32) {
+    unsafe { write_app_dword(WEEKEND_DAYS_KEY, days) }
+}
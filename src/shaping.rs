@@ -0,0 +1,183 @@
+// shaping.rs - Bangla text shaping with the embedded Ekush font
+//
+// GDI shapes text for the windows. The exporters draw glyphs themselves, so
+// they shape with rustybuzz: conjuncts, reordered vowel signs and marks come
+// out as positioned glyphs of the same font.
+
+use rustybuzz::{Face, UnicodeBuffer};
+use std::sync::OnceLock;
+
+// Embed the Ekush font directly into the executable
+pub const EKUSH_FONT_DATA: &[u8] = include_bytes!("../fonts/Ekush-Regular.ttf");
+
+static EKUSH_FACE: OnceLock<Face<'static>> = OnceLock::new();
+
+/// The parsed Ekush font
+pub fn ekush_face() -> &'static Face<'static> {
+    EKUSH_FACE.get_or_init(|| Face::from_slice(EKUSH_FONT_DATA, 0).expect("embedded Ekush font"))
+}
+
+/// A glyph of shaped text; positions are in ems from the start of the
+/// baseline (y up)
+pub struct ShapedGlyph {
+    pub id: u16,
+    pub x: f32,
+    pub y: f32,
+    pub text: String, // the characters this glyph starts, empty for the rest of a cluster
+}
+
+pub struct ShapedText {
+    pub glyphs: Vec<ShapedGlyph>,
+    pub width: f32, // in ems
+}
+
+/// Shape a line of text
+pub fn shape_text(text: &str) -> ShapedText {
+    let face = ekush_face();
+    let units_per_em = face.units_per_em() as f32;
+    let mut buffer = UnicodeBuffer::new();
+    buffer.push_str(text);
+    buffer.guess_segment_properties();
+    let shaped = rustybuzz::shape(face, &[], buffer);
+
+    // Cluster values are byte offsets; a cluster's text runs to the next one
+    let mut starts: Vec<usize> = shaped
+        .glyph_infos()
+        .iter()
+        .map(|info| info.cluster as usize)
+        .collect();
+    starts.sort_unstable();
+    starts.dedup();
+    let cluster_text = |start: usize| {
+        let end = starts
+            .iter()
+            .find(|&&s| s > start)
+            .copied()
+            .unwrap_or(text.len());
+        text.get(start..end).unwrap_or("").to_string()
+    };
+
+    let mut glyphs = Vec::with_capacity(shaped.len());
+    let mut pen = 0;
+    let mut previous_cluster = None;
+    for (info, position) in shaped.glyph_infos().iter().zip(shaped.glyph_positions()) {
+        let cluster = info.cluster as usize;
+        glyphs.push(ShapedGlyph {
+            id: info.glyph_id as u16,
+            x: (pen + position.x_offset) as f32 / units_per_em,
+            y: position.y_offset as f32 / units_per_em,
+            text: if previous_cluster == Some(cluster) {
+                String::new()
+            } else {
+                cluster_text(cluster)
+            },
+        });
+        previous_cluster = Some(cluster);
+        pen += position.x_advance;
+    }
+    ShapedText {
+        glyphs,
+        width: pen as f32 / units_per_em,
+    }
+}
+
+/// The tables of an OpenType font, by tag
+fn font_tables(data: &[u8]) -> Vec<([u8; 4], &[u8])> {
+    let read_u32 = |at: usize| {
+        data.get(at..at + 4)
+            .map_or(0, |b| u32::from_be_bytes([b[0], b[1], b[2], b[3]])) as usize
+    };
+    let count = data
+        .get(4..6)
+        .map_or(0, |b| u16::from_be_bytes([b[0], b[1]]));
+    (0..count as usize)
+        .filter_map(|i| {
+            let record = 12 + i * 16;
+            let tag = data.get(record..record + 4)?.try_into().ok()?;
+            let (offset, length) = (read_u32(record + 8), read_u32(record + 12));
+            Some((tag, data.get(offset..offset + length)?))
+        })
+        .collect()
+}
+
+/// Sum of a table as big-endian u32s, zero-padded
+fn table_checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut bytes = [0; 4];
+        bytes[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(bytes))
+    })
+}
+
+/// A TrueType font from its tables
+fn build_font(mut tables: Vec<([u8; 4], &[u8])>) -> Vec<u8> {
+    tables.sort_by_key(|&(tag, _)| tag);
+    let count = tables.len() as u16;
+    let entry_selector = 15 - count.max(1).leading_zeros() as u16;
+    let search_range = (1u16 << entry_selector) * 16;
+
+    let mut font = Vec::new();
+    font.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+    for value in [
+        count,
+        search_range,
+        entry_selector,
+        count * 16 - search_range,
+    ] {
+        font.extend_from_slice(&value.to_be_bytes());
+    }
+    let mut offset = 12 + tables.len() * 16;
+    let mut head_offset = None;
+    for &(tag, data) in &tables {
+        let checksum = if &tag == b"head" {
+            head_offset = Some(offset);
+            // checkSumAdjustment counts as zero
+            let mut head = data.to_vec();
+            if let Some(field) = head.get_mut(8..12) {
+                field.fill(0);
+            }
+            table_checksum(&head)
+        } else {
+            table_checksum(data)
+        };
+        font.extend_from_slice(&tag);
+        font.extend_from_slice(&checksum.to_be_bytes());
+        font.extend_from_slice(&(offset as u32).to_be_bytes());
+        font.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        offset += data.len().next_multiple_of(4);
+    }
+    for &(_, data) in &tables {
+        font.extend_from_slice(data);
+        font.resize(font.len().next_multiple_of(4), 0);
+    }
+    if let Some(head) = head_offset {
+        font[head + 8..head + 12].fill(0);
+        let adjustment = 0xB1B0_AFBAu32.wrapping_sub(table_checksum(&font));
+        font[head + 8..head + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    font
+}
+
+/// Ekush with only the glyphs needed to draw `texts`, for a browser to shape
+/// them itself: the outlines are subset (glyph IDs are kept) and the layout
+/// tables are carried over, so conjuncts and vowel signs still form
+pub fn web_font_subset<'a>(texts: impl IntoIterator<Item = &'a str>) -> Vec<u8> {
+    let mut glyphs: Vec<u16> = texts
+        .into_iter()
+        .flat_map(|text| shape_text(text).glyphs)
+        .map(|glyph| glyph.id)
+        .collect();
+    glyphs.sort_unstable();
+    glyphs.dedup();
+
+    let Ok(subset) = subsetter::subset(EKUSH_FONT_DATA, 0, subsetter::Profile::pdf(&glyphs)) else {
+        return EKUSH_FONT_DATA.to_vec();
+    };
+    let mut tables = font_tables(&subset);
+    for (tag, data) in font_tables(EKUSH_FONT_DATA) {
+        if matches!(&tag, b"GDEF" | b"GPOS" | b"GSUB") {
+            tables.push((tag, data));
+        }
+    }
+    build_font(tables)
+}
@@ -0,0 +1,112 @@
+// status.rs - Today's date for status bars and shell prompts
+//
+// Used by the `status` command: a plain line, a JSON block for i3bar or
+// waybar, or a tmux fragment. With --watch it keeps running and prints a new
+// line at each local midnight, when the date changes.
+
+use crate::calendar::{BanglaDate, bangla_date_from_days, current_days, seconds_until_next_day};
+use crate::feed_server::json_string;
+use crate::holidays::{HolidayKind, holidays_between};
+use std::io::Write;
+use std::time::Duration;
+
+// tmux colors: the flag green, and red on public holidays
+const TMUX_DATE_STYLE: &str = "#[fg=#006A4E,bold]";
+const TMUX_HOLIDAY_STYLE: &str = "#[fg=#F42A41,bold]";
+
+/// How the status is printed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatusFormat {
+    Line,
+    I3bar,
+    Waybar,
+    Tmux,
+}
+
+impl StatusFormat {
+    pub fn from_name(name: &str) -> Option<StatusFormat> {
+        match name {
+            "line" => Some(StatusFormat::Line),
+            "i3bar" => Some(StatusFormat::I3bar),
+            "waybar" => Some(StatusFormat::Waybar),
+            "tmux" => Some(StatusFormat::Tmux),
+            _ => None,
+        }
+    }
+}
+
+/// ০৬ই পৌষ, ১৪৩২ বঙ্গাব্দ
+fn date_text(date: &BanglaDate) -> String {
+    format!("{} {}", date.format_line1(), date.format_line2())
+}
+
+/// The status of a day, in one line
+fn status_line(days: i64, format: StatusFormat) -> String {
+    let date = bangla_date_from_days(days);
+    let holidays = holidays_between(days, days);
+    let is_holiday = holidays.iter().any(|h| h.kind == HolidayKind::Public);
+    // শনিবার, হেমন্তকাল, then the day's holidays
+    let tooltip = std::iter::once(date.format_line3())
+        .chain(holidays.iter().map(|h| h.name.clone()))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    match format {
+        StatusFormat::Line => date_text(&date),
+        StatusFormat::I3bar => format!(
+            "{{\"name\":\"bangla-calendar\",\"full_text\":{},\"short_text\":{}{}}}",
+            json_string(&date_text(&date)),
+            json_string(date.format_line1().trim_end_matches(',')),
+            if is_holiday { ",\"urgent\":true" } else { "" }
+        ),
+        StatusFormat::Waybar => format!(
+            "{{\"text\":{},\"tooltip\":{},\"class\":\"{}\"}}",
+            json_string(&date_text(&date)),
+            json_string(&tooltip),
+            if is_holiday { "holiday" } else { "normal" }
+        ),
+        StatusFormat::Tmux => format!(
+            "{}{}#[default] {}",
+            if is_holiday {
+                TMUX_HOLIDAY_STYLE
+            } else {
+                TMUX_DATE_STYLE
+            },
+            date.format_line1(),
+            date.format_line2()
+        ),
+    }
+}
+
+/// Print today's status
+pub fn print_status(format: StatusFormat) {
+    let line = status_line(current_days(), format);
+    println!("{}", line);
+}
+
+/// Print today's status, then a new one each time the date changes; returns
+/// only if the output is closed
+pub fn watch_status(format: StatusFormat) -> std::io::Result<()> {
+    let mut out = std::io::stdout();
+    // The i3bar protocol: a header, then an endless array of block arrays
+    if format == StatusFormat::I3bar {
+        writeln!(out, "{{\"version\":1}}\n[")?;
+    }
+    loop {
+        let today = current_days();
+        let line = status_line(today, format);
+        if format == StatusFormat::I3bar {
+            writeln!(out, "[{}],", line)?;
+        } else {
+            writeln!(out, "{}", line)?;
+        }
+        out.flush()?;
+
+        // Sleep until the date changes, looking at least once a minute in
+        // case the clock is changed
+        while current_days() == today {
+            let seconds = seconds_until_next_day().clamp(1, 60);
+            std::thread::sleep(Duration::from_secs(seconds as u64));
+        }
+    }
+}
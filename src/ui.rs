@@ -1,14 +1,82 @@
 // ui.rs - UI drawing, tray icon, and window management
 
-use crate::calendar::get_current_bangla_date;
+use crate::astronomy::jd_now;
+use crate::calendar::{format_clock_time, get_current_bangla_date};
 use crate::constants::*;
 use crate::fonts::{get_font_line1, get_font_line2, get_font_line3};
-use crate::get_flag_icon;
+use crate::locations::current_location;
+use crate::panchang::{format_tithi_line, local_day_minute};
+use crate::prayer::format_next_prayer_line;
+use crate::prohor::format_prohor_line;
+use crate::{WIDGET_LINES, get_flag_icon};
+use std::sync::Mutex;
+use std::sync::atomic::Ordering;
 use windows::{
     Win32::Foundation::*, Win32::Graphics::Gdi::*, Win32::UI::Shell::*,
     Win32::UI::WindowsAndMessaging::*, core::*,
 };
 
+// The optional lines as last computed, for sizing and painting the widget
+static EXTRA_LINES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Optional lines below the date, as enabled from the menu
+fn widget_extra_lines() -> Vec<String> {
+    let flags = WIDGET_LINES.load(Ordering::Relaxed);
+    let location = current_location();
+    let offset = location.utc_offset_minutes;
+    let now = jd_now();
+    let mut lines = Vec::new();
+    if flags & WIDGET_LINE_TITHI != 0 {
+        lines.push(format_tithi_line(now, offset));
+    }
+    if flags & WIDGET_LINE_SUN != 0 {
+        // সূর্যোদয় ৬:৩৮ • সূর্যাস্ত ৫:১৫
+        let (today, _) = local_day_minute(now, offset);
+        let (sunrise, sunset) = location.sun_times(today);
+        let clock = |jd: Option<f64>| {
+            jd.map(|jd| format_clock_time(local_day_minute(jd, offset).1))
+                .unwrap_or_else(|| "—".to_string())
+        };
+        lines.push(format!(
+            "সূর্যোদয় {} • সূর্যাস্ত {}",
+            clock(sunrise),
+            clock(sunset)
+        ));
+    }
+    if flags & WIDGET_LINE_PRAYER != 0 {
+        lines.extend(format_next_prayer_line(now));
+    }
+    if flags & WIDGET_LINE_PROHOR != 0 {
+        lines.push(format_prohor_line(now, location));
+    }
+    lines
+}
+
+/// Compute the optional lines again; the widget is sized and painted with
+/// them until the next refresh
+pub fn refresh_extra_lines() {
+    *EXTRA_LINES.lock().unwrap() = widget_extra_lines();
+}
+
+fn extra_lines() -> Vec<String> {
+    EXTRA_LINES.lock().unwrap().clone()
+}
+
+/// How often the widget refreshes: once a পল (24 seconds) while the prohor
+/// line counts them, otherwise once a minute
+pub fn widget_timer_interval() -> u32 {
+    if WIDGET_LINES.load(Ordering::Relaxed) & WIDGET_LINE_PROHOR != 0 {
+        24_000
+    } else {
+        60_000
+    }
+}
+
+/// Widget height including the optional lines
+pub fn widget_height() -> i32 {
+    WIDGET_HEIGHT + extra_lines().len() as i32 * WIDGET_EXTRA_LINE_HEIGHT
+}
+
 /// Calculate the optimal widget width based on text content
 pub fn calculate_widget_width() -> i32 {
     let bangla_date = get_current_bangla_date();
@@ -24,7 +92,31 @@ pub fn calculate_widget_width() -> i32 {
         .max(line3.chars().count());
     let text_width = (max_chars as i32 * 11).max(120); // Even tighter width
 
-    text_width + (PADDING * 2) // just text + padding on both sides
+    // Optional lines use the smaller line 3 font
+    let extra_width = extra_lines()
+        .iter()
+        .map(|line| line.chars().count() as i32 * 8)
+        .max()
+        .unwrap_or(0);
+
+    text_width.max(extra_width) + (PADDING * 2) // just text + padding on both sides
+}
+
+/// Resize the widget after the optional lines change
+pub fn resize_widget(hwnd: HWND) {
+    refresh_extra_lines();
+    unsafe {
+        let _ = SetWindowPos(
+            hwnd,
+            None,
+            0,
+            0,
+            calculate_widget_width(),
+            widget_height(),
+            SWP_NOMOVE | SWP_NOZORDER | SWP_NOACTIVATE,
+        );
+        let _ = InvalidateRect(Some(hwnd), None, true);
+    }
 }
 
 pub fn create_tray_icon(hwnd: HWND) -> Result<()> {
@@ -270,6 +362,24 @@ pub fn handle_paint(hwnd: HWND) -> LRESULT {
             DT_CENTER | DT_SINGLELINE,
         );
 
+        // Optional lines: তিথি, সূর্যোদয়-সূর্যাস্ত, নামাজ (same font and color as line 3)
+        for (i, line) in extra_lines().iter().enumerate() {
+            let top = 84 + i as i32 * WIDGET_EXTRA_LINE_HEIGHT;
+            let mut line_vec: Vec<u16> = line.encode_utf16().collect();
+            let mut line_rect = RECT {
+                left: text_left,
+                top,
+                right: text_right,
+                bottom: top + WIDGET_EXTRA_LINE_HEIGHT,
+            };
+            DrawTextW(
+                mem_dc,
+                &mut line_vec,
+                &mut line_rect,
+                DT_CENTER | DT_SINGLELINE,
+            );
+        }
+
         SelectObject(mem_dc, old_font);
         // Note: DO NOT delete cached fonts - they're reused
 
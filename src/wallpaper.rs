@@ -0,0 +1,153 @@
+// wallpaper.rs - The desktop wallpaper with today's date and month on it
+//
+// A picture the user chooses is drawn to fill the screen with the widget's
+// lines and the punjika's month in one corner (see image.rs), saved as
+// %APPDATA%\BanglaCalendar\wallpaper.png and set as the wallpaper. While it
+// is on, it is drawn again when the date or the screen resolution changes,
+// for those who would rather not keep the widget on screen. Outside Windows
+// the command line can still draw one to a file.
+
+use crate::image::{Corner, wallpaper_image};
+use crate::paths::user_data_dir;
+use std::path::{Path, PathBuf};
+#[cfg(windows)]
+use {
+    crate::calendar::get_current_bangla_date,
+    crate::export::file_dialog,
+    crate::registry::{load_wallpaper_image, save_wallpaper_image},
+    crate::{WALLPAPER_CORNER, WALLPAPER_ENABLED},
+    std::sync::atomic::{AtomicI32, Ordering},
+    windows::{
+        Win32::Foundation::*, Win32::Graphics::Gdi::*, Win32::UI::WindowsAndMessaging::*, core::*,
+    },
+};
+
+// The date the wallpaper was last drawn for (see date_key)
+#[cfg(windows)]
+static WALLPAPER_DATE: AtomicI32 = AtomicI32::new(0);
+
+/// A number that changes with the Bangla date
+#[cfg(windows)]
+fn date_key() -> i32 {
+    let date = get_current_bangla_date();
+    (date.year * 12 + date.month) * 32 + date.day
+}
+
+/// Where the tray's wallpaper is written
+pub fn wallpaper_path() -> Option<PathBuf> {
+    user_data_dir().map(|dir| dir.join("wallpaper.png"))
+}
+
+/// The primary screen's resolution, in physical pixels
+#[cfg(windows)]
+pub fn screen_size() -> (u32, u32) {
+    let mut mode = DEVMODEW {
+        dmSize: std::mem::size_of::<DEVMODEW>() as u16,
+        ..Default::default()
+    };
+    unsafe {
+        if EnumDisplaySettingsW(PCWSTR::null(), ENUM_CURRENT_SETTINGS, &mut mode).as_bool()
+            && mode.dmPelsWidth > 0
+            && mode.dmPelsHeight > 0
+        {
+            return (mode.dmPelsWidth, mode.dmPelsHeight);
+        }
+        (
+            GetSystemMetrics(SM_CXSCREEN).max(1) as u32,
+            GetSystemMetrics(SM_CYSCREEN).max(1) as u32,
+        )
+    }
+}
+
+/// Make an image file the desktop wallpaper
+#[cfg(windows)]
+pub fn set_wallpaper(path: &Path) -> std::result::Result<(), String> {
+    let mut path_wide: Vec<u16> = path
+        .to_string_lossy()
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    unsafe {
+        SystemParametersInfoW(
+            SPI_SETDESKWALLPAPER,
+            0,
+            Some(path_wide.as_mut_ptr() as *mut std::ffi::c_void),
+            SPIF_UPDATEINIFILE | SPIF_SENDCHANGE,
+        )
+    }
+    .map_err(|e| e.to_string())
+}
+
+/// Draw `source` with the calendar for the screen and write it to `output`
+pub fn write_wallpaper(
+    source: &Path,
+    output: &Path,
+    size: (u32, u32),
+    corner: Corner,
+) -> std::result::Result<(), String> {
+    let picture = std::fs::read(source).map_err(|e| format!("{}: {}", source.display(), e))?;
+    let wallpaper = wallpaper_image(&picture, size.0, size.1, corner)
+        .map_err(|e| format!("{}: {}", source.display(), e))?;
+    if let Some(dir) = output.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    std::fs::write(output, wallpaper).map_err(|e| format!("{}: {}", output.display(), e))
+}
+
+/// Draw the tray's wallpaper again and set it, in the background; `force`
+/// draws it even if the date has not changed. Failures are reported only
+/// when the user asked for the change.
+#[cfg(windows)]
+pub fn refresh_wallpaper(force: bool) {
+    if !WALLPAPER_ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let key = date_key();
+    if WALLPAPER_DATE.swap(key, Ordering::Relaxed) == key && !force {
+        return;
+    }
+    let (Some(source), Some(output)) = (unsafe { load_wallpaper_image() }, wallpaper_path()) else {
+        return;
+    };
+    let corner = Corner::from_index(WALLPAPER_CORNER.load(Ordering::Relaxed));
+    std::thread::spawn(move || {
+        let result = write_wallpaper(Path::new(&source), &output, screen_size(), corner)
+            .and_then(|()| set_wallpaper(&output));
+        if let Err(e) = result
+            && force
+        {
+            unsafe {
+                let _ = MessageBoxW(
+                    None,
+                    &HSTRING::from(e),
+                    w!("ওয়ালপেপার বানানো যায়নি"),
+                    MB_OK | MB_ICONWARNING,
+                );
+            }
+        }
+    });
+}
+
+/// Ask for a picture and turn the wallpaper on with it
+#[cfg(windows)]
+pub fn choose_wallpaper_image(hwnd: HWND) {
+    let Some(path) = file_dialog(
+        hwnd,
+        "",
+        ("ছবি (*.jpg, *.png)", "*.jpg;*.jpeg;*.png"),
+        "jpg",
+        false,
+    ) else {
+        return;
+    };
+    unsafe { save_wallpaper_image(&path.to_string_lossy()) };
+    WALLPAPER_ENABLED.store(true, Ordering::Relaxed);
+    refresh_wallpaper(true);
+}
+
+/// Stop drawing the wallpaper; the last one stays on the desktop
+#[cfg(windows)]
+pub fn disable_wallpaper() {
+    unsafe { save_wallpaper_image("") };
+    WALLPAPER_ENABLED.store(false, Ordering::Relaxed);
+}